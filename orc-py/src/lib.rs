@@ -0,0 +1,125 @@
+//! PyO3 bindings onto [`orc`]'s `OrcClient` — `fetch`, `stream`, and
+//! `check`, for a Python script that wants `orc`'s onion-only-traffic
+//! guarantee (every connection through the local SOCKS5 proxy, no local
+//! DNS) without shelling out to the `orc` binary and parsing its output.
+//!
+//! There's no async runtime on the Rust side — `orc` is synchronous
+//! top to bottom, see its own crate doc comment — so these functions
+//! block the calling thread the same way they would from Rust. Each one
+//! releases the GIL for the duration of the blocking call (via
+//! [`Python::detach`]), so a script awaiting it from
+//! `asyncio.run_in_executor` doesn't stall the rest of the event loop;
+//! that's as "async-compatible" as a blocking network call can honestly
+//! be without this crate inventing an async runtime it otherwise has no
+//! use for.
+//!
+//! A separate crate (rather than a feature on `orc` itself, like its own
+//! `capi` feature) because `pyo3`'s `extension-module`
+//! feature turns off linking against `libpython`, which would make `orc`
+//! the binary crate unusable if it were on by default, and because a
+//! `cdylib` built for Python needs to be named after the module
+//! (`orc_py`), not the crate (`orc`).
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use orc::error::OrcError;
+use orc::net::tcp::TorStream;
+use orc::OrcClient;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+fn to_py_err(err: OrcError) -> PyErr {
+    match err.kind() {
+        orc::error::ErrorKind::InvalidArgument => PyValueError::new_err(err.to_string()),
+        _ => PyOSError::new_err(err.to_string()),
+    }
+}
+
+fn parse_proxy(proxy_addr: &str) -> PyResult<SocketAddr> {
+    proxy_addr.parse().map_err(|_| PyValueError::new_err(format!("`{proxy_addr}` is not a valid SOCKS proxy address")))
+}
+
+/// `orc_py.fetch(proxy_addr, method, url)` — sends one `http://` request
+/// through the proxy at `proxy_addr` and returns the response body as
+/// `bytes`. Headers and status aren't exposed here; a caller that needs
+/// them should reach for [`orc::client::OrcClient::request`] from Rust,
+/// or `orc rpc`/`orc feed` from the shell.
+#[pyfunction]
+fn fetch(py: Python<'_>, proxy_addr: &str, method: &str, url: &str) -> PyResult<Py<PyBytes>> {
+    let proxy_addr = parse_proxy(proxy_addr)?;
+    let body = py
+        .detach(|| {
+            let client = OrcClient::new(proxy_addr);
+            client.request(method, url).send().map(|response| response.body)
+        })
+        .map_err(to_py_err)?;
+    Ok(PyBytes::new(py, &body).into())
+}
+
+/// `orc_py.check(host)` — raises `ValueError` if `host` would be refused
+/// by [`orc::security::check_host`] (a malformed onion address, or the
+/// configured allow/deny policy), without opening a connection. The same
+/// check [`fetch`] and [`TorStream`] already run on every call; exposed
+/// on its own so a script can validate a scraped list of onion addresses
+/// up front.
+#[pyfunction]
+fn check(py: Python<'_>, host: &str) -> PyResult<()> {
+    py.detach(|| orc::security::check_host(host)).map_err(to_py_err)
+}
+
+/// A connected SOCKS5 stream, returned by [`stream`]. Not a context
+/// manager on its own — call [`PyTorStream::close`] explicitly, or let
+/// it drop; there's no `__del__`-time GIL to release blocking I/O
+/// through anyway.
+#[pyclass(name = "TorStream", unsendable)]
+struct PyTorStream(Option<TorStream>);
+
+#[pymethods]
+impl PyTorStream {
+    /// Reads up to `size` bytes; an empty `bytes` means the peer closed
+    /// the connection.
+    fn read(&mut self, py: Python<'_>, size: usize) -> PyResult<Py<PyBytes>> {
+        let stream = self.live_mut()?;
+        let mut buf = vec![0u8; size];
+        let read = py.detach(|| stream.read(&mut buf)).map_err(|err| to_py_err(OrcError::from(err)))?;
+        Ok(PyBytes::new(py, &buf[..read]).into())
+    }
+
+    /// Writes `data` in full.
+    fn write(&mut self, py: Python<'_>, data: &[u8]) -> PyResult<()> {
+        let stream = self.live_mut()?;
+        py.detach(|| stream.write_all(data)).map_err(|err| to_py_err(OrcError::from(err)))
+    }
+
+    /// Drops the underlying connection; further calls raise `OSError`.
+    fn close(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl PyTorStream {
+    fn live_mut(&mut self) -> PyResult<&mut TorStream> {
+        self.0.as_mut().ok_or_else(|| PyOSError::new_err("stream is closed"))
+    }
+}
+
+/// `orc_py.stream(proxy_addr, host, port)` — opens a SOCKS5 CONNECT
+/// stream through the proxy at `proxy_addr` to `host:port` and returns a
+/// [`PyTorStream`] for reading and writing it.
+#[pyfunction]
+fn stream(py: Python<'_>, proxy_addr: &str, host: &str, port: u16) -> PyResult<PyTorStream> {
+    let proxy_addr = parse_proxy(proxy_addr)?;
+    let stream = py.detach(|| OrcClient::new(proxy_addr).connect(host, port)).map_err(to_py_err)?;
+    Ok(PyTorStream(Some(stream)))
+}
+
+#[pymodule]
+fn orc_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fetch, m)?)?;
+    m.add_function(wrap_pyfunction!(check, m)?)?;
+    m.add_function(wrap_pyfunction!(stream, m)?)?;
+    m.add_class::<PyTorStream>()?;
+    Ok(())
+}
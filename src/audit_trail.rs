@@ -0,0 +1,350 @@
+//! A bounded in-memory ring buffer of the commands this process has run —
+//! command name, target (where one makes sense), outcome, and how long it
+//! took — for `orc trail show` to print and `orc trail export` to turn
+//! into an encrypted file on explicit request. Nothing here ever touches
+//! disk on its own: that would break `orc`'s no-logs-by-default design
+//! just as surely as a real log file would.
+//!
+//! Recorded once per [`crate::cli::dispatch`] call, so every command run
+//! from a shell or from `orc repl`/`orc session` shows up the same way.
+//!
+//! [`json_mode`] governs the global `--json` flag: when it's on,
+//! [`crate::cli::dispatch`] also prints the entry it just recorded to
+//! stdout as a single JSON object, right after the command returns. That
+//! object is this module's existing entry shape — command, target,
+//! `ok`/`error`, and timing — not a bespoke schema per command; giving
+//! every command (`mail`, `matrix`, `rpc`, ...) its own rich JSON payload
+//! would mean restructuring each one's output individually, a much
+//! larger change than one flag, so `--json` only guarantees the part
+//! every command already gets for free by going through [`dispatch`]:
+//! a reliable, scriptable completion record with no prose to parse
+//! around. A command's own existing stdout output is unaffected either
+//! way.
+//!
+//! [`dispatch`]: crate::cli::dispatch
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::output;
+use crate::secret::SensitiveString;
+
+/// How many entries the ring buffer keeps before the oldest is dropped —
+/// enough for a typical debugging session without growing without bound
+/// over a long-running `orc repl`.
+const CAPACITY: usize = 500;
+const SALT_LEN: usize = 16;
+const TAG_LEN: usize = 32;
+const HMAC_BLOCK_SIZE: usize = 64;
+/// Same cost as [`crate::config::encrypt`]'s key stretching — see that
+/// constant's doc comment for why this crate doesn't reach for a real
+/// KDF like Argon2.
+const STRETCH_ROUNDS: u32 = 100_000;
+
+#[derive(Clone)]
+pub struct AuditEntry {
+    pub command: &'static str,
+    pub target: Option<String>,
+    pub outcome: Outcome,
+    pub started_unix_secs: u64,
+    pub duration: Duration,
+}
+
+#[derive(Clone)]
+pub enum Outcome {
+    Ok,
+    Err(String),
+}
+
+/// The ring buffer itself, kept free of any process-global state so
+/// tests can each use their own instance instead of racing on a shared
+/// one. [`record`]/[`snapshot`] below are the process-wide singleton
+/// every real caller uses.
+struct Trail {
+    entries: Vec<AuditEntry>,
+    capacity: usize,
+}
+
+impl Trail {
+    fn new(capacity: usize) -> Self {
+        Trail { entries: Vec::with_capacity(capacity), capacity }
+    }
+
+    fn record(&mut self, entry: AuditEntry) {
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+}
+
+fn global() -> &'static Mutex<Trail> {
+    static TRAIL: OnceLock<Mutex<Trail>> = OnceLock::new();
+    TRAIL.get_or_init(|| Mutex::new(Trail::new(CAPACITY)))
+}
+
+/// Appends an entry to the process-wide trail, dropping the oldest one
+/// first if it's already full.
+pub fn record(command: &'static str, target: Option<String>, outcome: &Result<()>, started: SystemTime, duration: Duration) {
+    global().lock().unwrap().record(AuditEntry {
+        command,
+        target,
+        outcome: match outcome {
+            Ok(()) => Outcome::Ok,
+            Err(err) => Outcome::Err(err.to_string()),
+        },
+        started_unix_secs: started.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        duration,
+    });
+}
+
+/// A snapshot of the process-wide trail as it stands right now, oldest
+/// first.
+pub fn snapshot() -> Vec<AuditEntry> {
+    global().lock().unwrap().entries.clone()
+}
+
+/// Whether `--json` was given. Off by default; set once from `main` by
+/// that flag, the same pre-clap-flag-plus-ambient-setter pattern
+/// [`crate::redact::set_enabled`] uses, since `dispatch` needs to consult
+/// this after every single command, not just one with a flag of its own.
+static JSON_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether [`crate::cli::dispatch`] should also print a command's
+/// outcome as JSON once it returns.
+pub fn set_json_mode(enabled: bool) {
+    JSON_MODE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn json_mode() -> bool {
+    JSON_MODE.load(Ordering::Relaxed)
+}
+
+/// The entry [`record`] just appended, rendered the same way [`render`]
+/// renders one line of the trail — what `--json` prints to stdout.
+/// `None` only if nothing has been recorded yet, which shouldn't happen
+/// by the time [`crate::cli::dispatch`] calls this.
+pub fn last_entry_json() -> Option<String> {
+    global().lock().unwrap().entries.last().map(entry_to_json).map(|v| v.to_string())
+}
+
+/// Renders `entries` as one JSON object per line, the same shape
+/// [`export`] encrypts.
+fn render_entries(entries: &[AuditEntry]) -> String {
+    entries.iter().map(entry_to_json).map(|v| v.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+/// Renders the current process-wide trail, for `orc trail show`.
+pub fn render() -> String {
+    render_entries(&snapshot())
+}
+
+/// Encrypts `entries` under `passphrase` and returns the bytes to write
+/// out.
+fn export_entries(entries: &[AuditEntry], passphrase: &SensitiveString) -> Vec<u8> {
+    let plaintext = render_entries(entries);
+    let salt = fresh_salt();
+    let key = derive_key(passphrase.as_str(), &salt);
+    let keystream = keystream(&key, plaintext.len());
+    let mut ciphertext: Vec<u8> = plaintext.bytes().zip(keystream).map(|(b, k)| b ^ k).collect();
+    let tag = hmac_sha256(&key, &ciphertext);
+    ciphertext.extend_from_slice(&tag);
+
+    Value::Object(vec![
+        ("orc_audit_trail".to_string(), Value::Bool(true)),
+        ("salt".to_string(), Value::String(output::hex_string(&salt))),
+        ("ciphertext".to_string(), Value::String(output::hex_string(&ciphertext))),
+    ])
+    .to_string()
+    .into_bytes()
+}
+
+/// Encrypts the current process-wide trail under `passphrase` — never
+/// called unless a user explicitly asks for an export, per this module's
+/// doc comment.
+pub fn export(passphrase: &SensitiveString) -> Vec<u8> {
+    export_entries(&snapshot(), passphrase)
+}
+
+fn entry_to_json(entry: &AuditEntry) -> Value {
+    let (ok, error) = match &entry.outcome {
+        Outcome::Ok => (true, None),
+        Outcome::Err(message) => (false, Some(Value::String(message.clone()))),
+    };
+    Value::Object(vec![
+        ("command".to_string(), Value::String(entry.command.to_string())),
+        ("target".to_string(), entry.target.clone().map(Value::String).unwrap_or(Value::Null)),
+        ("ok".to_string(), Value::Bool(ok)),
+        ("error".to_string(), error.unwrap_or(Value::Null)),
+        ("started_unix_secs".to_string(), Value::Number(entry.started_unix_secs as f64)),
+        ("duration_ms".to_string(), Value::Number(entry.duration.as_millis() as f64)),
+    ])
+}
+
+/// A fresh salt from the OS's CSPRNG via [`getrandom`] — see
+/// [`crate::config`]'s identically-shaped `fresh_salt`.
+#[cfg(feature = "serve")]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).expect("the OS's CSPRNG should not fail");
+    salt
+}
+
+/// Falls back to a salt built from wall-clock time and the process id
+/// when built without `getrandom` (`--no-default-features` without
+/// `serve`) — see [`crate::config`]'s identically-shaped fallback
+/// `fresh_salt`.
+#[cfg(not(feature = "serve"))]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_be_bytes());
+    hasher.update(std::process::id().to_be_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&digest[..SALT_LEN]);
+    salt
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-audit-trail-v1");
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    stretch_key(hasher.finalize().into())
+}
+
+fn stretch_key(key: [u8; 32]) -> [u8; 32] {
+    let mut current = key;
+    for _ in 0..STRETCH_ROUNDS {
+        current = Sha256::digest(current).into();
+    }
+    current
+}
+
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; HMAC_BLOCK_SIZE];
+    key_block[..key.len()].copy_from_slice(key);
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(message);
+    let inner_digest = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_digest);
+    outer.finalize().into()
+}
+
+/// Reverses [`export`], returning the decrypted NDJSON text. Used by
+/// `orc trail export --verify`, which re-reads an export right after
+/// writing it so a bad passphrase is caught immediately rather than on
+/// the next attempt to read it back.
+pub fn decrypt(data: &[u8], passphrase: &SensitiveString) -> Result<String> {
+    let text = std::str::from_utf8(data).map_err(|_| OrcError::InvalidArgument("not an orc audit trail export".into()))?;
+    let parsed = json::parse(text).map_err(|_| OrcError::InvalidArgument("not an orc audit trail export".into()))?;
+    let salt_hex = parsed.get("salt").and_then(Value::as_str).ok_or_else(|| OrcError::InvalidArgument("export has no salt".into()))?;
+    let ciphertext_hex = parsed.get("ciphertext").and_then(Value::as_str).ok_or_else(|| OrcError::InvalidArgument("export has no ciphertext".into()))?;
+    let salt = output::decode_hex(salt_hex)?;
+    let mut ciphertext = output::decode_hex(ciphertext_hex)?;
+    if ciphertext.len() < TAG_LEN {
+        return Err(OrcError::InvalidArgument("export is truncated".into()));
+    }
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+
+    let key = derive_key(passphrase.as_str(), &salt);
+    if !crate::constant_time::eq(&hmac_sha256(&key, &ciphertext), &tag) {
+        return Err(OrcError::InvalidArgument("wrong passphrase or corrupted export".into()));
+    }
+
+    let keystream = keystream(&key, ciphertext.len());
+    let plaintext: Vec<u8> = ciphertext.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+    String::from_utf8(plaintext).map_err(|_| OrcError::InvalidArgument("decrypted export is not valid UTF-8".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: &'static str, target: Option<&str>, outcome: Result<()>) -> AuditEntry {
+        AuditEntry {
+            command,
+            target: target.map(str::to_string),
+            outcome: match outcome {
+                Ok(()) => Outcome::Ok,
+                Err(err) => Outcome::Err(err.to_string()),
+            },
+            started_unix_secs: 0,
+            duration: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn trail_record_drops_the_oldest_entry_once_full() {
+        let mut trail = Trail::new(2);
+        trail.record(entry("gemini", None, Ok(())));
+        trail.record(entry("resolve", None, Ok(())));
+        trail.record(entry("nc", None, Ok(())));
+        assert_eq!(trail.entries.len(), 2);
+        assert_eq!(trail.entries[0].command, "resolve");
+        assert_eq!(trail.entries[1].command, "nc");
+    }
+
+    #[test]
+    fn render_entries_is_empty_for_an_empty_trail() {
+        assert_eq!(render_entries(&[]), "");
+    }
+
+    #[test]
+    fn render_entries_includes_the_target_and_error() {
+        let entries = [entry("resolve", Some("example.onion"), Err(OrcError::InvalidArgument("boom".into())))];
+        let rendered = render_entries(&entries);
+        assert!(rendered.contains("\"resolve\""));
+        assert!(rendered.contains("\"example.onion\""));
+        assert!(rendered.contains("boom"));
+    }
+
+    #[test]
+    fn export_entries_round_trips_through_decrypt() {
+        let entries = [entry("bench", Some("abc.onion:80"), Ok(()))];
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let exported = export_entries(&entries, &passphrase);
+        let plaintext = decrypt(&exported, &passphrase).unwrap();
+        assert!(plaintext.contains("\"bench\""));
+    }
+
+    #[test]
+    fn decrypt_rejects_the_wrong_passphrase() {
+        let entries = [entry("nc", None, Ok(()))];
+        let exported = export_entries(&entries, &SensitiveString::new("right".to_string()));
+        let err = decrypt(&exported, &SensitiveString::new("wrong".to_string())).unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase"));
+    }
+}
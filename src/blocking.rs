@@ -0,0 +1,17 @@
+//! A `blocking` namespace for an embedder who went looking for one next
+//! to an async client and a runtime to drive it.
+//!
+//! There isn't one to wrap: as [`crate::client`] says, nothing in this
+//! crate is async in the first place — [`OrcClient`] already blocks the
+//! calling thread the same way every protocol module under [`crate::net`]
+//! does. This module is just re-exports of the pieces an embedder coming
+//! from an async-first crate would expect to find under this name, so
+//! `orc::blocking::Client` resolves instead of a confusing "no such
+//! module" — there's no internal runtime here, and nothing to spawn onto
+//! one.
+//!
+//! [`OrcClient`]: crate::OrcClient
+
+pub use crate::client::{OrcClient as Client, OrcRequest as Request};
+pub use crate::net::http::HttpResponse;
+pub use crate::net::tcp::TorStream as Stream;
@@ -0,0 +1,74 @@
+//! A cooperative cancellation flag threaded through
+//! [`crate::net::tcp::ConnectOptions`] so a long-running transfer
+//! started with [`crate::net::http::send`] or a proxy-connect retry loop
+//! in [`crate::net::tcp`] can be stopped cleanly from another thread —
+//! with whatever partial response buffer it had read so far zeroized
+//! before the call returns — instead of a caller's only option being to
+//! kill the whole process.
+//!
+//! There's no async runtime anywhere in this crate, so there's nothing
+//! to cancel a `Future` with; [`CancellationToken`] is checked between
+//! chunks of blocking work the same way [`crate::killswitch`]'s typed
+//! phrase is checked between lines of REPL input — cooperatively, at
+//! points the cancellable code already visits, not by interrupting it
+//! mid-syscall.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::{OrcError, Result};
+
+/// A cancel button shared between threads: clone it to hand a caller
+/// the ability to cancel while keeping your own copy to check from
+/// wherever is doing the blocking work — [`Clone`] shares the same
+/// underlying flag, the same way [`crate::zeroize::Registration`]'s
+/// registry is shared state rather than copied per clone.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A fresh, not-yet-cancelled token.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Marks this token (and every clone of it) cancelled.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`Self::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// [`OrcError::Cancelled`] if this token has been cancelled, else
+    /// `Ok(())` — the check a cancellable loop makes between chunks of
+    /// work.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(OrcError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_fresh_token_is_not_cancelled() {
+        assert!(CancellationToken::new().check().is_ok());
+    }
+
+    #[test]
+    fn cancelling_is_visible_through_a_clone() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(matches!(clone.check(), Err(OrcError::Cancelled)));
+    }
+}
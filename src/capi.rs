@@ -0,0 +1,227 @@
+//! A small C ABI over [`OrcClient`], built alongside the Rust library
+//! whenever the `capi` feature is enabled and this crate is built as the
+//! `cdylib` declared in `Cargo.toml`'s `[lib]` section — so a non-Rust
+//! application can get `orc`'s Tor-only-traffic guarantee by linking
+//! against it directly, rather than shelling out to the `orc` binary and
+//! scraping its output the way [`OrcClient`]'s own doc comment already
+//! describes for a Rust embedder.
+//!
+//! Every function below returns an `i32` status: `0` on success, or
+//! [`OrcError::exit_code`] otherwise — the same `sysexits.h` numbering
+//! [`crate::run_cli`] itself is meant to report, so a caller on either
+//! side of the FFI boundary reads the same table. A Rust panic inside one
+//! of these functions is caught at the boundary and reported as
+//! `EX_SOFTWARE` (70) rather than unwinding into the caller's own
+//! language runtime, which is undefined behavior.
+//!
+//! Handles ([`OrcClientHandle`], [`OrcStreamHandle`]) are opaque
+//! heap-allocated boxes handed across the boundary as raw pointers —
+//! [`orc_free_client`] and [`orc_stream_close`] are the only valid way to
+//! free them; nothing on the Rust side ever frees one on a caller's
+//! behalf.
+
+use std::ffi::{c_char, CStr};
+use std::io::{Read, Write};
+use std::os::raw::c_int;
+
+use crate::error::OrcError;
+use crate::OrcClient;
+use crate::net::tcp::TorStream;
+
+const EX_SOFTWARE: c_int = 70;
+
+/// Opaque handle returned by [`orc_init`], freed by [`orc_free_client`].
+pub struct OrcClientHandle(OrcClient);
+
+/// Opaque handle returned by [`orc_stream`], freed by [`orc_stream_close`].
+pub struct OrcStreamHandle(TorStream);
+
+/// Runs `body`, catching a panic the same way every function below does,
+/// and converts its `Result` into the status code they all return.
+fn report<F>(body: F) -> c_int
+where
+    F: FnOnce() -> crate::error::Result<()>,
+{
+    // `AssertUnwindSafe` rather than requiring callers' closures to be
+    // `UnwindSafe` themselves: every closure below only reads through a
+    // raw pointer or writes an out-param on the success path, neither of
+    // which leaves anything in a state a panic could observe half-done.
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)) {
+        Ok(Ok(())) => 0,
+        Ok(Err(err)) => err.exit_code(),
+        Err(_) => EX_SOFTWARE,
+    }
+}
+
+/// Borrows `ptr` as a `&str`, refusing a null pointer or invalid UTF-8
+/// the same way [`crate::cli`] refuses a bad argument from the shell.
+unsafe fn borrow_str<'a>(ptr: *const c_char) -> crate::error::Result<&'a str> {
+    if ptr.is_null() {
+        return Err(OrcError::InvalidArgument("unexpected null pointer".into()));
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|_| OrcError::InvalidArgument("argument was not valid UTF-8".into()))
+}
+
+/// Parses `proxy_addr` (`"127.0.0.1:9050"`-style) and writes a new
+/// [`OrcClient`] handle to `*out_client` — default timeouts, no isolation
+/// credentials, the same starting point [`OrcClient::new`] gives a Rust
+/// caller.
+///
+/// # Safety
+/// `proxy_addr` must be a valid, NUL-terminated C string; `out_client`
+/// must point to valid, writable memory for one pointer.
+#[no_mangle]
+pub unsafe extern "C" fn orc_init(proxy_addr: *const c_char, out_client: *mut *mut OrcClientHandle) -> c_int {
+    report(|| {
+        let addr_str = borrow_str(proxy_addr)?;
+        let addr = addr_str
+            .parse()
+            .map_err(|_| OrcError::InvalidArgument(format!("`{addr_str}` is not a valid SOCKS proxy address")))?;
+        let handle = Box::new(OrcClientHandle(OrcClient::new(addr)));
+        *out_client = Box::into_raw(handle);
+        Ok(())
+    })
+}
+
+/// Frees a handle returned by [`orc_init`]. A null `client` is a no-op.
+///
+/// # Safety
+/// `client` must be a pointer [`orc_init`] returned, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn orc_free_client(client: *mut OrcClientHandle) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+/// Sends one `http://` request through `client` and writes the response
+/// body to a newly allocated buffer at `*out_body`/`*out_len` — headers
+/// and status aren't exposed at this layer; a caller that needs them
+/// should use [`OrcClient::request`] from Rust directly. Freed with
+/// [`orc_free_buf`].
+///
+/// # Safety
+/// `client` must be a live [`orc_init`] handle; `method` and `url` must
+/// be valid, NUL-terminated C strings; `out_body` and `out_len` must
+/// point to valid, writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn orc_fetch(
+    client: *const OrcClientHandle,
+    method: *const c_char,
+    url: *const c_char,
+    out_body: *mut *mut u8,
+    out_len: *mut usize,
+) -> c_int {
+    report(|| {
+        if client.is_null() {
+            return Err(OrcError::InvalidArgument("unexpected null client handle".into()));
+        }
+        let method = borrow_str(method)?;
+        let url = borrow_str(url)?;
+        let response = (*client).0.request(method, url).send()?;
+        let mut body = response.body.into_boxed_slice();
+        *out_len = body.len();
+        *out_body = body.as_mut_ptr();
+        std::mem::forget(body);
+        Ok(())
+    })
+}
+
+/// Frees a buffer [`orc_fetch`] allocated. A null `buf` is a no-op.
+///
+/// # Safety
+/// `buf`/`len` must be exactly the pointer and length [`orc_fetch`] wrote
+/// to `*out_body`/`*out_len`, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn orc_free_buf(buf: *mut u8, len: usize) {
+    if !buf.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(buf, len)));
+    }
+}
+
+/// Opens a SOCKS5 CONNECT stream through `client` to `host:port` and
+/// writes a new handle to `*out_stream` — the same [`crate::net::tcp::TorStream`]
+/// an embedding Rust caller gets from [`OrcClient::connect`], minus the
+/// `Read`/`Write` traits a C caller can't call directly; see
+/// [`orc_stream_read`]/[`orc_stream_write`]/[`orc_stream_close`].
+///
+/// # Safety
+/// `client` must be a live [`orc_init`] handle; `host` must be a valid,
+/// NUL-terminated C string; `out_stream` must point to valid, writable
+/// memory for one pointer.
+#[no_mangle]
+pub unsafe extern "C" fn orc_stream(
+    client: *const OrcClientHandle,
+    host: *const c_char,
+    port: u16,
+    out_stream: *mut *mut OrcStreamHandle,
+) -> c_int {
+    report(|| {
+        if client.is_null() {
+            return Err(OrcError::InvalidArgument("unexpected null client handle".into()));
+        }
+        let host = borrow_str(host)?;
+        let stream = (*client).0.connect(host, port)?;
+        *out_stream = Box::into_raw(Box::new(OrcStreamHandle(stream)));
+        Ok(())
+    })
+}
+
+/// Reads up to `len` bytes from `stream` into `buf`, writing how many
+/// were actually read to `*out_read` (`0` means the peer closed the
+/// connection, same as [`std::io::Read::read`]).
+///
+/// # Safety
+/// `stream` must be a live [`orc_stream`] handle; `buf` must point to at
+/// least `len` writable bytes; `out_read` must point to valid, writable
+/// memory.
+#[no_mangle]
+pub unsafe extern "C" fn orc_stream_read(stream: *mut OrcStreamHandle, buf: *mut u8, len: usize, out_read: *mut usize) -> c_int {
+    report(|| {
+        if stream.is_null() {
+            return Err(OrcError::InvalidArgument("unexpected null stream handle".into()));
+        }
+        let slice = std::slice::from_raw_parts_mut(buf, len);
+        *out_read = (*stream).0.read(slice)?;
+        Ok(())
+    })
+}
+
+/// Writes `len` bytes from `buf` to `stream`.
+///
+/// # Safety
+/// `stream` must be a live [`orc_stream`] handle; `buf` must point to at
+/// least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn orc_stream_write(stream: *mut OrcStreamHandle, buf: *const u8, len: usize) -> c_int {
+    report(|| {
+        if stream.is_null() {
+            return Err(OrcError::InvalidArgument("unexpected null stream handle".into()));
+        }
+        let slice = std::slice::from_raw_parts(buf, len);
+        (*stream).0.write_all(slice)?;
+        Ok(())
+    })
+}
+
+/// Frees a handle returned by [`orc_stream`]. A null `stream` is a no-op.
+///
+/// # Safety
+/// `stream` must be a pointer [`orc_stream`] returned, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn orc_stream_close(stream: *mut OrcStreamHandle) {
+    if !stream.is_null() {
+        drop(Box::from_raw(stream));
+    }
+}
+
+/// Best-effort-zeroizes every [`crate::secret`] buffer still registered
+/// in this process via [`crate::zeroize::zeroize_all`] — not
+/// [`crate::zeroize::emergency_exit`], which calls `std::process::exit`
+/// and would take an embedding application down with it; a library
+/// linked into someone else's process doesn't get to decide that it
+/// exits, only that its own secrets are gone.
+#[no_mangle]
+pub extern "C" fn orc_panic_wipe() {
+    crate::zeroize::zeroize_all();
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime};
+
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::commands::audit::AuditArgs;
+use crate::commands::bench::BenchArgs;
+#[cfg(feature = "tui")]
+use crate::commands::browse::BrowseArgs;
+use crate::commands::chat::ChatArgs;
+use crate::commands::config::ConfigArgs;
+use crate::commands::decrypt::DecryptArgs;
+use crate::commands::electrum::ElectrumArgs;
+#[cfg(feature = "http")]
+use crate::commands::feed::FeedArgs;
+#[cfg(feature = "http")]
+use crate::commands::fetch::FetchArgs;
+#[cfg(feature = "tcp")]
+use crate::commands::forward::ForwardArgs;
+use crate::commands::gemini::GeminiArgs;
+use crate::commands::irc::IrcArgs;
+use crate::commands::keys::KeysArgs;
+use crate::commands::mail::MailArgs;
+use crate::commands::matrix::MatrixArgs;
+use crate::commands::mqtt::MqttArgs;
+#[cfg(feature = "tcp")]
+use crate::commands::nc::NcArgs;
+use crate::commands::nntp::NntpArgs;
+use crate::commands::oshare::OshareArgs;
+use crate::commands::panic::PanicArgs;
+use crate::commands::repl::ReplArgs;
+use crate::commands::resolve::ResolveArgs;
+#[cfg(feature = "http")]
+use crate::commands::rpc::RpcArgs;
+#[cfg(all(feature = "serve", feature = "control-port"))]
+use crate::commands::serve::ServeArgs;
+use crate::commands::session::SessionArgs;
+#[cfg(feature = "tcp")]
+use crate::commands::stream::StreamArgs;
+use crate::commands::trail::TrailArgs;
+use crate::commands::xmpp::XmppArgs;
+use crate::{
+    audit_trail, commands, jitter,
+    error::{OrcError, Result},
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "orc", about = "A command-line client for services reachable over Tor")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Connect to a host:port through Tor and exchange raw bytes.
+    #[cfg(feature = "tcp")]
+    Stream(StreamArgs),
+    /// Forward a local TCP port to a remote host:port through Tor.
+    #[cfg(feature = "tcp")]
+    Forward(ForwardArgs),
+    /// Run an interactive REPL that keeps several named streams open.
+    Session(SessionArgs),
+    /// Run any orc command repeatedly in a single process.
+    Repl(ReplArgs),
+    /// Self-check that hostnames aren't resolved locally, the proxy is
+    /// local, and the check itself touches no files.
+    Audit(AuditArgs),
+    /// Benchmark connect time and TTFB against a target (throughput is
+    /// not measured).
+    Bench(BenchArgs),
+    /// Browse Gemini capsules with lazily-loaded, scrollable tabs.
+    #[cfg(feature = "tui")]
+    Browse(BrowseArgs),
+    /// Encrypted line chat with a peer over a mutual onion address.
+    Chat(ChatArgs),
+    /// Create and edit orc's JSON config file.
+    Config(ConfigArgs),
+    /// Reverse a passphrase-encrypted download back to plaintext.
+    Decrypt(DecryptArgs),
+    /// Query balances and broadcast transactions via an onion Electrum server.
+    Electrum(ElectrumArgs),
+    /// Fetch and render an RSS or Atom feed from an onion site.
+    #[cfg(feature = "http")]
+    Feed(FeedArgs),
+    /// Make a single http:// request over Tor, with curl-like flags.
+    #[cfg(feature = "http")]
+    Fetch(FetchArgs),
+    /// Fetch a gemini:// URL over Tor.
+    Gemini(GeminiArgs),
+    /// Connect to an onion IRC server.
+    Irc(IrcArgs),
+    /// Manage Tor v3 onion client-authorization keys.
+    Keys(KeysArgs),
+    /// Bridge stdin/stdout to a SOCKS stream (for ssh ProxyCommand).
+    #[cfg(feature = "tcp")]
+    Nc(NcArgs),
+    /// Submit mail to an onion SMTP provider.
+    Mail(MailArgs),
+    /// Sync and send messages on an onion Matrix homeserver.
+    Matrix(MatrixArgs),
+    /// Publish or subscribe to an onion MQTT broker.
+    Mqtt(MqttArgs),
+    /// Read newsgroups from an onion NNTP server.
+    Nntp(NntpArgs),
+    /// Send to or receive from an OnionShare instance.
+    Oshare(OshareArgs),
+    /// Wipe in-memory secrets and configured paths, then exit like a kill -9.
+    Panic(PanicArgs),
+    /// Resolve a hostname or IP address through Tor's exit resolvers.
+    Resolve(ResolveArgs),
+    /// Call one or more JSON-RPC methods on an onion node.
+    #[cfg(feature = "http")]
+    Rpc(RpcArgs),
+    /// Serve a local directory as an onion website.
+    #[cfg(all(feature = "serve", feature = "control-port"))]
+    Serve(ServeArgs),
+    /// Read back or export the in-memory record of commands this
+    /// process has run.
+    Trail(TrailArgs),
+    /// Roster, send, and receive against an onion XMPP server.
+    Xmpp(XmppArgs),
+}
+
+/// Runs a parsed [`Command`], applying [`crate::jitter`]'s opt-in delay
+/// first and recording the result in [`crate::audit_trail`] before
+/// returning. Shared by `main` and `orc repl` so running a command from
+/// the REPL is exactly what running it from the real CLI would do, and
+/// so both show up in the trail and the jitter delay the same way.
+///
+/// When `--json` is active (see [`audit_trail::json_mode`]), also prints
+/// the entry just recorded to stdout as a single JSON object, after the
+/// command's own output — see that module's doc comment for what this
+/// does and doesn't cover. A failing command additionally gets
+/// [`OrcError::to_json`] printed to *stderr* before that, so a wrapper
+/// watching stderr for failures gets a structured object instead of
+/// this crate's free-form error text.
+pub fn dispatch(command: Command) -> Result<()> {
+    jitter::delay(&jitter::load_jitter_options()?);
+
+    let (name, target) = describe(&command);
+    tracing::debug!(target: "orc::cli", command = name, target = target.as_deref().unwrap_or(""));
+    let started = SystemTime::now();
+    let start = Instant::now();
+    let result = dispatch_inner(command);
+    if let Err(err) = &result {
+        tracing::warn!(target: "orc::cli", command = name, error = %err);
+        if audit_trail::json_mode() {
+            eprintln!("{}", err.to_json(target.as_deref()));
+        }
+    }
+    audit_trail::record(name, target, &result, started, start.elapsed());
+    if audit_trail::json_mode() {
+        if let Some(summary) = audit_trail::last_entry_json() {
+            println!("{summary}");
+        }
+    }
+    result
+}
+
+/// The command name and, where one makes sense, the single most relevant
+/// target string — a host, a URL, a file — recorded alongside it.
+/// Subcommand-driven commands (`config`, `keys`, `mail`, and the like)
+/// don't have one obvious target across all of their actions, so only
+/// the command name is recorded for those.
+fn describe(command: &Command) -> (&'static str, Option<String>) {
+    match command {
+        #[cfg(feature = "tcp")]
+        Command::Stream(args) => ("stream", Some(args.target.clone())),
+        #[cfg(feature = "tcp")]
+        Command::Forward(args) => ("forward", Some(args.to.clone())),
+        Command::Session(_) => ("session", None),
+        Command::Repl(_) => ("repl", None),
+        Command::Audit(_) => ("audit", None),
+        Command::Bench(args) => ("bench", Some(args.target.clone())),
+        #[cfg(feature = "tui")]
+        Command::Browse(args) => ("browse", args.url.clone()),
+        Command::Chat(args) => ("chat", args.connect.clone()),
+        Command::Config(_) => ("config", None),
+        Command::Decrypt(args) => ("decrypt", Some(args.file.display().to_string())),
+        Command::Electrum(_) => ("electrum", None),
+        #[cfg(feature = "http")]
+        Command::Feed(args) => ("feed", Some(args.url.clone())),
+        #[cfg(feature = "http")]
+        Command::Fetch(args) => ("fetch", Some(args.url.clone())),
+        Command::Gemini(args) => ("gemini", Some(args.url.clone())),
+        Command::Irc(args) => ("irc", Some(args.server.clone())),
+        Command::Keys(_) => ("keys", None),
+        #[cfg(feature = "tcp")]
+        Command::Nc(args) => ("nc", Some(args.host.clone())),
+        Command::Mail(_) => ("mail", None),
+        Command::Matrix(_) => ("matrix", None),
+        Command::Mqtt(_) => ("mqtt", None),
+        Command::Nntp(_) => ("nntp", None),
+        Command::Oshare(_) => ("oshare", None),
+        Command::Panic(_) => ("panic", None),
+        Command::Resolve(args) => ("resolve", Some(args.target.clone())),
+        #[cfg(feature = "http")]
+        Command::Rpc(args) => ("rpc", Some(args.url.clone())),
+        #[cfg(all(feature = "serve", feature = "control-port"))]
+        Command::Serve(_) => ("serve", None),
+        Command::Trail(_) => ("trail", None),
+        Command::Xmpp(_) => ("xmpp", None),
+    }
+}
+
+fn dispatch_inner(command: Command) -> Result<()> {
+    match command {
+        #[cfg(feature = "tcp")]
+        Command::Stream(args) => commands::stream::run(args),
+        #[cfg(feature = "tcp")]
+        Command::Forward(args) => commands::forward::run(args),
+        Command::Session(args) => commands::session::run(args),
+        Command::Repl(args) => commands::repl::run(args),
+        Command::Audit(args) => commands::audit::run(args),
+        Command::Bench(args) => commands::bench::run(args),
+        #[cfg(feature = "tui")]
+        Command::Browse(args) => commands::browse::run(args),
+        Command::Chat(args) => commands::chat::run(args),
+        Command::Config(args) => commands::config::run(args),
+        Command::Decrypt(args) => commands::decrypt::run(args),
+        Command::Electrum(args) => commands::electrum::run(args),
+        #[cfg(feature = "http")]
+        Command::Feed(args) => commands::feed::run(args),
+        #[cfg(feature = "http")]
+        Command::Fetch(args) => commands::fetch::run(args),
+        Command::Gemini(args) => commands::gemini::run(args),
+        Command::Irc(args) => commands::irc::run(args),
+        Command::Keys(args) => commands::keys::run(args),
+        #[cfg(feature = "tcp")]
+        Command::Nc(args) => commands::nc::run(args),
+        Command::Mail(args) => commands::mail::run(args),
+        Command::Matrix(args) => commands::matrix::run(args),
+        Command::Mqtt(args) => commands::mqtt::run(args),
+        Command::Nntp(args) => commands::nntp::run(args),
+        Command::Oshare(args) => commands::oshare::run(args),
+        Command::Panic(args) => commands::panic::run(args),
+        Command::Resolve(args) => commands::resolve::run(args),
+        #[cfg(feature = "http")]
+        Command::Rpc(args) => commands::rpc::run(args),
+        #[cfg(all(feature = "serve", feature = "control-port"))]
+        Command::Serve(args) => commands::serve::run(args),
+        Command::Trail(args) => commands::trail::run(args),
+        Command::Xmpp(args) => commands::xmpp::run(args),
+    }
+}
+
+/// Expands a leading alias (`orc inbox` standing in for `orc gemini
+/// gemini://mymail.onion/`, say) against `aliases` before clap ever sees
+/// `args`, since by the time [`Cli::parse`] has run it's too late to swap
+/// out what subcommand was requested. Used by both `main` (reading
+/// [`crate::config::load_aliases`]) and `orc repl` (reading the same map
+/// once up front, rather than per line), so a shortcut works identically
+/// from a shell and from the REPL.
+///
+/// Checked against the real subcommand names first so an alias can never
+/// shadow one of them. A target is split the same way
+/// [`crate::commands::repl`] splits a REPL line — no quoting support,
+/// consistent with the rest of this crate's line parsing.
+pub fn expand_aliases(args: Vec<String>, aliases: &HashMap<String, String>) -> Result<Vec<String>> {
+    let Some(first) = args.get(1) else {
+        return Ok(args);
+    };
+    if Cli::command().get_subcommands().any(|sub| sub.get_name() == first) {
+        return Ok(args);
+    }
+    let Some(target) = aliases.get(first) else {
+        return Ok(args);
+    };
+    let expanded: Vec<String> = target.split_whitespace().map(str::to_string).collect();
+    if expanded.is_empty() {
+        return Err(OrcError::InvalidArgument(format!("alias `{first}` expands to an empty command")));
+    }
+
+    let mut result = vec![args[0].clone()];
+    result.extend(expanded);
+    result.extend(args.into_iter().skip(2));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn expand_aliases_replaces_a_bare_alias_with_its_target() {
+        let aliases = aliases(&[("inbox", "gemini gemini://mymail.onion/")]);
+        let expanded = expand_aliases(argv(&["orc", "inbox"]), &aliases).unwrap();
+        assert_eq!(expanded, argv(&["orc", "gemini", "gemini://mymail.onion/"]));
+    }
+
+    #[test]
+    fn expand_aliases_appends_trailing_arguments_after_the_expansion() {
+        let aliases = aliases(&[("inbox", "gemini --proxy 127.0.0.1:9050")]);
+        let expanded = expand_aliases(argv(&["orc", "inbox", "gemini://mymail.onion/"]), &aliases).unwrap();
+        assert_eq!(expanded, argv(&["orc", "gemini", "--proxy", "127.0.0.1:9050", "gemini://mymail.onion/"]));
+    }
+
+    #[test]
+    fn expand_aliases_leaves_an_unknown_first_word_alone() {
+        let expanded = expand_aliases(argv(&["orc", "bogus"]), &aliases(&[])).unwrap();
+        assert_eq!(expanded, argv(&["orc", "bogus"]));
+    }
+
+    #[test]
+    fn expand_aliases_never_shadows_a_real_subcommand() {
+        let aliases = aliases(&[("gemini", "resolve example.onion")]);
+        let expanded = expand_aliases(argv(&["orc", "gemini", "gemini://example.onion/"]), &aliases).unwrap();
+        assert_eq!(expanded, argv(&["orc", "gemini", "gemini://example.onion/"]));
+    }
+
+    #[test]
+    fn expand_aliases_leaves_a_bare_invocation_alone() {
+        let expanded = expand_aliases(argv(&["orc"]), &aliases(&[])).unwrap();
+        assert_eq!(expanded, argv(&["orc"]));
+    }
+
+    #[test]
+    fn expand_aliases_rejects_an_alias_with_an_empty_target() {
+        let aliases = aliases(&[("inbox", "   ")]);
+        assert!(expand_aliases(argv(&["orc", "inbox"]), &aliases).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! A small synchronous facade over [`crate::net::tcp`] and
+//! [`crate::net::resolve`] for a caller that wants `orc`'s
+//! Tor-only-traffic guarantee embedded in its own Rust program, without
+//! shelling out to the `orc` binary.
+//!
+//! There's no async runtime anywhere in this crate — every protocol
+//! module blocks the calling thread the same way [`crate::commands`]
+//! already does — so [`OrcClient`]'s methods are plain blocking calls
+//! rather than `async fn`s; an embedder that wants this off the main
+//! thread spawns its own, the same way `orc chat`/`orc browse` spawn
+//! their own worker threads internally.
+//!
+//! This wraps the operations every protocol module needs regardless of
+//! what's on top of them — opening a proxied stream, asking the exit
+//! relay to resolve a name, and (via [`OrcRequest`]) making a plain
+//! `http://` request. [`Self::with_events`] subscribes to
+//! [`crate::events::OrcEvent`]s as those calls progress. A
+//! protocol-specific client (Gemini, JSON-RPC, ...) is still reached
+//! through [`crate::net`] directly; [`OrcClient`] isn't trying to
+//! re-expose this crate's whole surface, just the part with no natural
+//! home in any one protocol module.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::events::EventSink;
+use crate::net::http::{self, HttpRequest, HttpResponse};
+use crate::net::resolve;
+use crate::net::tcp::{self, ConnectOptions, TorStream};
+use crate::net::transport::{DuplexStream, TorTransport};
+
+/// A configured SOCKS5 proxy address and connect options, reused across
+/// calls the same way a command's parsed `--proxy`/timeout flags are.
+#[derive(Debug, Clone)]
+pub struct OrcClient {
+    proxy_addr: SocketAddr,
+    options: ConnectOptions,
+}
+
+impl OrcClient {
+    /// Targets the Tor daemon's SOCKS5 port at `proxy_addr`, with
+    /// default timeouts and no isolation credentials — the same starting
+    /// point `--proxy 127.0.0.1:9050` gives every CLI command.
+    pub fn new(proxy_addr: SocketAddr) -> Self {
+        OrcClient { proxy_addr, options: ConnectOptions::default() }
+    }
+
+    /// Replaces the connect timeout, read/write timeouts, keepalive, and
+    /// SOCKS isolation credentials this client connects with.
+    pub fn with_options(mut self, options: ConnectOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Subscribes `sink` to this client's [`crate::events::OrcEvent`]s —
+    /// connect started, the SOCKS handshake completing, a retry being
+    /// scheduled, bytes sent or received — the same hook
+    /// [`ConnectOptions::events`] exposes directly for a caller that
+    /// builds its own `ConnectOptions`.
+    pub fn with_events(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.options.events = Some(sink);
+        self
+    }
+
+    /// Attaches `token`, so a caller on another thread can cancel any
+    /// call this client makes afterwards by calling
+    /// [`crate::cancellation::CancellationToken::cancel`] — see
+    /// [`crate::cancellation`].
+    pub fn with_cancellation(mut self, token: crate::cancellation::CancellationToken) -> Self {
+        self.options.cancellation = Some(token);
+        self
+    }
+
+    /// Opens a SOCKS5 CONNECT stream to `target_host:target_port`
+    /// through the proxy — checked against
+    /// [`crate::security::check_host`] first, same as every CLI command
+    /// — and wraps it in [`TorStream`] rather than handing back the raw
+    /// [`tcp::Socks5Stream`], so an embedder's `dyn Read + Write` usage
+    /// goes through the same construction-time check a second time.
+    pub fn connect(&self, target_host: &str, target_port: u16) -> Result<TorStream> {
+        let stream = tcp::create_socks_stream(self.proxy_addr, target_host, target_port, &self.options)?;
+        TorStream::new(stream, target_host)
+    }
+
+    /// Asks the exit relay to resolve `hostname`, rather than resolving
+    /// it with this process's own DNS.
+    pub fn resolve(&self, hostname: &str) -> Result<IpAddr> {
+        resolve::resolve(self.proxy_addr, hostname, &self.options)
+    }
+
+    /// Asks the exit relay to resolve `addr` back to a hostname.
+    pub fn resolve_ptr(&self, addr: IpAddr) -> Result<String> {
+        resolve::resolve_ptr(self.proxy_addr, addr, &self.options)
+    }
+
+    /// Starts building an `http://` request against `url`, reusing this
+    /// client's proxy address and connect options — the builder
+    /// [`crate::commands::rpc`] and [`crate::commands::feed`] should move
+    /// onto in place of calling [`http::send`] directly.
+    pub fn request(&self, method: &str, url: &str) -> OrcRequest<'_> {
+        OrcRequest {
+            proxy_addr: self.proxy_addr,
+            options: &self.options,
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            max_redirects: crate::defaults::max_redirects(),
+        }
+    }
+}
+
+/// A single `http://` request being assembled before it's sent — method,
+/// headers, body, and a redirect limit, gathered in one place instead of
+/// [`http::send`]'s flat [`HttpRequest`] argument list. Built with
+/// [`OrcClient::request`]; reuses the client's proxy address and connect
+/// options, so isolation credentials and timeouts are set once on the
+/// client rather than per request.
+pub struct OrcRequest<'a> {
+    proxy_addr: SocketAddr,
+    options: &'a ConnectOptions,
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    max_redirects: u32,
+}
+
+impl<'a> OrcRequest<'a> {
+    /// Adds a header, sent in addition to whatever [`http::send`] already
+    /// adds (`Host`, `Connection`, `User-Agent`, `Content-Length`).
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the request body, and implicitly its `Content-Length`.
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Caps how many `3xx` `Location` redirects [`Self::send`] will
+    /// follow, overriding [`crate::defaults::max_redirects`]. `0` means
+    /// the first response is always returned as-is.
+    pub fn max_redirects(mut self, max_redirects: u32) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// Sends the request, following `3xx` responses' `Location` header
+    /// up to [`Self::max_redirects`] times before returning whatever
+    /// response stops the chain — a non-redirect status, a `Location`-less
+    /// redirect, or the redirect limit itself.
+    pub fn send(self) -> Result<HttpResponse> {
+        let mut url = self.url;
+        let mut redirects_left = self.max_redirects;
+        loop {
+            let response = http::send(HttpRequest {
+                proxy: self.proxy_addr,
+                method: &self.method,
+                url: &url,
+                headers: &self.headers,
+                body: &self.body,
+                options: self.options,
+            })?;
+            if !(300..400).contains(&response.status) {
+                return Ok(response);
+            }
+            let Some(location) = response.header("location") else {
+                return Ok(response);
+            };
+            if redirects_left == 0 {
+                return Ok(response);
+            }
+            redirects_left -= 1;
+            url = http::resolve_location(&url, location)?;
+        }
+    }
+}
+
+/// The real SOCKS5-backed implementation of [`TorTransport`] —
+/// everything else in this crate that dials out does so through exactly
+/// this path; see that trait's doc comment for why it exists alongside
+/// these same three operations as plain methods above.
+impl TorTransport for OrcClient {
+    fn connect_stream(&self, host: &str, port: u16) -> Result<Box<dyn DuplexStream>> {
+        Ok(Box::new(self.connect(host, port)?))
+    }
+
+    fn resolve(&self, hostname: &str) -> Result<IpAddr> {
+        OrcClient::resolve(self, hostname)
+    }
+
+    fn http_client(&self, method: &str, url: &str, headers: &[(String, String)], body: &[u8]) -> Result<HttpResponse> {
+        http::send(HttpRequest { proxy: self.proxy_addr, method, url, headers, body, options: &self.options })
+    }
+}
@@ -0,0 +1,134 @@
+//! Whether `orc`'s own status lines and diagnostics get ANSI colour, and
+//! the pre-clap `--color always|never|auto` flag (plus `NO_COLOR`) that
+//! decides it.
+//!
+//! There's no `orc check` command in this crate for this to cover —
+//! `orc fetch`'s status line and [`crate::diagnostics::export_to_stderr`]
+//! are the two places it actually lands. Distinct from
+//! [`crate::config::Theme`], which colours `orc browse`'s TUI under its
+//! own `--theme`/config-file setting: that one is a whole interactive
+//! theme with its own defaults file, this is the plain one-shot-command
+//! equivalent, read from a single process-wide flag the same way
+//! [`crate::redact`] reads `--no-redact`.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Always,
+    Never,
+    Auto,
+}
+
+impl ColorMode {
+    /// Parses `--color`'s argument. `None` if it's none of the three
+    /// accepted spellings.
+    pub fn parse(value: &str) -> Option<ColorMode> {
+        match value {
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            "auto" => Some(ColorMode::Auto),
+            _ => None,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            ColorMode::Always => 0,
+            ColorMode::Never => 1,
+            ColorMode::Auto => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> ColorMode {
+        match value {
+            0 => ColorMode::Always,
+            1 => ColorMode::Never,
+            _ => ColorMode::Auto,
+        }
+    }
+}
+
+/// `Auto` until `--color` says otherwise, the same default curl and git
+/// both use.
+static MODE: AtomicU8 = AtomicU8::new(2);
+
+/// Set once from `main` after its pre-clap `--color` handling; read from
+/// every later colour-printing path, the same pre-clap-flag-plus-ambient-setter
+/// pattern [`crate::security::set_allow_remote_socks`] uses.
+pub fn set_mode(mode: ColorMode) {
+    MODE.store(mode.to_u8(), Ordering::Relaxed);
+}
+
+fn mode() -> ColorMode {
+    ColorMode::from_u8(MODE.load(Ordering::Relaxed))
+}
+
+/// Whether output should carry ANSI colour right now: `always`/`never`
+/// decide outright; `auto` (the default) colours only when stderr is a
+/// real terminal and [`NO_COLOR`](https://no-color.org) isn't set.
+pub fn enabled() -> bool {
+    match mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+    }
+}
+
+/// Wraps `text` in the ANSI SGR code `code` (e.g. `"31"` for red) when
+/// [`enabled`], otherwise returns it unchanged.
+pub fn paint(code: &str, text: &str) -> String {
+    paint_if(code, text, enabled())
+}
+
+fn paint_if(code: &str, text: &str, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Colours `text` by the class of an HTTP `status` code: green for
+/// `2xx`, yellow for `3xx`, red for everything else.
+pub fn status(status: u16, text: &str) -> String {
+    paint(status_code(status), text)
+}
+
+fn status_code(status: u16) -> &'static str {
+    match status / 100 {
+        2 => "32",
+        3 => "33",
+        _ => "31",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_if_disabled_leaves_text_unpainted() {
+        assert_eq!(paint_if("31", "hi", false), "hi");
+    }
+
+    #[test]
+    fn paint_if_enabled_wraps_text_in_the_given_code() {
+        assert_eq!(paint_if("31", "hi", true), "\x1b[31mhi\x1b[0m");
+    }
+
+    #[test]
+    fn status_code_picks_green_yellow_or_red_by_class() {
+        assert_eq!(status_code(200), "32");
+        assert_eq!(status_code(301), "33");
+        assert_eq!(status_code(404), "31");
+        assert_eq!(status_code(500), "31");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_spellings() {
+        assert_eq!(ColorMode::parse("always"), Some(ColorMode::Always));
+        assert_eq!(ColorMode::parse("rainbow"), None);
+    }
+}
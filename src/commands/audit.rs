@@ -0,0 +1,238 @@
+//! `orc audit`: a read-only self-check for paranoid users, confirming at
+//! runtime the handful of properties the rest of this crate's doc
+//! comments only promise in prose — that hostnames are handed to the
+//! SOCKS proxy as domain names rather than resolved locally first (see
+//! [`crate::net::tcp::create_socks_stream`]'s doc comment), that the
+//! configured proxy is actually local, and that running the check itself
+//! doesn't leave anything on disk. Exits with status 1 if any check
+//! fails, so it's usable as a CI gate, not just something to eyeball.
+//!
+//! The domain-name check doesn't touch the real `--proxy` at all: it
+//! stands up a throwaway `TcpListener` on loopback and points
+//! [`create_socks_stream`] at that instead, so the audit is a pure,
+//! offline protocol-level check of this crate's own SOCKS client code —
+//! the same function every other command dials out through — rather
+//! than depending on a real Tor daemon being reachable.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::config;
+use crate::error::Result;
+use crate::net::tcp::create_socks_stream;
+
+const ATYP_DOMAIN: u8 = 0x03;
+const AUDIT_HOSTNAME: &str = "orc-audit-self-test.invalid";
+
+/// One directory listing entry: name, modification time, and length.
+type DirSnapshot = Vec<(String, SystemTime, u64)>;
+
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    /// Address of the local SOCKS5 proxy normally used for real
+    /// connections. Only checked for being a loopback address here —
+    /// the protocol-level check below never actually dials it.
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+struct CheckResult {
+    name: &'static str,
+    passed: bool,
+    detail: String,
+}
+
+pub fn run(args: AuditArgs) -> Result<()> {
+    let watched_dirs = [config::default_config_file().parent().map(Path::to_path_buf), std::env::current_dir().ok()];
+    let before: Vec<Option<DirSnapshot>> = watched_dirs.iter().map(|dir| dir.as_deref().and_then(snapshot_dir)).collect();
+
+    let mut results = vec![check_proxy_is_loopback(args.proxy), check_domain_name_handshake(&args)];
+
+    let after: Vec<Option<DirSnapshot>> = watched_dirs.iter().map(|dir| dir.as_deref().and_then(snapshot_dir)).collect();
+    results.push(check_no_files_written(&before, &after));
+
+    let failed = results.iter().filter(|r| !r.passed).count();
+    for result in &results {
+        let status = if result.passed { "PASS" } else { "FAIL" };
+        println!("[{status}] {}: {}", result.name, result.detail);
+    }
+    println!();
+    if failed == 0 {
+        println!("all {} checks passed", results.len());
+        Ok(())
+    } else {
+        println!("{failed} of {} checks failed", results.len());
+        std::process::exit(1);
+    }
+}
+
+fn check_proxy_is_loopback(proxy: SocketAddr) -> CheckResult {
+    let passed = proxy.ip().is_loopback();
+    let detail = if passed {
+        format!("{proxy} is a loopback address")
+    } else {
+        format!("{proxy} is not a loopback address — traffic to the proxy itself could leave this machine")
+    };
+    CheckResult { name: "proxy is local", passed, detail }
+}
+
+fn check_domain_name_handshake(args: &AuditArgs) -> CheckResult {
+    let listener = match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(err) => return CheckResult { name: "hostnames are never resolved locally", passed: false, detail: format!("could not bind a throwaway listener: {err}") },
+    };
+    let proxy_addr = listener.local_addr().expect("a bound listener has a local address");
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(capture_connect_request(listener));
+    });
+
+    let options = args.timeouts.to_connect_options();
+    let dial_result = create_socks_stream(proxy_addr, AUDIT_HOSTNAME, 80, &options);
+
+    // `dial_result` can fail before ever reaching the fake proxy — most
+    // notably if the user's own `security.deny` list happens to match
+    // `AUDIT_HOSTNAME` — in which case the capture thread is still
+    // sitting in `listener.accept()` with nothing ever going to connect
+    // to it. `recv_timeout` instead of `recv` keeps that case a prompt
+    // failure instead of a hang.
+    let recv_result = rx.recv_timeout(Duration::from_secs(args.timeouts.connect_timeout));
+
+    match (dial_result, recv_result) {
+        (Ok(_), Ok(Some((atyp, name)))) if atyp == ATYP_DOMAIN && name == AUDIT_HOSTNAME => {
+            CheckResult { name: "hostnames are never resolved locally", passed: true, detail: format!("`{AUDIT_HOSTNAME}` reached the proxy as a SOCKS5 domain name, unresolved") }
+        }
+        (Ok(_), Ok(Some((atyp, name)))) => {
+            CheckResult { name: "hostnames are never resolved locally", passed: false, detail: format!("proxy received address type {atyp} (`{name}`) instead of the original domain name") }
+        }
+        (dial_result, recv_result) => {
+            let reason = dial_result.err().map(|err| err.to_string()).or(recv_result.err().map(|err| err.to_string())).unwrap_or_else(|| "the fake proxy never completed the handshake".to_string());
+            CheckResult { name: "hostnames are never resolved locally", passed: false, detail: format!("could not complete a test handshake: {reason}") }
+        }
+    }
+}
+
+/// Plays the server side of one SOCKS5 CONNECT handshake just far enough
+/// to read the address `orc` sent, then replies with a dummy success
+/// reply so the client side doesn't hang waiting. Returns the address
+/// type and, for a domain name, the name itself.
+fn capture_connect_request(listener: TcpListener) -> Option<(u8, String)> {
+    let (mut stream, _) = listener.accept().ok()?;
+
+    let mut greeting = [0u8; 3];
+    stream.read_exact(&mut greeting).ok()?;
+    stream.write_all(&[0x05, 0x00]).ok()?;
+
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).ok()?;
+    let atyp = header[3];
+
+    let name = match atyp {
+        ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).ok()?;
+            let mut name = vec![0u8; len[0] as usize];
+            stream.read_exact(&mut name).ok()?;
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port).ok()?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        0x01 => {
+            let mut addr = [0u8; 4 + 2];
+            stream.read_exact(&mut addr).ok()?;
+            std::net::Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]).to_string()
+        }
+        0x04 => {
+            let mut addr = [0u8; 16 + 2];
+            stream.read_exact(&mut addr).ok()?;
+            std::net::Ipv6Addr::from(<[u8; 16]>::try_from(&addr[..16]).expect("fixed-size slice")).to_string()
+        }
+        _ => return None,
+    };
+
+    // Bogus success reply (0.0.0.0:0) so `create_socks_stream` returns
+    // `Ok` instead of timing out waiting for one.
+    stream.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).ok()?;
+
+    Some((atyp, name))
+}
+
+fn check_no_files_written(before: &[Option<DirSnapshot>], after: &[Option<DirSnapshot>]) -> CheckResult {
+    let passed = before == after;
+    let detail = if passed {
+        "no files were created or modified in the config directory or the current directory while this audit ran".to_string()
+    } else {
+        "a file in the config directory or the current directory changed while this audit ran".to_string()
+    };
+    CheckResult { name: "audit itself writes nothing to disk", passed, detail }
+}
+
+/// A filename + modification time + length for every entry directly
+/// inside `dir`, sorted by name so two snapshots of an unchanged
+/// directory compare equal regardless of listing order. `None` if `dir`
+/// doesn't exist (e.g. no config file has ever been written).
+fn snapshot_dir(dir: &Path) -> Option<DirSnapshot> {
+    let mut entries: DirSnapshot = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            Some((entry.file_name().to_string_lossy().into_owned(), metadata.modified().ok()?, metadata.len()))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_check_passes_for_loopback_addresses() {
+        assert!(check_proxy_is_loopback("127.0.0.1:9050".parse().unwrap()).passed);
+        assert!(check_proxy_is_loopback("[::1]:9050".parse().unwrap()).passed);
+    }
+
+    #[test]
+    fn proxy_check_fails_for_a_non_loopback_address() {
+        assert!(!check_proxy_is_loopback("203.0.113.5:9050".parse().unwrap()).passed);
+    }
+
+    #[test]
+    fn no_files_written_passes_when_snapshots_match() {
+        let snapshot = vec![Some(vec![("config.json".to_string(), SystemTime::UNIX_EPOCH, 42u64)])];
+        assert!(check_no_files_written(&snapshot, &snapshot).passed);
+    }
+
+    #[test]
+    fn no_files_written_fails_when_a_file_appears() {
+        let before = vec![None];
+        let after = vec![Some(vec![("config.json".to_string(), SystemTime::UNIX_EPOCH, 0u64)])];
+        assert!(!check_no_files_written(&before, &after).passed);
+    }
+
+    #[test]
+    fn snapshot_dir_sees_a_newly_created_file() {
+        let dir = std::env::temp_dir().join(format!("orc-audit-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let before = snapshot_dir(&dir);
+        std::fs::write(dir.join("new-file.txt"), b"hi").unwrap();
+        let after = snapshot_dir(&dir);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_ne!(before, after);
+    }
+}
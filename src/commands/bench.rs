@@ -0,0 +1,175 @@
+//! `orc bench`: repeatedly connect (and optionally fetch) an onion target
+//! to measure connect time and time-to-first-byte. Throughput is not
+//! measured: only the first byte of each reply is read.
+
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::split_host_port;
+use crate::net::tcp::create_socks_stream;
+use crate::secret::HexSource;
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Target to connect to, as `host:port`.
+    pub target: String,
+
+    /// Number of connection attempts to make.
+    #[arg(long, default_value_t = 10)]
+    pub count: usize,
+
+    /// Bytes to send on each connection before timing the first read.
+    /// Defaults to nothing, which only measures connect time.
+    #[arg(long, conflicts_with_all = ["hex_stdin", "hex_file", "secret_fd"])]
+    pub send_hex: Option<String>,
+
+    /// Read the hex payload from stdin instead of `--send-hex`, so it
+    /// never appears in `ps` output or shell history.
+    #[arg(long)]
+    pub hex_stdin: bool,
+
+    /// Read the hex payload from a file instead of `--send-hex`.
+    #[arg(long, conflicts_with = "hex_stdin")]
+    pub hex_file: Option<std::path::PathBuf>,
+
+    /// Read the hex payload from this already-open file descriptor
+    /// instead of `--send-hex` (Unix only) — e.g. `--secret-fd 3`
+    /// alongside a caller's own `exec 3<secret.hex`.
+    #[arg(long, conflicts_with_all = ["hex_stdin", "hex_file"])]
+    pub secret_fd: Option<u32>,
+
+    /// Use a fresh SOCKS stream per attempt (the default, and currently
+    /// the only supported mode, since circuit isolation across attempts
+    /// requires Tor-side `IsolateSOCKSAuth` support we don't drive yet).
+    #[arg(long, default_value_t = true)]
+    pub isolate: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub connect: std::time::Duration,
+    pub ttfb: Option<std::time::Duration>,
+}
+
+/// Picks whichever of `--send-hex`/`--hex-stdin`/`--hex-file`/
+/// `--secret-fd` the user gave, clap's `conflicts_with_all` having
+/// already ruled out more than one at once.
+fn resolve_payload(args: &BenchArgs) -> Result<Option<Vec<u8>>> {
+    if let Some(hex) = &args.send_hex {
+        return Ok(Some(crate::output::decode_hex(hex)?));
+    }
+    if args.hex_stdin {
+        return Ok(Some(HexSource::Stdin.read()?.into_bytes()));
+    }
+    if let Some(path) = &args.hex_file {
+        return Ok(Some(HexSource::File(path.clone()).read()?.into_bytes()));
+    }
+    if let Some(fd) = args.secret_fd {
+        return Ok(Some(HexSource::Fd(fd).read()?.into_bytes()));
+    }
+    Ok(None)
+}
+
+pub fn run(args: BenchArgs) -> Result<()> {
+    let (host, port) = split_host_port(&args.target)?;
+    let options = args.timeouts.to_connect_options();
+    let payload = resolve_payload(&args)?;
+
+    let mut samples = Vec::with_capacity(args.count);
+    for i in 0..args.count {
+        match run_one(args.proxy, host, port, &options, payload.as_deref()) {
+            Ok(sample) => samples.push(sample),
+            Err(err) => eprintln!("orc: attempt {} failed: {err}", i + 1),
+        }
+    }
+
+    print_report(&samples);
+    Ok(())
+}
+
+fn run_one(
+    proxy: SocketAddr,
+    host: &str,
+    port: u16,
+    options: &crate::net::tcp::ConnectOptions,
+    payload: Option<&[u8]>,
+) -> Result<Sample> {
+    use std::io::{Read, Write};
+
+    let start = Instant::now();
+    let mut stream = create_socks_stream(proxy, host, port, options)?;
+    let connect = start.elapsed();
+
+    let ttfb = if let Some(payload) = payload {
+        stream.write_all(payload)?;
+        let before_read = Instant::now();
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte)?;
+        Some(before_read.elapsed())
+    } else {
+        None
+    };
+
+    Ok(Sample { connect, ttfb })
+}
+
+fn print_report(samples: &[Sample]) {
+    if samples.is_empty() {
+        println!("no successful attempts");
+        return;
+    }
+
+    let mut connects: Vec<f64> = samples.iter().map(|s| s.connect.as_secs_f64() * 1000.0).collect();
+    connects.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    println!("attempts: {}", samples.len());
+    println!("connect time (ms): p50={:.1} p90={:.1} p99={:.1}", percentile(&connects, 0.50), percentile(&connects, 0.90), percentile(&connects, 0.99));
+
+    let ttfbs: Vec<f64> = samples
+        .iter()
+        .filter_map(|s| s.ttfb)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .collect();
+    if !ttfbs.is_empty() {
+        let mut ttfbs = ttfbs;
+        ttfbs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        println!("ttfb (ms): p50={:.1} p90={:.1} p99={:.1}", percentile(&ttfbs, 0.50), percentile(&ttfbs, 0.90), percentile(&ttfbs, 0.99));
+    }
+}
+
+/// Nearest-rank percentile over an already-sorted slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_picks_nearest_rank() {
+        let sorted = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.50), 3.0);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+    }
+
+    #[test]
+    fn percentile_handles_empty() {
+        assert_eq!(percentile(&[], 0.50), 0.0);
+    }
+}
@@ -0,0 +1,1569 @@
+//! `orc browse`: a REPL-driven, tabbed Gemini capsule browser.
+//!
+//! There's no curses-style full-screen renderer in this crate (no raw
+//! terminal dependency at all), so tabs are driven the same line-
+//! oriented way as `orc session`'s named streams rather than with actual
+//! keyboard shortcuts. Each tab is fetched lazily: `open` just records
+//! its URL, and the fetch (and its rendered lines) happen — and get
+//! cached — the first time that tab is shown, so comparing capsules over
+//! a slow circuit doesn't mean re-fetching one you've already paid for.
+//! Each tab also keeps its own scroll position and its own back/forward
+//! history. History lives only in these `Vec`s for the life of the
+//! process — nothing is written to disk, so it's gone on a normal exit
+//! or a panic without any explicit wiping step, and `--no-history`
+//! disables recording it in the first place for anyone who'd rather it
+//! never existed even in memory.
+//!
+//! Links can also be saved to disk without blocking the REPL: `dl` queues
+//! a download and a single background dispatcher thread works through
+//! the queue one at a time, so `show`/`nav`/etc. on other tabs stay
+//! responsive while a big capsule is still being fetched. Because
+//! [`gemini::fetch`] reads a whole response before returning, there's no
+//! mid-transfer progress to report and `pause` only takes effect on a
+//! download that hasn't started yet — one already in flight still runs
+//! to completion.
+//!
+//! Commands, one per line on stdin:
+//!
+//! ```text
+//! open <url>        fetch a new tab and make it active
+//! tabs              list open tabs with their isolation identity, marking the active one
+//! switch <n>        make tab n active (`tab <n>` also works)
+//! close <n>         close tab n
+//! nav <url>         navigate the active tab to a new capsule
+//! back              go back in the active tab's history
+//! forward           go forward in the active tab's history
+//! history           list the active tab's history, marking the current entry
+//! scroll <+n|-n>    move the active tab's scroll position
+//! show [lines]      print the active tab's rendered text from its scroll position
+//! headers           print the active tab's Gemini status and meta line
+//! split             toggle showing rendered text next to raw source in `show`
+//! reader            toggle reflowed, link-free reading view in `show`
+//! find <term>       search the active tab's rendered text and scroll to the first match
+//! n                 scroll to the next match
+//! N                 scroll to the previous match
+//! submit <text>     answer the active tab's pending Gemini input request
+//! dl <url> [dest]   queue a download of url, optionally under a given path
+//! save [dest]       queue a download of the active tab's own URL (see a binary tab's hexdump first with `show`)
+//! downloads         list queued, running, and finished downloads
+//! pause <n>         skip download n until it's resumed
+//! resume <n>        re-queue a paused download
+//! open-folder <n>   open the folder containing download n (off by default)
+//! status            show the Tor version, SOCKS listener, circuit, and traffic totals
+//! newnym            ask Tor for a fresh circuit identity
+//! session save <path>     encrypt and save the open tabs to path
+//! session restore <path>  replace the open tabs with a saved session
+//! session delete <path>   securely wipe a saved session file
+//! image <url>       fetch url and render it inline (needs --images)
+//! hints             label the active tab's links with short letters
+//! goto <hint>       navigate to the link hints labelled
+//! complete [prefix] list commands starting with prefix (or all of them)
+//! quit
+//! ```
+//!
+//! Every line is already a command, but a leading `:` is accepted too
+//! (and ignored) for anyone used to an ex-style command line — `:open
+//! url`, `:tab 2`, and `:panic` (if that's the configured kill-switch
+//! word) all work exactly like their unprefixed form. `complete` stands
+//! in for shell-style tab completion, since there's no raw-terminal
+//! dependency here to hook a keypress.
+//!
+//! Gemini capsules don't carry HTML, so there's no form to fill in —
+//! logging in or searching a capsule works through the protocol's own
+//! input mechanism instead: a `1x` response asks for a line of text in
+//! its `meta` field, and `submit` resends the active tab's URL with that
+//! text as a percent-encoded query.
+//!
+//! A response whose meta isn't a `text/*` MIME type (or that claims to
+//! be text but isn't valid UTF-8) is treated as binary: `show` renders it
+//! as a scrollable hexdump via [`crate::output::hexdump`] instead of
+//! gemtext, and `save` queues it through the same download manager as
+//! `dl` rather than trying to print it.
+//!
+//! For debugging a capsule, `headers` prints Gemini's entire header —
+//! just the status code and `meta` line, since the protocol has nothing
+//! resembling HTTP's multi-line headers — and `split` toggles a second
+//! column in `show`'s output carrying the response body's raw lines next
+//! to their rendered counterpart, standing in for "HTML source" since
+//! there's no HTML for a capsule to carry.
+//!
+//! `reader` toggles a readability pass over `show`'s rendered text for
+//! the active tab: [`gemini::reader_mode`] drops `=>` link lines and
+//! reflows runs of paragraph text to a fixed width, leaving headings,
+//! quotes, and list items alone, for reading long-form capsules without
+//! navigation clutter or whatever width the author wrapped their source
+//! at. It has no effect while `split` is also on, since split's raw
+//! source column is meant to show the response exactly as it arrived.
+//!
+//! `image <url>` fetches `url` and writes it straight to stdout as a
+//! terminal graphics escape sequence (see [`crate::net::imageterm`]) —
+//! strictly opt-in via `--images` or a config file's `"images": true`,
+//! since fetching a capsule's image links is a second, separate request
+//! per picture and changes `orc`'s traffic profile in a way text-only
+//! browsing doesn't. `--image-protocol <kitty|iterm>` picks which
+//! terminal's escape sequence to emit; there's no terminal-capability
+//! detection in this crate, so picking the one the terminal doesn't
+//! understand just prints garbage instead of a picture.
+//!
+//! `hints` is this REPL's stand-in for vimium's `f`-key overlay: there's
+//! no terminal-control dependency here to draw letters over the actual
+//! links on screen, so it prints the active tab's `=>` lines instead,
+//! each labelled with a short letter code (`a`, `b`, ..., `z`, `aa`,
+//! ...), and `goto <hint>` navigates to the one a letter names — the
+//! same two-keystroke link-picking `f` gives a real browser, just typed
+//! out instead of overlaid.
+//!
+//! `status` needs `--control-port` pointing at Tor's control port (it's
+//! not reached through the SOCKS proxy — it's local to the Tor daemon).
+//! It's queried fresh on every call rather than kept open and refreshed
+//! automatically, since nothing here drives a redraw loop.
+//!
+//! Every tab also gets its own SOCKS isolation credentials (see
+//! [`crate::net::tcp::SocksAuth`]) the moment it's opened: Tor gives two
+//! connections separate circuits whenever they present different SOCKS5
+//! usernames ("IsolateSOCKSAuth" in `torrc`, on by default), so two tabs
+//! browsing two onion services never end up sharing one. A tab's
+//! credentials are nothing more than `orc-tab-<n>`, not random bytes,
+//! for the n-th tab ever opened in this
+//! process — distinct per tab is all isolation needs, not unguessable —
+//! and `tabs` prints that identity next to each URL as the closest thing
+//! to an indicator this line-oriented REPL has. `--new-circuit-on-open`
+//! additionally sends `SIGNAL NEWNYM` (the same thing `newnym` does)
+//! every time a tab is opened, for anyone who wants Tor to retire its
+//! whole pool of existing circuits rather than relying on isolation
+//! credentials alone to keep a new tab off them; it needs
+//! `--control-port` like `status` and `newnym` do.
+//!
+//! `session save <path>` asks for a passphrase on stdin (and optionally
+//! a second duress passphrase — see [`crate::duress`] — left blank to
+//! skip it) and writes the open tabs' URLs and scroll positions to
+//! `path` as an encrypted blob (see [`crate::session_store`]); `session
+//! restore <path>` asks for the same passphrase and replaces the open
+//! tabs with what was saved, or, if it's instead given the duress
+//! passphrase, silently wipes `path` and restores no tabs at all.
+//! Nothing is ever saved automatically — only these explicit commands
+//! touch disk — and there's no cookie jar in the blob since Gemini
+//! carries no cookies to save. `session delete <path>` overwrites and
+//! removes a saved file via [`crate::killswitch::secure_wipe_file`]
+//! rather than leaving the plaintext blob for a plain `rm` to unlink.
+//!
+//! There's also a kill switch: typing the word set by `--kill-switch`
+//! (`panic` by default) alone on a line wipes the TLS pin store and
+//! exits with status 137 instead of running it as a command. See
+//! [`crate::killswitch`].
+//!
+//! `--config-file`'s `"hosts"` section is re-read on the fly: before
+//! running each line, this REPL stats the file and, if its modified time
+//! has moved on since the last check, reloads just the per-host timeout,
+//! header, and pin overrides (see [`reload_hosts`]) without touching any
+//! open tab, its scroll position, or its SOCKS isolation credentials —
+//! there's no daemon here, and `SIGHUP` is handled by
+//! [`crate::signals`] as an exit signal rather than a reload trigger, so
+//! polling once per command is the stand-in. Everything
+//! else `--config-file` can set (`keys`, `theme`, `images`, `proxies`,
+//! `socks_socket`) is read once at startup only, since those pick a
+//! proxy or a rendering style rather than a per-connection setting, and
+//! changing them mid-session out from under already-open tabs would be a
+//! more surprising kind of "non-disruptive" than this is worth being.
+//!
+//! `--ephemeral` keeps the whole session in memory: no config file is
+//! read, TLS pins live only in a `Vec` for the life of the process
+//! instead of `--pin-file`, and `dl`/`save`/`session save`/`session
+//! restore`/`session delete` all refuse outright rather than silently
+//! becoming no-ops, since each of those is specifically about writing or
+//! reading something on disk. Every one of those refusals goes through
+//! [`crate::persistence::guard`], so "what can still touch disk in
+//! ephemeral mode" is one function to audit rather than a scattered set
+//! of checks.
+//!
+//! `--keymap <vi|emacs>` loads a preset of short aliases for the commands
+//! above (vi's `j`/`k` for `scroll +1`/`scroll -1`, and so on) — the
+//! closest thing to keybindings a line-oriented REPL can offer without a
+//! raw-terminal dependency to capture single keypresses. `--theme
+//! <monochrome|color|high-contrast>` picks how search highlights,
+//! active-item markers, and errors are rendered (`monochrome`, the
+//! default, is what `orc` printed before themes existed). `--config-file`
+//! points at a JSON file that can override either setting (`{"keys":
+//! {...}, "theme": "..."}`, either section optional); see
+//! [`crate::config`]. Every alias is checked against the real command
+//! list at startup, so a typo in a config file fails before the REPL
+//! starts rather than silently never firing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command as ChildCommand;
+use std::str::SplitWhitespace;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::{Args, ValueEnum};
+
+use crate::commands::common::{KillSwitchArgs, TimeoutArgs};
+use crate::config::{self, KeyMap, Theme};
+use crate::download_crypto::{self, DownloadEncryption};
+use crate::download_verify::{self, DownloadVerification};
+use crate::error::{OrcError, Result};
+use crate::killswitch;
+use crate::net::gemini;
+use crate::net::imageterm::{self, ImageProtocol};
+use crate::net::tcp::{detect_proxy, ConnectOptions, ProxyTarget, SocksAuth};
+use crate::net::tls::{self, PinStore};
+#[cfg(feature = "control-port")]
+use crate::net::torctl::{self, TorControlClient};
+use crate::output;
+use crate::persistence;
+use crate::secret::SensitiveString;
+use crate::session_store::{self, SavedTab};
+
+const DEFAULT_SHOW_LINES: usize = 20;
+const DISPATCHER_IDLE_POLL: Duration = Duration::from_millis(200);
+const SPLIT_COLUMN_WIDTH: usize = 40;
+const READER_WIDTH: usize = 72;
+
+/// Every verb `execute` dispatches on, for validating a custom keymap at
+/// startup — a binding to anything outside this list is a config typo,
+/// not a new command.
+const KNOWN_VERBS: &[&str] = &[
+    "open", "tabs", "switch", "tab", "close", "nav", "back", "forward", "history", "scroll", "show", "find", "n", "N", "submit", "dl", "save", "downloads",
+    "pause", "resume", "open-folder", "status", "newnym", "session", "complete", "headers", "split", "reader", "image", "hints", "goto", "quit",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeymapPreset {
+    /// No aliases: only the full command names work.
+    None,
+    Vi,
+    Emacs,
+}
+
+impl KeymapPreset {
+    fn build(self) -> KeyMap {
+        match self {
+            KeymapPreset::None => KeyMap::default(),
+            KeymapPreset::Vi => config::vi_preset(),
+            KeymapPreset::Emacs => config::emacs_preset(),
+        }
+    }
+}
+
+struct Tab {
+    url: String,
+    rendered: Option<Vec<String>>,
+    scroll: usize,
+    /// Visited URLs, oldest first. Empty when history recording is
+    /// disabled, which also naturally disables `back`/`forward` since
+    /// there's nothing in it to move through.
+    history: Vec<String>,
+    history_pos: usize,
+    /// The active search term and the line indices it matched, set by
+    /// `find` and walked by `n`/`N`. There's no terminal to colour text
+    /// in, so `show` "highlights" a match by wrapping it in `>>>...<<<`
+    /// instead.
+    search: Option<Search>,
+    /// Set when the last fetch came back as a Gemini input request
+    /// (status `1x`) — the protocol's form equivalent, since capsules
+    /// don't carry HTML. Holds the prompt from the response's `meta`
+    /// field; `submit` answers it.
+    pending_input: Option<String>,
+    /// The last response's status and meta, e.g. `20 text/gemini` —
+    /// Gemini's entire "headers" section, since the protocol has nothing
+    /// resembling HTTP's multi-line header block.
+    header: Option<String>,
+    /// The response body's raw lines, before [`gemini::render_gemtext`]
+    /// strips preformat fences — the nearest thing to "HTML source" a
+    /// capsule has, shown by `split` next to the rendered view.
+    source: Option<Vec<String>>,
+    /// Whether `show` prints rendered text side by side with `source`
+    /// instead of just the rendered view. A display preference, not page
+    /// state, so `navigate` leaves it alone.
+    split_view: bool,
+    /// Set once a fetch comes back with a non-`text/*` meta or a body
+    /// that isn't valid UTF-8: `rendered` then holds a hexdump instead of
+    /// gemtext, and `save` (rather than `show`) is the useful action.
+    binary: bool,
+    /// Whether `show` runs `rendered` through [`gemini::reader_mode`]
+    /// first, dropping link lines and reflowing paragraphs for long-form
+    /// reading. A display preference, not page state, so `navigate`
+    /// leaves it alone — same as `split_view`, which it has no effect
+    /// under, since split's raw-source column is meant to stay literal.
+    reader: bool,
+    /// The link URLs `hints` last labelled, indexed by [`hint_code`] so
+    /// `goto` can resolve a typed letter back to a URL. Page state, not a
+    /// display preference, so `navigate` clears it — the old hints no
+    /// longer point at anything on the new page.
+    hints: Vec<String>,
+    /// This tab's own SOCKS isolation credentials (see [`SocksAuth`]),
+    /// assigned once when the tab is opened and kept for its whole
+    /// lifetime so every fetch it makes lands on the same circuit — and,
+    /// since no other tab is ever given the same credentials, never the
+    /// same circuit as theirs.
+    isolation: SocksAuth,
+}
+
+struct Search {
+    term: String,
+    matches: Vec<usize>,
+    pos: usize,
+}
+
+impl Tab {
+    fn new(url: String, record_history: bool, isolation: SocksAuth) -> Self {
+        let history = if record_history { vec![url.clone()] } else { Vec::new() };
+        Tab {
+            url,
+            rendered: None,
+            scroll: 0,
+            history,
+            history_pos: 0,
+            search: None,
+            pending_input: None,
+            header: None,
+            source: None,
+            split_view: false,
+            binary: false,
+            reader: false,
+            hints: Vec::new(),
+            isolation,
+        }
+    }
+
+    /// Navigates to `url`, resetting the render cache, scroll position,
+    /// and any active search or pending input, and recording the jump in
+    /// history if it's being kept.
+    fn navigate(&mut self, url: &str) {
+        self.url = url.to_string();
+        self.rendered = None;
+        self.scroll = 0;
+        self.search = None;
+        self.pending_input = None;
+        self.header = None;
+        self.source = None;
+        self.binary = false;
+        self.hints.clear();
+        if !self.history.is_empty() {
+            self.history.truncate(self.history_pos + 1);
+            self.history.push(url.to_string());
+            self.history_pos = self.history.len() - 1;
+        }
+    }
+}
+
+/// Line numbers (into `lines`) that contain `term` as a substring.
+fn find_matches(lines: &[String], term: &str) -> Vec<usize> {
+    lines.iter().enumerate().filter(|(_, line)| line.contains(term)).map(|(i, _)| i).collect()
+}
+
+/// Marks every occurrence of `term` in `line` using `theme` so a match
+/// stands out.
+fn highlight_matches(line: &str, term: &str, theme: Theme) -> String {
+    if term.is_empty() {
+        return line.to_string();
+    }
+    line.replace(term, &theme.highlight(term))
+}
+
+/// The short letter label `hints` assigns its `index`-th link: `a`, `b`,
+/// ..., `z`, `aa`, `ab`, ... — a spreadsheet-style bijective base-26
+/// count, standing in for vimium's letter overlay since there's no
+/// terminal-control dependency here to actually draw one on top of the
+/// page.
+fn hint_code(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'a' + (index % 26) as u8) as char);
+        index /= 26;
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Finds the index `code` (as produced by [`hint_code`]) refers to among
+/// `hint_count` hints, by regenerating codes rather than inverting the
+/// bijective base-26 math — simple, and `hints` never labels more than a
+/// page's worth of links.
+fn hint_index(hint_count: usize, code: &str) -> Option<usize> {
+    (0..hint_count).find(|&i| hint_code(i) == code)
+}
+
+/// Assigns the next tab's SOCKS isolation credentials. Tor treats two
+/// connections as needing separate circuits whenever they present
+/// different SOCKS5 usernames/passwords ("IsolateSOCKSAuth" in `torrc`,
+/// on by default), so a value that's merely distinct per tab is enough —
+/// it doesn't need to be unguessable, so no random bytes are drawn for
+/// it. A plain per-process counter
+/// fills the same role [`crate::session_store`]'s save salt does.
+fn next_isolation(counter: &mut u64) -> SocksAuth {
+    let id = *counter;
+    *counter += 1;
+    SocksAuth { username: format!("orc-tab-{id}"), password: format!("orc-tab-{id}") }
+}
+
+/// The shared connect options with `tab`'s own isolation credentials
+/// attached, so a fetch made on its behalf lands on a circuit Tor won't
+/// also hand to any other tab — then layers on whatever `hosts.<host>`
+/// override applies to this tab's URL, if any.
+fn tab_connect_options(config: &BrowseConfig, tab: &Tab) -> ConnectOptions {
+    let mut options = config.options.clone();
+    options.auth = Some(tab.isolation.clone());
+
+    let Ok((host, _)) = gemini::parse_url(&tab.url) else {
+        return options;
+    };
+    let Some(override_) = config.hosts.get(host) else {
+        return options;
+    };
+
+    if let Some(seconds) = override_.connect_timeout {
+        options.connect_timeout = Duration::from_secs(seconds);
+    }
+    if let Some(seconds) = override_.read_timeout {
+        options.read_timeout = Some(Duration::from_secs(seconds));
+    }
+    if let Some(seconds) = override_.write_timeout {
+        options.write_timeout = Some(Duration::from_secs(seconds));
+    }
+    if override_.isolate == Some(false) {
+        options.auth = None;
+    }
+    options
+}
+
+/// Asks Tor for a brand-new circuit identity, the same way the `newnym`
+/// command does — used by `--new-circuit-on-open` so a freshly opened
+/// tab doesn't even reuse an existing *pooled* circuit under a nym it
+/// happens to share, on top of that tab's own isolation credentials.
+#[cfg(feature = "control-port")]
+fn request_new_circuit(config: &BrowseConfig) -> Result<()> {
+    let addr = config.control_port.ok_or_else(|| OrcError::InvalidArgument("--new-circuit-on-open needs --control-port".into()))?;
+    let mut client = TorControlClient::connect(addr, config.control_auth())?;
+    client.signal_newnym()
+}
+
+#[cfg(not(feature = "control-port"))]
+fn request_new_circuit(_config: &BrowseConfig) -> Result<()> {
+    Err(OrcError::InvalidArgument("--new-circuit-on-open needs the control-port feature, which this build was compiled without".into()))
+}
+
+/// How a queued download is getting on. There's no byte-level progress
+/// here because [`gemini::fetch`] doesn't stream — a download is either
+/// waiting, running, or finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DownloadStatus {
+    Queued,
+    Paused,
+    Downloading,
+    Completed { bytes: usize },
+    Failed(String),
+}
+
+impl std::fmt::Display for DownloadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadStatus::Queued => write!(f, "queued"),
+            DownloadStatus::Paused => write!(f, "paused"),
+            DownloadStatus::Downloading => write!(f, "downloading"),
+            DownloadStatus::Completed { bytes } => write!(f, "done ({bytes} bytes)"),
+            DownloadStatus::Failed(err) => write!(f, "failed: {err}"),
+        }
+    }
+}
+
+struct DownloadEntry {
+    url: String,
+    dest: PathBuf,
+    status: DownloadStatus,
+    encryption: DownloadEncryption,
+    verification: DownloadVerification,
+}
+
+type Downloads = Arc<Mutex<Vec<DownloadEntry>>>;
+
+/// Picks the file name a download is saved under when the user doesn't
+/// give one explicitly: the last non-empty path segment of the URL, or
+/// `download` if there isn't one (e.g. the URL is just a bare capsule
+/// root).
+fn default_download_name(url: &str) -> String {
+    url.rsplit('/').find(|segment| !segment.is_empty()).unwrap_or("download").to_string()
+}
+
+/// Runs forever in the background, pulling the next queued download off
+/// the list and fetching it. Started lazily on the first `dl`, and never
+/// joined: it lives for as long as the process does and simply stops
+/// mattering once `main` returns.
+fn spawn_dispatcher(downloads: Downloads, proxy: SocketAddr, pin_store: PinStore, options: ConnectOptions) {
+    thread::spawn(move || loop {
+        let next = {
+            let mut list = downloads.lock().unwrap();
+            let index = list.iter().position(|entry| entry.status == DownloadStatus::Queued);
+            index.map(|i| {
+                list[i].status = DownloadStatus::Downloading;
+                (i, list[i].url.clone(), list[i].dest.clone(), list[i].encryption.clone(), list[i].verification.clone())
+            })
+        };
+
+        let Some((index, url, dest, encryption, verification)) = next else {
+            thread::sleep(DISPATCHER_IDLE_POLL);
+            continue;
+        };
+
+        let result = gemini::fetch(proxy, &url, &pin_store, &options).and_then(|response| {
+            let bytes = response.body.len();
+            fs::write(&dest, download_crypto::apply(&response.body, &encryption))?;
+            if let Err(err) = download_verify::verify(&response.body, &verification) {
+                killswitch::secure_wipe_file(&dest)?;
+                return Err(err);
+            }
+            Ok(bytes)
+        });
+
+        let mut list = downloads.lock().unwrap();
+        list[index].status = match result {
+            Ok(bytes) => DownloadStatus::Completed { bytes },
+            Err(err) => DownloadStatus::Failed(err.to_string()),
+        };
+    });
+}
+
+/// Queues `url` for background download under `dest` (or a name derived
+/// from the URL), starting the dispatcher thread on the first call.
+/// Shared by `dl` and `save`, the hex viewer's "save as" action.
+fn queue_download(
+    state: &mut BrowseState,
+    config: &BrowseConfig,
+    url: String,
+    dest: Option<PathBuf>,
+    encryption: DownloadEncryption,
+    verification: DownloadVerification,
+) {
+    let dest = dest.unwrap_or_else(|| PathBuf::from(default_download_name(&url)));
+
+    if !state.dispatcher_started {
+        spawn_dispatcher(state.downloads.clone(), config.proxy, config.pin_store.clone(), config.options.clone());
+        state.dispatcher_started = true;
+    }
+
+    let mut downloads = state.downloads.lock().unwrap();
+    downloads.push(DownloadEntry { url, dest: dest.clone(), status: DownloadStatus::Queued, encryption, verification });
+    println!("queued download {}: {}", downloads.len() - 1, dest.display());
+}
+
+#[derive(Debug, Args)]
+pub struct BrowseArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Reach the SOCKS proxy at this address or Unix domain socket path
+    /// instead, skipping both `--proxy` and any config file `proxies`
+    /// candidate list entirely.
+    #[arg(long)]
+    pub socks: Option<String>,
+
+    /// Capsule to open in the first tab.
+    pub url: Option<String>,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[cfg_attr(feature = "keyring-backend", arg(long, conflicts_with = "pin_keyring"))]
+    #[cfg_attr(not(feature = "keyring-backend"), arg(long))]
+    pub pin_file: Option<PathBuf>,
+
+    /// Keep trust-on-first-use certificate pins in the platform keyring
+    /// instead of a file.
+    #[cfg(feature = "keyring-backend")]
+    #[arg(long, conflicts_with_all = ["pin_file", "ephemeral"])]
+    pub pin_keyring: bool,
+
+    /// Don't keep a back/forward history for any tab.
+    #[arg(long)]
+    pub no_history: bool,
+
+    /// Allow `open-folder` to shell out and open a download's containing
+    /// folder. Off by default since it runs an external program.
+    #[arg(long)]
+    pub allow_open_folder: bool,
+
+    /// Address of Tor's control port, for the `status` command. Reached
+    /// directly, not through `--proxy`. Needs the control-port feature.
+    #[cfg(feature = "control-port")]
+    #[arg(long)]
+    pub control_port: Option<SocketAddr>,
+
+    /// Read the control port password from stdin instead of using cookie
+    /// or NULL authentication.
+    #[cfg(feature = "control-port")]
+    #[arg(long)]
+    pub control_password_stdin: bool,
+
+    /// Authenticate to the control port with the cookie at this path
+    /// (`torrc`'s `CookieAuthFile`) instead of a password.
+    #[cfg(feature = "control-port")]
+    #[arg(long)]
+    pub control_cookie_file: Option<PathBuf>,
+
+    /// Short aliases to resolve before a line is dispatched as a command,
+    /// e.g. vi's `j`/`k` for `scroll +1`/`scroll -1`.
+    #[arg(long, value_enum, default_value_t = KeymapPreset::None)]
+    pub keymap: KeymapPreset,
+
+    /// Colour scheme for search highlights, active-item markers, and
+    /// errors.
+    #[arg(long, value_enum, default_value_t = Theme::Monochrome)]
+    pub theme: Theme,
+
+    /// A JSON config file (`{"keys": {...}, "theme": "...", "images":
+    /// true, "proxies": [...], "hosts": {...}}`) layered on top of
+    /// `--keymap`'s preset, `--theme`, and `--images`; any section may be
+    /// omitted.
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+
+    /// Allow `image` to fetch a capsule's linked images and render them
+    /// inline. Off by default, and only a config file's `"images": true`
+    /// can turn it on otherwise — fetching image subresources changes
+    /// what `orc browse` reveals about its traffic, so it's never the
+    /// default and never inferred from a terminal capability check.
+    #[arg(long)]
+    pub images: bool,
+
+    /// Which terminal graphics protocol `image` encodes pictures for.
+    #[arg(long, value_enum, default_value_t = ImageProtocol::Kitty)]
+    pub image_protocol: ImageProtocol,
+
+    /// Ask Tor for a brand-new circuit identity every time a tab is
+    /// opened, on top of that tab's own SOCKS isolation credentials.
+    /// Needs --control-port.
+    #[arg(long)]
+    pub new_circuit_on_open: bool,
+
+    /// Never create or read a file: TLS pins live only in memory instead
+    /// of `--pin-file`, `--config-file` is refused rather than read, and
+    /// `dl`/`save`/`session save`/`session restore`/`session delete` all
+    /// refuse rather than silently doing nothing. See
+    /// [`crate::persistence::guard`].
+    #[arg(long)]
+    pub ephemeral: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+
+    #[command(flatten)]
+    pub kill_switch: KillSwitchArgs,
+}
+
+/// Config for the life of the REPL — mostly fixed once the REPL starts,
+/// except `hosts`, which [`reload_hosts`] refreshes from `config_file` as
+/// it's edited on disk. See this module's doc comment for why only
+/// `hosts` gets that treatment.
+struct BrowseConfig {
+    proxy: SocketAddr,
+    pin_store: PinStore,
+    options: ConnectOptions,
+    record_history: bool,
+    allow_open_folder: bool,
+    #[cfg(feature = "control-port")]
+    control_port: Option<SocketAddr>,
+    #[cfg(feature = "control-port")]
+    control_password: Option<SensitiveString>,
+    #[cfg(feature = "control-port")]
+    control_cookie_file: Option<PathBuf>,
+    keymap: KeyMap,
+    theme: Theme,
+    images: bool,
+    image_protocol: ImageProtocol,
+    new_circuit_on_open: bool,
+    /// Per-host overrides from a config file's `"hosts"` section, applied
+    /// in [`tab_connect_options`] (and seeded into the pin store up front
+    /// in [`run`] for any host with a `"pin"` set). Kept fresh by
+    /// [`reload_hosts`] for the rest of the REPL's life.
+    hosts: HashMap<String, config::HostOverride>,
+    /// Whether `--ephemeral` is active; every disk-touching command verb
+    /// checks this through [`persistence::guard`] before doing anything.
+    ephemeral: bool,
+    /// Where `hosts` came from, watched by [`reload_hosts`]. `None` when
+    /// no `--config-file` was given, or `--ephemeral` refused one — there's
+    /// nothing to watch either way.
+    config_file: Option<PathBuf>,
+}
+
+#[cfg(feature = "control-port")]
+impl BrowseConfig {
+    fn control_auth(&self) -> torctl::Auth<'_> {
+        match (&self.control_password, &self.control_cookie_file) {
+            (Some(password), _) => torctl::Auth::Password(password),
+            (None, Some(cookie_file)) => torctl::Auth::CookieFile(cookie_file),
+            (None, None) => torctl::Auth::Null,
+        }
+    }
+}
+
+/// Everything the REPL mutates as commands come in.
+struct BrowseState {
+    tabs: Vec<Tab>,
+    active: Option<usize>,
+    downloads: Downloads,
+    dispatcher_started: bool,
+    /// How many tabs have ever been opened in this process, used to hand
+    /// each new one a distinct [`SocksAuth`] via [`next_isolation`].
+    next_tab_id: u64,
+}
+
+pub fn run(args: BrowseArgs) -> Result<()> {
+    #[cfg(feature = "control-port")]
+    let control_password = if args.control_password_stdin {
+        let mut prompt_stdin = io::stdin().lock();
+        Some(read_passphrase(&mut prompt_stdin, "control port password")?)
+    } else {
+        None
+    };
+
+    if let Some(socks) = &args.socks {
+        match socks.parse::<SocketAddr>() {
+            Ok(_) => std::env::set_var("ORC_SOCKS_ADDR", socks),
+            Err(_) => std::env::set_var("ORC_SOCKS_SOCKET", socks),
+        }
+    }
+
+    let mut keymap = args.keymap.build();
+    let mut theme = args.theme;
+    let mut images = args.images;
+
+    // Loaded from `--config-file` if given, otherwise just the built-in
+    // default — either way, `ORC_*` overrides (see
+    // `config::apply_env_overrides`) are layered on top, so a
+    // containerized deployment can configure `orc browse` without a
+    // config file on disk at all. `--ephemeral` refuses a `--config-file`
+    // outright rather than silently ignoring it, since passing one is an
+    // explicit request to read something off disk.
+    let file_config = match &args.config_file {
+        Some(config_file) => {
+            persistence::guard(args.ephemeral, "--config-file")?;
+            config::load(config_file)?
+        }
+        None => config::ConfigFile::default(),
+    };
+    let loaded = config::apply_env_overrides(file_config)?;
+
+    keymap.merge(loaded.keymap);
+    if let Some(file_theme) = loaded.theme {
+        theme = file_theme;
+    }
+    if let Some(file_images) = loaded.images {
+        images = images || file_images;
+    }
+    if let Some(socket_path) = loaded.socks_socket {
+        if std::env::var_os("ORC_SOCKS_SOCKET").is_none() {
+            std::env::set_var("ORC_SOCKS_SOCKET", socket_path);
+        }
+    }
+    // `--socks` and `socks_socket` both pin down one specific proxy; only
+    // fall back to probing the candidate list when neither has already
+    // settled the question.
+    if !loaded.proxies.is_empty() && std::env::var_os("ORC_SOCKS_SOCKET").is_none() && std::env::var_os("ORC_SOCKS_ADDR").is_none() {
+        if let Some(candidate) = detect_proxy(&loaded.proxies) {
+            match &candidate.target {
+                ProxyTarget::Tcp(addr) => std::env::set_var("ORC_SOCKS_ADDR", addr.to_string()),
+                ProxyTarget::Unix(path) => std::env::set_var("ORC_SOCKS_SOCKET", path),
+            }
+        }
+    }
+    keymap.validate(KNOWN_VERBS)?;
+
+    // `--ephemeral` keeps pins in memory for the life of the process
+    // instead of at `--pin-file`, so nothing ever hits disk even on a
+    // first connection to a never-before-seen host. `--pin-keyring`
+    // (mutually exclusive with both) keeps them in the platform keyring.
+    #[cfg(feature = "keyring-backend")]
+    let pin_store = if args.pin_keyring {
+        PinStore::Keyring
+    } else if args.ephemeral {
+        PinStore::memory()
+    } else {
+        PinStore::File(args.pin_file.unwrap_or_else(tls::default_pin_file))
+    };
+    #[cfg(not(feature = "keyring-backend"))]
+    let pin_store = if args.ephemeral {
+        PinStore::memory()
+    } else {
+        PinStore::File(args.pin_file.unwrap_or_else(tls::default_pin_file))
+    };
+    // Seeded ahead of any connection so a configured pin is checked
+    // against from the start, rather than trusting whatever certificate
+    // the host happens to present first.
+    for (host, override_) in &loaded.hosts {
+        if let Some(pin) = &override_.pin {
+            tls::seed_pin(&pin_store, host, pin)?;
+        }
+    }
+
+    let mut config = BrowseConfig {
+        proxy: args.proxy,
+        pin_store,
+        options: args.timeouts.to_connect_options(),
+        record_history: !args.no_history,
+        allow_open_folder: args.allow_open_folder,
+        #[cfg(feature = "control-port")]
+        control_port: args.control_port,
+        #[cfg(feature = "control-port")]
+        control_password,
+        #[cfg(feature = "control-port")]
+        control_cookie_file: args.control_cookie_file,
+        keymap,
+        theme,
+        images,
+        image_protocol: args.image_protocol,
+        new_circuit_on_open: args.new_circuit_on_open,
+        hosts: loaded.hosts,
+        ephemeral: args.ephemeral,
+        config_file: args.config_file,
+    };
+    let kill_switch = args.kill_switch.kill_switch;
+    // Recorded now so the first hot-reload check in the loop below sees
+    // "unchanged" rather than immediately reloading what was just loaded.
+    let mut hosts_mtime = config.config_file.as_deref().and_then(file_mtime);
+
+    let mut state = BrowseState { tabs: Vec::new(), active: None, downloads: Arc::new(Mutex::new(Vec::new())), dispatcher_started: false, next_tab_id: 0 };
+    if let Some(url) = args.url {
+        if config.new_circuit_on_open {
+            request_new_circuit(&config)?;
+        }
+        let isolation = next_isolation(&mut state.next_tab_id);
+        state.tabs.push(Tab::new(url, config.record_history, isolation));
+        state.active = Some(0);
+    }
+
+    // Held for the whole REPL loop (rather than re-locked per line) so
+    // that a command needing more input of its own — `session save`'s
+    // passphrase prompt — can read the next line straight off the same
+    // locked handle instead of trying to lock stdin a second time, which
+    // would deadlock against the lock this loop is already holding.
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+    loop {
+        let mut raw = String::new();
+        if stdin.read_line(&mut raw)? == 0 {
+            break;
+        }
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // An optional leading `:` is accepted (but not required, since
+        // every line here is already a command) for anyone used to an
+        // ex-style command line — `:open url` and `open url` behave
+        // identically.
+        let line = line.strip_prefix(':').unwrap_or(line).trim();
+        if line == kill_switch {
+            let mut paths: Vec<&Path> = match &config.pin_store {
+                PinStore::File(path) => vec![path.as_path()],
+                PinStore::Memory(_) => Vec::new(),
+                #[cfg(feature = "keyring-backend")]
+                PinStore::Keyring => Vec::new(),
+            };
+            let wipe_paths = killswitch::load_wipe_paths()?;
+            paths.extend(wipe_paths.iter().map(PathBuf::as_path));
+            killswitch::trigger(&mut [], &paths);
+        }
+        if line == "quit" {
+            break;
+        }
+
+        if let Some(path) = config.config_file.clone() {
+            match reload_hosts(&path, &mut hosts_mtime) {
+                Ok(Some(hosts)) => {
+                    for (host, override_) in &hosts {
+                        if let Some(pin) = &override_.pin {
+                            tls::seed_pin(&config.pin_store, host, pin)?;
+                        }
+                    }
+                    config.hosts = hosts;
+                    eprintln!("reloaded host overrides from {}", path.display());
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("{}", config.theme.error(&format!("not reloading {}: {err}", path.display()))),
+            }
+        }
+
+        let resolved = config.keymap.resolve_line(line);
+        if let Err(err) = execute(&resolved, &mut state, &config, &mut stdin) {
+            eprintln!("{}", config.theme.error(&format!("orc: {err}")));
+        }
+    }
+
+    Ok(())
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Re-reads `path`'s `"hosts"` section if its modified time has moved on
+/// since `previous_mtime`, returning the new map (and updating
+/// `previous_mtime`) — `None` means nothing to do, either because the
+/// file hasn't changed or its mtime isn't readable at all (a filesystem
+/// without mtime support, say; silently skipping is the same as never
+/// having polled in that case, rather than reloading every single line).
+/// Goes through the same [`config::load`] every other reader of this file
+/// does, so a host override typo is rejected with the same message it
+/// would get at startup instead of a different one on reload.
+fn reload_hosts(path: &Path, previous_mtime: &mut Option<SystemTime>) -> Result<Option<HashMap<String, config::HostOverride>>> {
+    let mtime = file_mtime(path);
+    if mtime.is_none() || mtime == *previous_mtime {
+        return Ok(None);
+    }
+    *previous_mtime = mtime;
+    let loaded = config::load(path)?;
+    Ok(Some(loaded.hosts))
+}
+
+fn read_passphrase(stdin: &mut impl BufRead, prompt: &str) -> Result<SensitiveString> {
+    eprint!("{prompt}: ");
+    let mut line = String::new();
+    stdin.read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+/// Parses `dl`/`save`'s trailing `[--passphrase-stdin | --encrypt-to
+/// <age1...>] [--sha256 <hash> | --verify-minisign <pubkey>]` tokens into
+/// a [`DownloadEncryption`] and a [`DownloadVerification`]. Reads the
+/// passphrase off the REPL's own already-locked `stdin` (same reasoning
+/// as [`read_passphrase`]) rather than through
+/// [`download_crypto::resolve`]'s own prompt, which would try to lock
+/// stdin a second time and deadlock.
+fn resolve_download_encryption(
+    parts: &mut std::str::SplitWhitespace<'_>,
+    stdin: &mut impl BufRead,
+) -> Result<(DownloadEncryption, DownloadVerification)> {
+    let mut passphrase_stdin = false;
+    let mut encrypt_to = None;
+    let mut sha256 = None;
+    let mut verify_minisign = None;
+    while let Some(token) = parts.next() {
+        match token {
+            "--passphrase-stdin" => passphrase_stdin = true,
+            "--encrypt-to" => encrypt_to = parts.next(),
+            "--sha256" => sha256 = parts.next(),
+            "--verify-minisign" => verify_minisign = parts.next(),
+            other => return Err(OrcError::InvalidArgument(format!("unexpected argument `{other}`"))),
+        }
+    }
+
+    let encryption = if let Some(recipient) = encrypt_to {
+        download_crypto::resolve(false, Some(recipient))?
+    } else if passphrase_stdin {
+        DownloadEncryption::Passphrase(read_passphrase(stdin, "download passphrase")?)
+    } else {
+        DownloadEncryption::None
+    };
+    let verification = download_verify::resolve(sha256, verify_minisign)?;
+    Ok((encryption, verification))
+}
+
+fn execute(line: &str, state: &mut BrowseState, config: &BrowseConfig, stdin: &mut impl BufRead) -> Result<()> {
+    let mut parts = line.split_whitespace();
+    let verb = parts.next().ok_or_else(|| OrcError::InvalidArgument("empty command".into()))?;
+    let tabs = &mut state.tabs;
+    let active = &mut state.active;
+
+    match verb {
+        "open" => {
+            let url = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: open <url>".into()))?;
+            if config.new_circuit_on_open {
+                request_new_circuit(config)?;
+            }
+            let isolation = next_isolation(&mut state.next_tab_id);
+            tabs.push(Tab::new(url.to_string(), config.record_history, isolation));
+            *active = Some(tabs.len() - 1);
+            println!("opened tab {}: {url}", tabs.len() - 1);
+            Ok(())
+        }
+        "tabs" => {
+            for (i, tab) in tabs.iter().enumerate() {
+                let marker = if *active == Some(i) { config.theme.accent("*") } else { " ".to_string() };
+                println!("{marker}{i}: {} [{}]", tab.url, tab.isolation.username);
+            }
+            Ok(())
+        }
+        "switch" | "tab" => {
+            *active = Some(parse_index(&mut parts, tabs.len())?);
+            Ok(())
+        }
+        "close" => {
+            let index = parse_index(&mut parts, tabs.len())?;
+            tabs.remove(index);
+            *active = reindex_after_close(*active, index, tabs.len());
+            Ok(())
+        }
+        "nav" => {
+            let url = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: nav <url>".into()))?.to_string();
+            active_tab_mut(tabs, *active)?.navigate(&url);
+            Ok(())
+        }
+        "back" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            if tab.history_pos == 0 {
+                return Err(OrcError::InvalidArgument("no earlier entry in this tab's history".into()));
+            }
+            tab.history_pos -= 1;
+            tab.url = tab.history[tab.history_pos].clone();
+            tab.rendered = None;
+            tab.header = None;
+            tab.source = None;
+            tab.binary = false;
+            tab.hints.clear();
+            tab.scroll = 0;
+            Ok(())
+        }
+        "forward" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            if tab.history_pos + 1 >= tab.history.len() {
+                return Err(OrcError::InvalidArgument("no later entry in this tab's history".into()));
+            }
+            tab.history_pos += 1;
+            tab.url = tab.history[tab.history_pos].clone();
+            tab.rendered = None;
+            tab.header = None;
+            tab.source = None;
+            tab.binary = false;
+            tab.hints.clear();
+            tab.scroll = 0;
+            Ok(())
+        }
+        "history" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            for (i, url) in tab.history.iter().enumerate() {
+                let marker = if i == tab.history_pos { config.theme.accent("*") } else { " ".to_string() };
+                println!("{marker}{i}: {url}");
+            }
+            Ok(())
+        }
+        "scroll" => {
+            let delta: i64 = parts
+                .next()
+                .and_then(|raw| raw.parse().ok())
+                .ok_or_else(|| OrcError::InvalidArgument("usage: scroll <+n|-n>".into()))?;
+            let tab = active_tab_mut(tabs, *active)?;
+            tab.scroll = (tab.scroll as i64 + delta).max(0) as usize;
+            Ok(())
+        }
+        "show" => {
+            let lines = parts.next().and_then(|raw| raw.parse().ok()).unwrap_or(DEFAULT_SHOW_LINES);
+            let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+            let options = tab_connect_options(config, &tabs[index]);
+            ensure_fetched(tabs, index, config.proxy, &config.pin_store, &options)?;
+            let tab = &tabs[index];
+            let term = tab.search.as_ref().map(|search| search.term.as_str()).unwrap_or("");
+            let rendered = tab.rendered.as_ref().unwrap();
+            if tab.split_view {
+                if let Some(header) = &tab.header {
+                    println!("-- headers: {header} --");
+                }
+                let source = tab.source.as_deref().unwrap_or(&[]);
+                println!("{:<SPLIT_COLUMN_WIDTH$} | source", "rendered");
+                for (i, rendered_line) in rendered.iter().enumerate().skip(tab.scroll).take(lines) {
+                    let left = highlight_matches(rendered_line, term, config.theme);
+                    let right = source.get(i).map(String::as_str).unwrap_or("");
+                    println!("{left:<SPLIT_COLUMN_WIDTH$} | {right}");
+                }
+            } else {
+                let reader_lines = tab.reader.then(|| gemini::reader_mode(rendered, READER_WIDTH));
+                let display = reader_lines.as_deref().unwrap_or(rendered);
+                for line in display.iter().skip(tab.scroll).take(lines) {
+                    println!("{}", highlight_matches(line, term, config.theme));
+                }
+            }
+            Ok(())
+        }
+        "headers" => {
+            let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+            let options = tab_connect_options(config, &tabs[index]);
+            ensure_fetched(tabs, index, config.proxy, &config.pin_store, &options)?;
+            match &tabs[index].header {
+                Some(header) => println!("{header}"),
+                None => println!("(no response yet)"),
+            }
+            Ok(())
+        }
+        "split" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            tab.split_view = !tab.split_view;
+            println!("split view {} for this tab", if tab.split_view { "on" } else { "off" });
+            Ok(())
+        }
+        "reader" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            tab.reader = !tab.reader;
+            println!("reader mode {} for this tab", if tab.reader { "on" } else { "off" });
+            Ok(())
+        }
+        "hints" => {
+            let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+            let options = tab_connect_options(config, &tabs[index]);
+            ensure_fetched(tabs, index, config.proxy, &config.pin_store, &options)?;
+            let tab = &mut tabs[index];
+            let rendered = tab.rendered.as_ref().unwrap();
+            let links: Vec<(String, String)> = rendered.iter().filter_map(|line| gemini::parse_link_line(line)).collect();
+            if links.is_empty() {
+                tab.hints.clear();
+                return Err(OrcError::InvalidArgument("no links on this page".into()));
+            }
+            for (i, (url, label)) in links.iter().enumerate() {
+                println!("[{}] {label} -> {url}", config.theme.accent(&hint_code(i)));
+            }
+            tab.hints = links.into_iter().map(|(url, _)| url).collect();
+            Ok(())
+        }
+        "goto" => {
+            let hint = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: goto <hint> (see `hints`)".into()))?;
+            let tab = active_tab_mut(tabs, *active)?;
+            let index = hint_index(tab.hints.len(), hint).ok_or_else(|| OrcError::InvalidArgument(format!("no hint `{hint}`; run `hints` first")))?;
+            let url = tab.hints[index].clone();
+            tab.navigate(&url);
+            Ok(())
+        }
+        "find" => {
+            let term = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: find <term>".into()))?.to_string();
+            let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+            let options = tab_connect_options(config, &tabs[index]);
+            ensure_fetched(tabs, index, config.proxy, &config.pin_store, &options)?;
+            let tab = &mut tabs[index];
+            let matches = find_matches(tab.rendered.as_ref().unwrap(), &term);
+            if matches.is_empty() {
+                tab.search = None;
+                return Err(OrcError::InvalidArgument(format!("no match for `{term}`")));
+            }
+            tab.scroll = matches[0];
+            println!("{} match(es) for `{term}`", matches.len());
+            tab.search = Some(Search { term, matches, pos: 0 });
+            Ok(())
+        }
+        "n" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            let search = tab.search.as_mut().ok_or_else(|| OrcError::InvalidArgument("no active search; use `find` first".into()))?;
+            search.pos = (search.pos + 1) % search.matches.len();
+            tab.scroll = tab.search.as_ref().unwrap().matches[tab.search.as_ref().unwrap().pos];
+            Ok(())
+        }
+        "N" => {
+            let tab = active_tab_mut(tabs, *active)?;
+            let search = tab.search.as_mut().ok_or_else(|| OrcError::InvalidArgument("no active search; use `find` first".into()))?;
+            search.pos = if search.pos == 0 { search.matches.len() - 1 } else { search.pos - 1 };
+            tab.scroll = tab.search.as_ref().unwrap().matches[tab.search.as_ref().unwrap().pos];
+            Ok(())
+        }
+        "submit" => {
+            let text: Vec<&str> = parts.collect();
+            if text.is_empty() {
+                return Err(OrcError::InvalidArgument("usage: submit <text>".into()));
+            }
+            let tab = active_tab_mut(tabs, *active)?;
+            if tab.pending_input.is_none() {
+                return Err(OrcError::InvalidArgument("active tab has no pending input request".into()));
+            }
+            let url = gemini::build_query_url(&tab.url, &text.join(" "));
+            tab.navigate(&url);
+            Ok(())
+        }
+        "dl" => {
+            persistence::guard(config.ephemeral, "dl")?;
+            let url = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: dl <url> [dest] [--passphrase-stdin | --encrypt-to <age1...>] [--sha256 <hash> | --verify-minisign <pubkey>]".into()))?.to_string();
+            let dest = parts.next().map(PathBuf::from);
+            let (encryption, verification) = resolve_download_encryption(&mut parts, stdin)?;
+            queue_download(state, config, url, dest, encryption, verification);
+            Ok(())
+        }
+        "save" => {
+            persistence::guard(config.ephemeral, "save")?;
+            let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+            let url = tabs[index].url.clone();
+            let dest = parts.next().map(PathBuf::from);
+            let (encryption, verification) = resolve_download_encryption(&mut parts, stdin)?;
+            queue_download(state, config, url, dest, encryption, verification);
+            Ok(())
+        }
+        "image" => {
+            if !config.images {
+                return Err(OrcError::InvalidArgument("image rendering is disabled; enable it with --images or \"images\": true in a config file".into()));
+            }
+            let url = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: image <url>".into()))?;
+            let response = gemini::fetch(config.proxy, url, &config.pin_store, &config.options)?;
+            println!("{}", imageterm::render(config.image_protocol, &response.body));
+            Ok(())
+        }
+        "downloads" => {
+            let downloads = state.downloads.lock().unwrap();
+            for (i, entry) in downloads.iter().enumerate() {
+                println!("{i}: {} -> {} [{}]", entry.url, entry.dest.display(), entry.status);
+            }
+            Ok(())
+        }
+        "pause" => {
+            let mut downloads = state.downloads.lock().unwrap();
+            let index = parse_index(&mut parts, downloads.len())?;
+            if downloads[index].status != DownloadStatus::Queued {
+                return Err(OrcError::InvalidArgument("only a queued download can be paused".into()));
+            }
+            downloads[index].status = DownloadStatus::Paused;
+            Ok(())
+        }
+        "resume" => {
+            let mut downloads = state.downloads.lock().unwrap();
+            let index = parse_index(&mut parts, downloads.len())?;
+            if downloads[index].status != DownloadStatus::Paused {
+                return Err(OrcError::InvalidArgument("only a paused download can be resumed".into()));
+            }
+            downloads[index].status = DownloadStatus::Queued;
+            Ok(())
+        }
+        "open-folder" => {
+            if !config.allow_open_folder {
+                return Err(OrcError::InvalidArgument("opening folders is disabled; pass --allow-open-folder to enable it".into()));
+            }
+            let downloads = state.downloads.lock().unwrap();
+            let index = parse_index(&mut parts, downloads.len())?;
+            let folder = downloads[index].dest.parent().unwrap_or(Path::new("."));
+            ChildCommand::new("xdg-open").arg(folder).status()?;
+            Ok(())
+        }
+        #[cfg(feature = "control-port")]
+        "status" => {
+            let addr = config.control_port.ok_or_else(|| OrcError::InvalidArgument("status needs --control-port".into()))?;
+            let mut client = TorControlClient::connect(addr, config.control_auth())?;
+            let status = client.status()?;
+            let circuit = if status.circuit_established { "established" } else { "not yet established" };
+            println!("tor {} | socks {} | circuit {circuit} | read {}B | written {}B", status.version, status.socks_listeners, status.bytes_read, status.bytes_written);
+            Ok(())
+        }
+        #[cfg(not(feature = "control-port"))]
+        "status" => Err(OrcError::InvalidArgument("status needs the control-port feature, which this build was compiled without".into())),
+        #[cfg(feature = "control-port")]
+        "newnym" => {
+            let addr = config.control_port.ok_or_else(|| OrcError::InvalidArgument("newnym needs --control-port".into()))?;
+            let mut client = TorControlClient::connect(addr, config.control_auth())?;
+            client.signal_newnym()?;
+            println!("requested a new circuit identity");
+            Ok(())
+        }
+        #[cfg(not(feature = "control-port"))]
+        "newnym" => Err(OrcError::InvalidArgument("newnym needs the control-port feature, which this build was compiled without".into())),
+        "session" => {
+            let sub = parts.next().ok_or_else(|| OrcError::InvalidArgument("usage: session <save|restore|delete> <path>".into()))?;
+            let path = parts
+                .next()
+                .map(PathBuf::from)
+                .ok_or_else(|| OrcError::InvalidArgument("usage: session <save|restore|delete> <path>".into()))?;
+            match sub {
+                "save" => {
+                    persistence::guard(config.ephemeral, "session save")?;
+                    let passphrase = read_passphrase(stdin, "session passphrase")?;
+                    let duress_passphrase = read_passphrase(stdin, "duress passphrase (blank for none)")?;
+                    let duress_passphrase = if duress_passphrase.as_str().is_empty() { None } else { Some(&duress_passphrase) };
+                    let saved: Vec<SavedTab> = tabs.iter().map(|tab| SavedTab { url: tab.url.clone(), scroll: tab.scroll }).collect();
+                    let count = saved.len();
+                    session_store::save(&path, &saved, &passphrase, duress_passphrase)?;
+                    println!("saved {count} tab(s) to {}", path.display());
+                    Ok(())
+                }
+                "restore" => {
+                    persistence::guard(config.ephemeral, "session restore")?;
+                    let passphrase = read_passphrase(stdin, "session passphrase")?;
+                    let restored = session_store::load(&path, &passphrase)?;
+                    let mut restored_tabs = Vec::with_capacity(restored.len());
+                    for saved in restored {
+                        let isolation = next_isolation(&mut state.next_tab_id);
+                        let mut tab = Tab::new(saved.url, config.record_history, isolation);
+                        tab.scroll = saved.scroll;
+                        restored_tabs.push(tab);
+                    }
+                    *tabs = restored_tabs;
+                    *active = if tabs.is_empty() { None } else { Some(0) };
+                    println!("restored {} tab(s) from {}", tabs.len(), path.display());
+                    Ok(())
+                }
+                "delete" => {
+                    persistence::guard(config.ephemeral, "session delete")?;
+                    killswitch::secure_wipe_file(&path)?;
+                    println!("wiped {}", path.display());
+                    Ok(())
+                }
+                other => Err(OrcError::InvalidArgument(format!("unknown session action `{other}`; expected save, restore, or delete"))),
+            }
+        }
+        "complete" => {
+            let prefix = parts.next().unwrap_or("");
+            let matches: Vec<&&str> = KNOWN_VERBS.iter().filter(|verb| verb.starts_with(prefix)).collect();
+            for verb in &matches {
+                println!("{verb}");
+            }
+            if matches.is_empty() {
+                return Err(OrcError::InvalidArgument(format!("no command starts with `{prefix}`")));
+            }
+            Ok(())
+        }
+        other => Err(OrcError::InvalidArgument(format!("unknown browse command `{other}`"))),
+    }
+}
+
+fn parse_index(parts: &mut SplitWhitespace<'_>, len: usize) -> Result<usize> {
+    let index: usize = parts.next().and_then(|raw| raw.parse().ok()).ok_or_else(|| OrcError::InvalidArgument("expected a tab index".into()))?;
+    if index >= len {
+        return Err(OrcError::InvalidArgument(format!("no tab {index}")));
+    }
+    Ok(index)
+}
+
+fn active_tab_mut(tabs: &mut [Tab], active: Option<usize>) -> Result<&mut Tab> {
+    let index = active.ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))?;
+    tabs.get_mut(index).ok_or_else(|| OrcError::InvalidArgument("no active tab".into()))
+}
+
+/// Works out the new active tab index after closing `closed`, given the
+/// tab list's length once it's gone: the active tab shifts down by one
+/// if it came after the closed tab, stays put if it came before, and
+/// falls back to whatever now sits at `closed`'s old position (or `None`
+/// if that was the last tab) if it was the one that got closed.
+fn reindex_after_close(active: Option<usize>, closed: usize, remaining_len: usize) -> Option<usize> {
+    match active {
+        Some(a) if a == closed => {
+            if remaining_len == 0 {
+                None
+            } else {
+                Some(closed.min(remaining_len - 1))
+            }
+        }
+        Some(a) if a > closed => Some(a - 1),
+        other => other,
+    }
+}
+
+fn ensure_fetched(tabs: &mut [Tab], index: usize, proxy: SocketAddr, pin_store: &PinStore, options: &ConnectOptions) -> Result<()> {
+    if tabs[index].rendered.is_some() {
+        return Ok(());
+    }
+    let response = gemini::fetch(proxy, &tabs[index].url, pin_store, options)?;
+    tabs[index].header = Some(format!("{} {}", response.status, response.meta));
+    if gemini::is_input_status(response.status) {
+        tabs[index].pending_input = Some(response.meta.clone());
+        tabs[index].rendered = Some(vec![format!("[input requested] {}", response.meta)]);
+        tabs[index].source = Some(Vec::new());
+        return Ok(());
+    }
+    tabs[index].pending_input = None;
+    if is_binary_response(&response.meta, &response.body) {
+        tabs[index].binary = true;
+        tabs[index].source = Some(Vec::new());
+        tabs[index].rendered = Some(output::hexdump(&response.body).lines().map(str::to_string).collect());
+        return Ok(());
+    }
+    tabs[index].binary = false;
+    let body = String::from_utf8_lossy(&response.body).into_owned();
+    tabs[index].source = Some(body.lines().map(str::to_string).collect());
+    tabs[index].rendered = Some(gemini::render_gemtext(&body).lines().map(str::to_string).collect());
+    Ok(())
+}
+
+/// A fetched body is treated as binary (rendered as a hexdump rather than
+/// gemtext) when its meta isn't a `text/*` MIME type, or when it claims
+/// to be text but isn't valid UTF-8.
+fn is_binary_response(meta: &str, body: &[u8]) -> bool {
+    let mime = meta.split(';').next().unwrap_or(meta).trim().to_ascii_lowercase();
+    if !mime.is_empty() && !mime.starts_with("text/") {
+        return true;
+    }
+    std::str::from_utf8(body).is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closing_the_active_tab_falls_back_to_the_same_slot() {
+        assert_eq!(reindex_after_close(Some(1), 1, 2), Some(1));
+    }
+
+    #[test]
+    fn closing_the_active_tab_clamps_to_the_new_last_slot() {
+        assert_eq!(reindex_after_close(Some(2), 2, 2), Some(1));
+    }
+
+    #[test]
+    fn closing_the_only_tab_leaves_no_active_tab() {
+        assert_eq!(reindex_after_close(Some(0), 0, 0), None);
+    }
+
+    #[test]
+    fn closing_a_tab_before_the_active_one_shifts_it_down() {
+        assert_eq!(reindex_after_close(Some(2), 0, 2), Some(1));
+    }
+
+    #[test]
+    fn closing_a_tab_after_the_active_one_leaves_it_alone() {
+        assert_eq!(reindex_after_close(Some(0), 1, 1), Some(0));
+    }
+
+    #[test]
+    fn new_tab_seeds_history_with_its_opening_url_when_recording() {
+        let tab = Tab::new("gemini://a".to_string(), true, SocksAuth { username: "t".to_string(), password: "t".to_string() });
+        assert_eq!(tab.history, vec!["gemini://a".to_string()]);
+        assert_eq!(tab.history_pos, 0);
+    }
+
+    #[test]
+    fn new_tab_keeps_no_history_when_recording_is_disabled() {
+        let tab = Tab::new("gemini://a".to_string(), false, SocksAuth { username: "t".to_string(), password: "t".to_string() });
+        assert!(tab.history.is_empty());
+    }
+
+    #[test]
+    fn navigate_appends_to_history_and_resets_render_state() {
+        let mut tab = Tab::new("gemini://a".to_string(), true, SocksAuth { username: "t".to_string(), password: "t".to_string() });
+        tab.rendered = Some(vec!["line".to_string()]);
+        tab.scroll = 3;
+        tab.navigate("gemini://b");
+        assert_eq!(tab.history, vec!["gemini://a".to_string(), "gemini://b".to_string()]);
+        assert_eq!(tab.history_pos, 1);
+        assert!(tab.rendered.is_none());
+        assert_eq!(tab.scroll, 0);
+    }
+
+    #[test]
+    fn navigate_after_going_back_truncates_the_forward_branch() {
+        let mut tab = Tab::new("gemini://a".to_string(), true, SocksAuth { username: "t".to_string(), password: "t".to_string() });
+        tab.navigate("gemini://b");
+        tab.navigate("gemini://c");
+        tab.history_pos = 0;
+        tab.navigate("gemini://d");
+        assert_eq!(tab.history, vec!["gemini://a".to_string(), "gemini://d".to_string()]);
+        assert_eq!(tab.history_pos, 1);
+    }
+
+    #[test]
+    fn navigate_is_a_no_op_on_history_when_recording_is_disabled() {
+        let mut tab = Tab::new("gemini://a".to_string(), false, SocksAuth { username: "t".to_string(), password: "t".to_string() });
+        tab.navigate("gemini://b");
+        assert!(tab.history.is_empty());
+    }
+
+    #[test]
+    fn default_download_name_uses_the_last_path_segment() {
+        assert_eq!(default_download_name("gemini://example.onion/files/report.gmi"), "report.gmi");
+    }
+
+    #[test]
+    fn default_download_name_falls_back_when_the_url_has_no_segments() {
+        assert_eq!(default_download_name(""), "download");
+    }
+
+    #[test]
+    fn find_matches_returns_matching_line_indices() {
+        let lines: Vec<String> = vec!["first line".into(), "second match".into(), "third, another match".into()];
+        assert_eq!(find_matches(&lines, "match"), vec![1, 2]);
+    }
+
+    #[test]
+    fn find_matches_returns_nothing_for_no_hits() {
+        let lines: Vec<String> = vec!["nothing here".into()];
+        assert!(find_matches(&lines, "missing").is_empty());
+    }
+
+    #[test]
+    fn highlight_matches_wraps_every_occurrence() {
+        assert_eq!(highlight_matches("cat and cat", "cat", Theme::Monochrome), ">>>cat<<< and >>>cat<<<");
+    }
+
+    #[test]
+    fn highlight_matches_is_a_no_op_for_an_empty_term() {
+        assert_eq!(highlight_matches("unchanged", "", Theme::Monochrome), "unchanged");
+    }
+
+    #[test]
+    fn hint_code_counts_through_the_alphabet_then_doubles_up() {
+        assert_eq!(hint_code(0), "a");
+        assert_eq!(hint_code(25), "z");
+        assert_eq!(hint_code(26), "aa");
+        assert_eq!(hint_code(27), "ab");
+    }
+
+    #[test]
+    fn hint_index_inverts_hint_code() {
+        for i in 0..60 {
+            assert_eq!(hint_index(61, &hint_code(i)), Some(i));
+        }
+    }
+
+    #[test]
+    fn hint_index_rejects_an_unknown_code() {
+        assert_eq!(hint_index(3, "zz"), None);
+    }
+
+    #[test]
+    fn next_isolation_hands_out_distinct_credentials_per_tab() {
+        let mut counter = 0;
+        let first = next_isolation(&mut counter);
+        let second = next_isolation(&mut counter);
+        assert_ne!(first.username, second.username);
+        assert_eq!(counter, 2);
+    }
+
+    #[test]
+    fn text_gemini_with_valid_utf8_is_not_binary() {
+        assert!(!is_binary_response("text/gemini; charset=utf-8", b"# hello"));
+    }
+
+    #[test]
+    fn a_non_text_mime_type_is_binary() {
+        assert!(is_binary_response("image/png", b"\x89PNG"));
+    }
+
+    #[test]
+    fn text_meta_with_invalid_utf8_body_is_still_binary() {
+        assert!(is_binary_response("text/plain", &[0xff, 0xfe]));
+    }
+
+    #[test]
+    fn reload_hosts_is_a_no_op_when_the_file_has_not_changed() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-browse-test-reload-unchanged-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"hosts": {"slow.onion": {"connect_timeout": 10}}}"#).unwrap();
+
+        let mut mtime = file_mtime(&file);
+        let result = reload_hosts(&file, &mut mtime);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn reload_hosts_picks_up_an_edited_file() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-browse-test-reload-changed-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"hosts": {"slow.onion": {"connect_timeout": 10}}}"#).unwrap();
+
+        let mut mtime = None;
+        let hosts = reload_hosts(&file, &mut mtime).unwrap().unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(hosts.get("slow.onion").unwrap().connect_timeout, Some(10));
+        assert!(mtime.is_some());
+    }
+
+    #[test]
+    fn reload_hosts_surfaces_a_parse_error_instead_of_silently_keeping_the_old_map() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-browse-test-reload-bad-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"hosts": {"slow.onion": {"pin": 5}}}"#).unwrap();
+
+        let mut mtime = None;
+        let result = reload_hosts(&file, &mut mtime);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.is_err());
+    }
+}
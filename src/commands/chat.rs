@@ -0,0 +1,145 @@
+//! `orc chat`: a line-based encrypted chat between two peers, one
+//! listening and one connecting, meeting over onion addresses.
+//!
+//! `orc chat` doesn't publish an onion service itself; `chat --listen`
+//! expects Tor to already be forwarding an onion service's port to the
+//! local port given here (configured in `torrc`, same as any other
+//! hidden service), and `chat --connect` reaches the other peer's onion
+//! address the same way every other `orc` command does, through the
+//! local SOCKS5 proxy.
+
+use std::io::{BufRead, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::{OrcError, Result};
+use crate::net::chat::{ChatReader, ChatWriter};
+use crate::net::split_host_port;
+use crate::net::tcp::{create_socks_stream, Socks5Stream};
+use crate::secret::SensitiveString;
+
+/// Either side `--listen` or `--connect` can end up with: a plain
+/// incoming TCP connection for the former, a SOCKS5-proxied stream for
+/// the latter. [`ChatReader`]/[`ChatWriter`] only need `Read`/`Write`, so
+/// this just unifies the two concrete types `run` can produce.
+enum ChatSocket {
+    Tcp(TcpStream),
+    Proxied(Socks5Stream),
+}
+
+impl ChatSocket {
+    fn try_clone(&self) -> Result<ChatSocket> {
+        match self {
+            ChatSocket::Tcp(s) => Ok(ChatSocket::Tcp(s.try_clone()?)),
+            ChatSocket::Proxied(s) => Ok(ChatSocket::Proxied(s.try_clone()?)),
+        }
+    }
+}
+
+impl Read for ChatSocket {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            ChatSocket::Tcp(s) => s.read(buf),
+            ChatSocket::Proxied(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for ChatSocket {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            ChatSocket::Tcp(s) => s.write(buf),
+            ChatSocket::Proxied(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ChatSocket::Tcp(s) => s.flush(),
+            ChatSocket::Proxied(s) => s.flush(),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ChatArgs {
+    /// Local address to accept a single chat connection on, expected to
+    /// be reachable through a Tor-configured onion service. Mutually
+    /// exclusive with `--connect`.
+    #[arg(long, conflicts_with = "connect")]
+    pub listen: Option<SocketAddr>,
+
+    /// The other peer's onion address to connect to, as `host:port`.
+    /// Mutually exclusive with `--listen`.
+    #[arg(long, conflicts_with = "listen")]
+    pub connect: Option<String>,
+
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon), used
+    /// only with `--connect`.
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Read the shared passphrase from stdin instead of prompting.
+    #[arg(long)]
+    pub passphrase_stdin: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: ChatArgs) -> Result<()> {
+    let passphrase = read_passphrase(args.passphrase_stdin)?;
+
+    let socket = match (&args.listen, &args.connect) {
+        (Some(listen), None) => {
+            let listener = TcpListener::bind(listen)?;
+            eprintln!("orc: waiting for a chat connection on {listen}");
+            let (socket, peer) = listener.accept()?;
+            eprintln!("orc: chat connection from {peer}");
+            ChatSocket::Tcp(socket)
+        }
+        (None, Some(connect)) => {
+            let (host, port) = split_host_port(connect)?;
+            let options = args.timeouts.to_connect_options();
+            ChatSocket::Proxied(create_socks_stream(args.proxy, host, port, &options)?)
+        }
+        _ => return Err(OrcError::InvalidArgument("exactly one of --listen or --connect is required".into())),
+    };
+
+    converse(socket, &passphrase)
+}
+
+fn read_passphrase(from_stdin: bool) -> Result<SensitiveString> {
+    if !from_stdin {
+        eprint!("passphrase: ");
+    }
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+/// Reads outgoing lines from stdin and forwards them on a background
+/// thread over a cloned write half, while the main thread blocks on
+/// incoming frames — the same split-direction shape as `orc nc`'s
+/// stdin/stdout bridge.
+fn converse(socket: ChatSocket, passphrase: &SensitiveString) -> Result<()> {
+    let write_socket = socket.try_clone()?;
+    let mut writer = ChatWriter::new(write_socket, passphrase);
+
+    let sender = thread::spawn(move || -> Result<()> {
+        for line in std::io::BufReader::new(std::io::stdin()).lines() {
+            writer.send_line(&line?)?;
+        }
+        Ok(())
+    });
+
+    let mut reader = ChatReader::new(socket, passphrase);
+    while let Ok(line) = reader.recv_line() {
+        println!("{line}");
+    }
+
+    sender.join().expect("chat sender thread panicked")
+}
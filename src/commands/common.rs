@@ -0,0 +1,97 @@
+//! CLI argument groups shared by more than one subcommand.
+
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::net::tcp::ConnectOptions;
+
+/// Timeout and keepalive flags shared by every command that opens a SOCKS
+/// connection, flattened into that command's own argument struct.
+#[derive(Debug, Args)]
+pub struct TimeoutArgs {
+    /// Seconds to wait for the handshake with the local SOCKS proxy.
+    #[arg(long, default_value_t = 30)]
+    pub connect_timeout: u64,
+
+    /// Seconds to wait for data from the remote peer before giving up.
+    /// Unset means wait forever.
+    #[arg(long)]
+    pub read_timeout: Option<u64>,
+
+    /// Seconds to wait when writing to the remote peer before giving up.
+    /// Unset means wait forever.
+    #[arg(long)]
+    pub write_timeout: Option<u64>,
+
+    /// Enable TCP keepalive on the connection, probing after this many
+    /// idle seconds. Unset disables keepalive.
+    #[arg(long)]
+    pub keepalive: Option<u64>,
+}
+
+impl TimeoutArgs {
+    pub fn to_connect_options(&self) -> ConnectOptions {
+        ConnectOptions {
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            read_timeout: self.read_timeout.map(Duration::from_secs),
+            write_timeout: self.write_timeout.map(Duration::from_secs),
+            keepalive: self.keepalive.map(Duration::from_secs),
+            auth: None,
+            events: None,
+            cancellation: None,
+        }
+    }
+}
+
+/// A typed-phrase kill switch, flattened into any REPL-style command's
+/// argument struct. See [`crate::killswitch`] for what triggering it does.
+#[derive(Debug, Args)]
+pub struct KillSwitchArgs {
+    /// Word that, typed alone on its own line, immediately wipes
+    /// in-memory state and configured paths and exits with status 137.
+    #[arg(long, default_value = "panic")]
+    pub kill_switch: String,
+}
+
+/// Polling flags shared by commands that can repeat a one-shot action on
+/// an interval, e.g. to watch for a service coming back up.
+#[derive(Debug, Args)]
+pub struct PollArgs {
+    /// Number of times to run the action. Defaults to running it once.
+    #[arg(long, default_value_t = 1)]
+    pub repeat: usize,
+
+    /// Seconds to wait between repeats.
+    #[arg(long, default_value_t = 1)]
+    pub interval: u64,
+}
+
+impl PollArgs {
+    /// Runs `attempt` up to `repeat` times, sleeping `interval` seconds
+    /// between attempts, printing per-iteration timing and a summary of
+    /// how many attempts succeeded.
+    pub fn run_polled<F>(&self, mut attempt: F) -> crate::error::Result<()>
+    where
+        F: FnMut(usize) -> crate::error::Result<()>,
+    {
+        let mut successes = 0;
+        for i in 0..self.repeat.max(1) {
+            let start = std::time::Instant::now();
+            match attempt(i) {
+                Ok(()) => {
+                    successes += 1;
+                    eprintln!("orc: attempt {} ok in {:.1}ms", i + 1, start.elapsed().as_secs_f64() * 1000.0);
+                }
+                Err(err) => eprintln!("orc: attempt {} failed after {:.1}ms: {err}", i + 1, start.elapsed().as_secs_f64() * 1000.0),
+            }
+            if i + 1 < self.repeat && self.interval > 0 {
+                std::thread::sleep(Duration::from_secs(self.interval));
+            }
+        }
+        if self.repeat > 1 {
+            eprintln!("orc: {successes}/{} attempts succeeded", self.repeat);
+        }
+        Ok(())
+    }
+}
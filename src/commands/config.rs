@@ -0,0 +1,340 @@
+//! `orc config`: create and edit `orc`'s JSON config file from a shell
+//! instead of having to track down its path and hand-write JSON into it.
+//! It's the same file every other command's `--config-file` flag reads
+//! (see [`crate::config`]); this just gives it a conventional default
+//! location ([`crate::config::default_config_file`]) and a few verbs for
+//! poking at individual keys.
+//!
+//! `encrypt`/`decrypt` toggle whether that file sits on disk in the
+//! clear or under a passphrase — worth doing since a config file can
+//! carry onion addresses in its keymap or elsewhere that someone reading
+//! the disk shouldn't get for free. `show`/`get`/`set` only understand
+//! the plaintext shape, so an encrypted file has to be decrypted first;
+//! [`crate::config::load`] (used at `orc browse` startup) is the one
+//! reader in this crate that decrypts transparently, prompting for the
+//! passphrase itself.
+//!
+//! `set-duress` adds a second passphrase to an already-encrypted file:
+//! entering it instead of the real one at that same prompt wipes the
+//! file and unlocks into an empty config rather than failing. See
+//! [`crate::duress`].
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::config;
+use crate::duress;
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Create an empty config file, failing if one already exists.
+    Init(InitArgs),
+    /// Print the whole config file.
+    Show(ShowArgs),
+    /// Print one key's value (dotted for a nested key, e.g. `keys.j`).
+    Get(GetArgs),
+    /// Set one key's value, creating the file and any parent objects its
+    /// path needs along the way.
+    Set(SetArgs),
+    /// Print the config file's path without reading or writing it.
+    Path(PathArgs),
+    /// Encrypt the config file under a passphrase prompted on stdin.
+    Encrypt(EncryptArgs),
+    /// Decrypt a config file encrypted with `encrypt`, also prompted on stdin.
+    Decrypt(DecryptArgs),
+    /// Add or replace a duress passphrase on an already-encrypted config
+    /// file: entering it instead of the real passphrase at `orc browse`
+    /// startup silently wipes the file and starts from an empty config.
+    SetDuress(SetDuressArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Config file to create, instead of the default
+    /// `~/.config/orc/config.json`.
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct GetArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+
+    /// Key to read, e.g. `theme` or `keys.j`.
+    pub key: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SetArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+
+    /// Key to write, e.g. `theme` or `keys.j`.
+    pub key: String,
+
+    /// Value to store. `true`/`false` and anything that parses as a
+    /// number are stored as that JSON type; everything else is stored as
+    /// a string.
+    pub value: String,
+}
+
+#[derive(Debug, Args)]
+pub struct PathArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct EncryptArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct DecryptArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct SetDuressArgs {
+    #[arg(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+pub fn run(args: ConfigArgs) -> Result<()> {
+    match args.action {
+        ConfigAction::Path(args) => {
+            println!("{}", resolve_path(args.config_file).display());
+            Ok(())
+        }
+        ConfigAction::Init(args) => {
+            let path = resolve_path(args.config_file);
+            if path.exists() {
+                return Err(OrcError::InvalidArgument(format!("config file already exists at {}", path.display())));
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, "{}")?;
+            println!("created {}", path.display());
+            Ok(())
+        }
+        ConfigAction::Show(args) => {
+            let value = read(&resolve_path(args.config_file))?;
+            println!("{value}");
+            Ok(())
+        }
+        ConfigAction::Get(args) => {
+            let value = read(&resolve_path(args.config_file))?;
+            let found = get_path(&value, &args.key).ok_or_else(|| OrcError::InvalidArgument(format!("no such config key `{}`", args.key)))?;
+            match found {
+                Value::String(s) => println!("{s}"),
+                other => println!("{other}"),
+            }
+            Ok(())
+        }
+        ConfigAction::Set(args) => {
+            let path = resolve_path(args.config_file);
+            let mut root = if path.exists() { read(&path)? } else { Value::Object(Vec::new()) };
+            set_path(&mut root, &args.key, parse_value(&args.value))?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, root.to_string())?;
+            println!("set {} in {}", args.key, path.display());
+            Ok(())
+        }
+        ConfigAction::Encrypt(args) => {
+            let path = resolve_path(args.config_file);
+            let parsed = read_raw(&path)?;
+            if config::is_encrypted(&parsed) {
+                return Err(OrcError::InvalidArgument(format!("{} is already encrypted", path.display())));
+            }
+            let passphrase = read_passphrase("new config passphrase")?;
+            std::fs::write(&path, config::encrypt(&parsed.to_string(), &passphrase))?;
+            println!("encrypted {}", path.display());
+            Ok(())
+        }
+        ConfigAction::Decrypt(args) => {
+            let path = resolve_path(args.config_file);
+            let parsed = read_raw(&path)?;
+            if !config::is_encrypted(&parsed) {
+                return Err(OrcError::InvalidArgument(format!("{} is not encrypted", path.display())));
+            }
+            let passphrase = read_passphrase("config passphrase")?;
+            let plaintext = config::decrypt(&parsed, &passphrase)?;
+            std::fs::write(&path, plaintext)?;
+            println!("decrypted {}", path.display());
+            Ok(())
+        }
+        ConfigAction::SetDuress(args) => {
+            let path = resolve_path(args.config_file);
+            let mut envelope = read_raw(&path)?;
+            if !config::is_encrypted(&envelope) {
+                return Err(OrcError::InvalidArgument(format!("{} is not encrypted; run `orc config encrypt` first", path.display())));
+            }
+            let passphrase = read_passphrase("config passphrase")?;
+            // Proves the caller already knows the real passphrase before
+            // letting them set a duress one, the same as `decrypt`
+            // itself would demand.
+            config::decrypt(&envelope, &passphrase)?;
+
+            let duress_passphrase = read_passphrase("new duress passphrase")?;
+            let duress = duress::Duress::set(&duress_passphrase);
+            if let Value::Object(fields) = &mut envelope {
+                fields.retain(|(key, _)| key != "duress_salt" && key != "duress_hash");
+                fields.extend(duress.fields());
+            }
+            std::fs::write(&path, envelope.to_string())?;
+            println!("set a duress passphrase on {}", path.display());
+            Ok(())
+        }
+    }
+}
+
+fn read_passphrase(prompt: &str) -> Result<SensitiveString> {
+    eprint!("{prompt}: ");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+fn resolve_path(config_file: Option<PathBuf>) -> PathBuf {
+    config_file.unwrap_or_else(config::default_config_file)
+}
+
+/// Reads and parses the config file at `path`, whatever shape it's in —
+/// used by `encrypt`/`decrypt`, which need to inspect a file before
+/// knowing whether it's already encrypted.
+fn read_raw(path: &std::path::Path) -> Result<Value> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|_| OrcError::InvalidArgument(format!("no config file at {}; run `orc config init` first", path.display())))?;
+    json::parse(&text)
+}
+
+/// Reads the config file at `path` for `show`/`get`/`set`, which only
+/// understand plain config keys — an encrypted file has to be decrypted
+/// with `orc config decrypt` first.
+fn read(path: &std::path::Path) -> Result<Value> {
+    let parsed = read_raw(path)?;
+    if config::is_encrypted(&parsed) {
+        return Err(OrcError::InvalidArgument(format!("{} is encrypted; run `orc config decrypt` first", path.display())));
+    }
+    Ok(parsed)
+}
+
+/// Looks up a dot-separated path (`"keys.j"`) through nested objects.
+fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.') {
+        current = current.get(segment)?;
+    }
+    Some(current)
+}
+
+/// Sets a dot-separated path, creating any missing intermediate objects
+/// along the way. Passing through a key that's already something other
+/// than an object (e.g. `theme.x` when `theme` is a string) is an error
+/// rather than silently clobbering it.
+fn set_path(root: &mut Value, path: &str, value: Value) -> Result<()> {
+    let mut segments = path.split('.').peekable();
+    let mut current = root;
+    while let Some(segment) = segments.next() {
+        let Value::Object(fields) = current else {
+            return Err(OrcError::InvalidArgument(format!("`{path}` passes through a key that isn't an object")));
+        };
+        if segments.peek().is_none() {
+            match fields.iter_mut().find(|(k, _)| k == segment) {
+                Some((_, existing)) => *existing = value,
+                None => fields.push((segment.to_string(), value)),
+            }
+            return Ok(());
+        }
+        let index = match fields.iter().position(|(k, _)| k == segment) {
+            Some(i) => i,
+            None => {
+                fields.push((segment.to_string(), Value::Object(Vec::new())));
+                fields.len() - 1
+            }
+        };
+        current = &mut fields[index].1;
+    }
+    Ok(())
+}
+
+/// Guesses the JSON type a CLI string should become: `true`/`false` to a
+/// bool, anything that parses as a number to a number, everything else
+/// stays a string. There's no way to type a JSON array or object through
+/// `set` — the same gap a one-key-at-a-time `git config` has.
+fn parse_value(raw: &str) -> Value {
+    match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => match raw.parse::<f64>() {
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::String(raw.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_path_walks_nested_objects() {
+        let value = json::parse(r#"{"keys": {"j": "scroll +1"}}"#).unwrap();
+        assert_eq!(get_path(&value, "keys.j").and_then(Value::as_str), Some("scroll +1"));
+    }
+
+    #[test]
+    fn get_path_rejects_an_unknown_key() {
+        let value = json::parse(r#"{"theme": "color"}"#).unwrap();
+        assert!(get_path(&value, "images").is_none());
+    }
+
+    #[test]
+    fn set_path_creates_missing_intermediate_objects() {
+        let mut value = Value::Object(Vec::new());
+        set_path(&mut value, "keys.j", Value::String("scroll +1".to_string())).unwrap();
+        assert_eq!(get_path(&value, "keys.j").and_then(Value::as_str), Some("scroll +1"));
+    }
+
+    #[test]
+    fn set_path_overwrites_an_existing_leaf() {
+        let mut value = json::parse(r#"{"theme": "color"}"#).unwrap();
+        set_path(&mut value, "theme", Value::String("monochrome".to_string())).unwrap();
+        assert_eq!(get_path(&value, "theme").and_then(Value::as_str), Some("monochrome"));
+    }
+
+    #[test]
+    fn set_path_rejects_passing_through_a_non_object() {
+        let mut value = json::parse(r#"{"theme": "color"}"#).unwrap();
+        assert!(set_path(&mut value, "theme.x", Value::Bool(true)).is_err());
+    }
+
+    #[test]
+    fn parse_value_recognizes_booleans_and_numbers() {
+        assert_eq!(parse_value("true"), Value::Bool(true));
+        assert_eq!(parse_value("42"), Value::Number(42.0));
+        assert_eq!(parse_value("hello"), Value::String("hello".to_string()));
+    }
+}
@@ -0,0 +1,45 @@
+//! `orc decrypt`: reverses [`crate::download_crypto::encrypt`] (applied
+//! by `orc oshare get --passphrase-stdin` or `orc browse`'s
+//! `dl`/`save --passphrase-stdin`), writing the plaintext back out — the
+//! one place a passphrase-encrypted download is ever read.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::download_crypto;
+use crate::error::Result;
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct DecryptArgs {
+    /// Encrypted file to read.
+    pub file: PathBuf,
+
+    /// Where to write the decrypted plaintext, instead of `<file>` with
+    /// its extension replaced by `.dec`.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: DecryptArgs) -> Result<()> {
+    let ciphertext = std::fs::read(&args.file)?;
+    let passphrase = read_passphrase()?;
+    let plaintext = download_crypto::decrypt(&ciphertext, &passphrase)?;
+
+    let output = args.output.unwrap_or_else(|| {
+        let mut path = args.file.clone();
+        path.set_extension("dec");
+        path
+    });
+    std::fs::write(&output, plaintext)?;
+    println!("decrypted to {}", output.display());
+    Ok(())
+}
+
+fn read_passphrase() -> Result<SensitiveString> {
+    eprint!("download passphrase: ");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
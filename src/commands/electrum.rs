@@ -0,0 +1,96 @@
+//! `orc electrum`: query balances and broadcast transactions against an
+//! onion Electrum server.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::electrum::ElectrumConnection;
+use crate::net::tls;
+
+#[derive(Debug, Args)]
+pub struct ElectrumArgs {
+    #[command(subcommand)]
+    pub action: ElectrumCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ElectrumCommand {
+    /// Look up the balance of a scripthash.
+    Balance(BalanceArgs),
+    /// Broadcast a raw transaction.
+    Broadcast(BroadcastArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BalanceArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Electrum server to query, as `host:port`.
+    pub server: String,
+
+    /// Scripthash to query, as used by the Electrum protocol (the
+    /// sha256 of the output script, reversed, in hex).
+    pub scripthash: String,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct BroadcastArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Electrum server to broadcast through, as `host:port`.
+    pub server: String,
+
+    /// Raw transaction, hex-encoded.
+    pub raw_tx_hex: String,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: ElectrumArgs) -> Result<()> {
+    match args.action {
+        ElectrumCommand::Balance(balance_args) => run_balance(balance_args),
+        ElectrumCommand::Broadcast(broadcast_args) => run_broadcast(broadcast_args),
+    }
+}
+
+fn run_balance(args: BalanceArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let options = args.timeouts.to_connect_options();
+
+    let mut connection = ElectrumConnection::connect(args.proxy, host, port, &pin_file, &options)?;
+    let balance = connection.get_balance(&args.scripthash)?;
+    println!("{balance}");
+    Ok(())
+}
+
+fn run_broadcast(args: BroadcastArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let options = args.timeouts.to_connect_options();
+
+    let mut connection = ElectrumConnection::connect(args.proxy, host, port, &pin_file, &options)?;
+    let txid = connection.broadcast_transaction(&args.raw_tx_hex)?;
+    println!("{txid}");
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! `orc feed`: fetch and render an RSS or Atom feed from an onion site.
+
+use std::net::SocketAddr;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::feed;
+use crate::net::json::Value;
+
+#[derive(Debug, Args)]
+pub struct FeedArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Feed URL, e.g. `http://news.onion/feed.xml`.
+    #[arg(long)]
+    pub url: String,
+
+    /// Print the feed as a JSON array instead of a readable list.
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: FeedArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+    let items = feed::fetch(args.proxy, &args.url, &options)?;
+
+    if args.json {
+        let array = Value::Array(
+            items
+                .iter()
+                .map(|item| {
+                    Value::Object(vec![
+                        ("title".to_string(), Value::String(item.title.clone())),
+                        ("link".to_string(), Value::String(item.link.clone())),
+                        ("date".to_string(), Value::String(item.date.clone())),
+                        ("summary".to_string(), Value::String(item.summary.clone())),
+                    ])
+                })
+                .collect(),
+        );
+        println!("{array}");
+    } else {
+        for item in &items {
+            println!("{}", item.title);
+            if !item.date.is_empty() {
+                println!("  {}", item.date);
+            }
+            println!("  {}", item.link);
+            if !item.summary.is_empty() {
+                println!("  {}", item.summary);
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! `orc fetch`: a generic `http://` request over Tor, the CLI command
+//! [`crate::client::OrcRequest`]'s own doc comment already anticipated
+//! (it was built for `rpc`/`feed` to move onto, but nothing had moved
+//! yet) and the one `-H`/`-X`/`-d`/`-o`/`-L`/`-I`/`-u`/`--dump-headers`/
+//! `--format` below are aliases on, for anyone replacing a `curl
+//! --socks5-hostname ...` invocation.
+//!
+//! There's no `orc probe` or `orc batch` in this crate for `--format` to
+//! also cover — `fetch` is the one command here whose summary a curl
+//! `-w`-style template makes sense for.
+
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use base64::Engine;
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::secret::SensitiveString;
+use crate::OrcClient;
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// `http://host[:port][/path]` to request, normally an onion
+    /// service.
+    pub url: String,
+
+    /// HTTP method. Defaults to `POST` if `-d`/`--data` is given without
+    /// this, `GET` otherwise — the same default curl uses.
+    #[arg(short = 'X', long = "request")]
+    pub method: Option<String>,
+
+    /// Extra request header, as `Name: value`. Pass more than once for
+    /// more than one header.
+    #[arg(short = 'H', long = "header")]
+    pub headers: Vec<String>,
+
+    /// Request body, sent as-is. Implies `-X POST` unless `-X` is also
+    /// given.
+    #[arg(short = 'd', long = "data")]
+    pub data: Option<String>,
+
+    /// Write the response body here instead of stdout. `-` (the
+    /// default) means stdout.
+    #[arg(short = 'o', long = "output", default_value = "-")]
+    pub output: PathBuf,
+
+    /// Follow `3xx` `Location` redirects, the same way curl needs `-L`
+    /// to. Without it, the first response is always returned as-is —
+    /// unlike [`OrcClient::request`]'s own default of following up to
+    /// [`crate::defaults::max_redirects`], chosen here so this command's
+    /// default behavior matches curl's rather than this crate's library
+    /// default.
+    #[arg(short = 'L', long = "location")]
+    pub location: bool,
+
+    /// Send a `HEAD` request and print only the status line and
+    /// headers, never a body. Overrides `-X`/`--data`.
+    #[arg(short = 'I', long = "head")]
+    pub head: bool,
+
+    /// HTTP Basic auth username. Unlike curl's `-u user:pass`, the
+    /// password is never accepted on the command line — it's always
+    /// read from stdin — the same line this crate already drew for
+    /// `orc rpc --username`, so a password doesn't sit in shell history
+    /// or `/proc/<pid>/cmdline`.
+    #[arg(short = 'u', long = "user")]
+    pub user: Option<String>,
+
+    /// Also write the status line and headers, exactly as printed on
+    /// stderr, to this file (`-` for stdout) — so a script that wants
+    /// both the header block and the body has somewhere to read the
+    /// former from that isn't stderr, which other diagnostics may also
+    /// be writing to.
+    #[arg(long = "dump-headers")]
+    pub dump_headers: Option<PathBuf>,
+
+    /// Print this template, substituted and newline-terminated, after
+    /// the response — curl `-w`-style, minus the dozens of variables
+    /// curl supports. Recognizes `{status}`, `{size}` (response body
+    /// bytes), `{time_ms}` (wall-clock time spent sending the request
+    /// and reading the response), and `{url}`.
+    #[arg(long = "format")]
+    pub format: Option<String>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: FetchArgs) -> Result<()> {
+    let method = if args.head {
+        "HEAD".to_string()
+    } else {
+        args.method.clone().unwrap_or_else(|| if args.data.is_some() { "POST".to_string() } else { "GET".to_string() })
+    };
+
+    let password = if args.user.is_some() {
+        eprint!("password: ");
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Some(SensitiveString::new(line.trim_end().to_string()))
+    } else {
+        None
+    };
+
+    let options = args.timeouts.to_connect_options();
+    let client = OrcClient::new(args.proxy).with_options(options);
+
+    let mut request = client.request(&method, &args.url);
+    for header in &args.headers {
+        let (name, value) = header.split_once(':').ok_or_else(|| {
+            crate::error::OrcError::InvalidArgument(format!("`{header}` is not in `Name: value` form"))
+        })?;
+        request = request.header(name.trim(), value.trim());
+    }
+    if let Some(user) = &args.user {
+        let password = password.as_ref().map(SensitiveString::as_str).unwrap_or("");
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{password}"));
+        request = request.header("Authorization", &format!("Basic {credentials}"));
+    }
+    if let Some(data) = &args.data {
+        request = request.body(data.clone().into_bytes());
+    }
+    request = request.max_redirects(if args.location { crate::defaults::max_redirects() } else { 0 });
+
+    let started = std::time::Instant::now();
+    let response = request.send()?;
+    let elapsed = started.elapsed();
+
+    // The status line and headers are decoration, not the response —
+    // printed to stderr, the same split `orc gemini` already makes, so
+    // `orc fetch ... | tar x` only ever sees the body on stdout. The
+    // status line is coloured by response class (see `crate::color`);
+    // `header_block` below stays plain, since it's also what
+    // `--dump-headers` writes to a file or stdout, where ANSI escapes
+    // would just get in a script's way.
+    let mut header_block = format!("HTTP {}\n", response.status);
+    for (name, value) in &response.headers {
+        header_block.push_str(&format!("{name}: {value}\n"));
+    }
+    eprintln!("< {}", crate::color::status(response.status, &format!("HTTP {}", response.status)));
+    for (name, value) in &response.headers {
+        eprintln!("< {name}: {value}");
+    }
+    if let Some(dump_headers) = &args.dump_headers {
+        if dump_headers.as_os_str() == "-" {
+            std::io::stdout().write_all(header_block.as_bytes())?;
+        } else {
+            std::fs::write(dump_headers, &header_block)?;
+        }
+    }
+
+    if !args.head {
+        if args.output.as_os_str() == "-" {
+            std::io::stdout().write_all(&response.body)?;
+        } else {
+            std::fs::write(&args.output, &response.body)?;
+        }
+    }
+
+    if let Some(template) = &args.format {
+        println!("{}", render_format(template, response.status, response.body.len(), elapsed.as_millis(), &args.url));
+    }
+    Ok(())
+}
+
+fn render_format(template: &str, status: u16, size: usize, time_ms: u128, url: &str) -> String {
+    template
+        .replace("{status}", &status.to_string())
+        .replace("{size}", &size.to_string())
+        .replace("{time_ms}", &time_ms.to_string())
+        .replace("{url}", url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_format_substitutes_every_variable() {
+        let rendered = render_format("{status} {size} {time_ms} {url}", 200, 42, 7, "http://example.onion/");
+        assert_eq!(rendered, "200 42 7 http://example.onion/");
+    }
+
+    #[test]
+    fn render_format_leaves_unknown_placeholders_alone() {
+        assert_eq!(render_format("{status} {nope}", 404, 0, 0, ""), "404 {nope}");
+    }
+}
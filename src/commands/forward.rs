@@ -0,0 +1,85 @@
+//! `orc forward`: a local TCP listener that tunnels every accepted
+//! connection through a fresh SOCKS stream, so tools that don't know how
+//! to speak SOCKS themselves can still reach onion services.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::split_host_port;
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+
+#[derive(Debug, Args)]
+pub struct ForwardArgs {
+    /// Local address to accept plain TCP connections on.
+    #[arg(long)]
+    pub listen: SocketAddr,
+
+    /// Remote target each connection is forwarded to, as `host:port`.
+    #[arg(long)]
+    pub to: String,
+
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: ForwardArgs) -> Result<()> {
+    let (target_host, target_port) = split_host_port(&args.to)?;
+    let listener = TcpListener::bind(args.listen)?;
+    let options = args.timeouts.to_connect_options();
+    eprintln!(
+        "orc: forwarding {} -> {} via {}",
+        args.listen, args.to, args.proxy
+    );
+
+    for incoming in listener.incoming() {
+        let client = match incoming {
+            Ok(client) => client,
+            Err(err) => {
+                eprintln!("orc: failed to accept connection: {err}");
+                continue;
+            }
+        };
+
+        let proxy = args.proxy;
+        let target_host = target_host.to_string();
+        let options = options.clone();
+        thread::spawn(move || {
+            if let Err(err) = forward_one(client, proxy, &target_host, target_port, options) {
+                eprintln!("orc: forwarded connection failed: {err}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn forward_one(
+    client: TcpStream,
+    proxy: SocketAddr,
+    target_host: &str,
+    target_port: u16,
+    options: ConnectOptions,
+) -> Result<()> {
+    let remote = create_socks_stream(proxy, target_host, target_port, &options)?;
+    let mut remote_for_read = remote.try_clone()?;
+    let mut remote_for_write = remote.try_clone()?;
+    let mut client_for_read = client.try_clone()?;
+    let mut client_for_write = client;
+
+    let upload = thread::spawn(move || io::copy(&mut client_for_read, &mut remote_for_write));
+    let download = io::copy(&mut remote_for_read, &mut client_for_write);
+
+    download?;
+    upload.join().expect("upload thread panicked")?;
+    Ok(())
+}
+
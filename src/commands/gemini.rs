@@ -0,0 +1,64 @@
+//! `orc gemini`: fetch a gemini:// URL over Tor.
+//!
+//! `-s`/`--quiet` below drops the status line this command otherwise
+//! prints to stderr, leaving the raw response body on stdout with
+//! nothing around it.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::gemini;
+use crate::net::tls;
+
+#[derive(Debug, Args)]
+pub struct GeminiArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// A `gemini://host[:port]/path` URL, normally an onion capsule.
+    pub url: String,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    /// Print the raw response body instead of the stripped-down render.
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Suppress the status/meta line on stderr and always print the raw
+    /// response body exactly as received, so `orc gemini -s ... | tar x`
+    /// or similar pipelines get nothing but the body on stdout, with no
+    /// trailing newline added beyond whatever the server sent. Implies
+    /// `--raw`.
+    #[arg(short = 's', long = "quiet")]
+    pub quiet: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: GeminiArgs) -> Result<()> {
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let pin_store = tls::PinStore::File(pin_file);
+    let options = args.timeouts.to_connect_options();
+    let response = gemini::fetch(args.proxy, &args.url, &pin_store, &options)?;
+
+    if !args.quiet {
+        eprintln!("{} {}", response.status, response.meta);
+    }
+
+    if args.quiet || args.raw || response.status / 10 != 2 {
+        std::io::Write::write_all(&mut std::io::stdout(), &response.body)?;
+        return Ok(());
+    }
+
+    let body = String::from_utf8_lossy(&response.body);
+    print!("{}", gemini::render_gemtext(&body));
+    Ok(())
+}
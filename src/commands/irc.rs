@@ -0,0 +1,123 @@
+//! `orc irc`: a minimal line-based IRC client for onion IRC servers.
+//! Handles registration, PING/PONG keepalives, and lets the user type
+//! raw IRC commands or `/join`, `/msg`, `/quit` shortcuts.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::thread;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::split_host_port;
+use crate::net::tcp::create_socks_stream;
+
+#[derive(Debug, Args)]
+pub struct IrcArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// IRC server to connect to, as `host:port`.
+    pub server: String,
+
+    /// Nickname to register with.
+    #[arg(long)]
+    pub nick: String,
+
+    /// Channel to join immediately after registration.
+    #[arg(long)]
+    pub join: Option<String>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: IrcArgs) -> Result<()> {
+    let (host, port) = split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+    let mut stream = create_socks_stream(args.proxy, host, port, &options)?;
+
+    write_line(&mut stream, &format!("NICK {}", args.nick))?;
+    write_line(&mut stream, &format!("USER {} 0 * :orc", args.nick))?;
+    if let Some(channel) = &args.join {
+        write_line(&mut stream, &format!("JOIN {channel}"))?;
+    }
+
+    let reader_stream = stream.try_clone()?;
+    let mut pong_stream = stream.try_clone()?;
+    let reader = thread::spawn(move || -> Result<()> {
+        let lines = BufReader::new(reader_stream).lines();
+        for line in lines {
+            let line = line?;
+            if let Some(server) = line.strip_prefix("PING ") {
+                // Echo the PING's argument back as-is; most servers send
+                // it already colon-prefixed.
+                writeln_raw(&mut pong_stream, &format!("PONG {server}"))?;
+            }
+            println!("{line}");
+        }
+        Ok(())
+    });
+
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line == "/quit" {
+            write_line(&mut stream, "QUIT :leaving")?;
+            break;
+        }
+        let wire = translate_command(&line);
+        write_line(&mut stream, &wire)?;
+    }
+
+    drop(stream);
+    let _ = reader.join();
+    Ok(())
+}
+
+/// Translates the small set of `/`-shortcuts into raw IRC commands;
+/// anything else is sent verbatim, letting power users type raw commands.
+fn translate_command(line: &str) -> String {
+    if let Some(rest) = line.strip_prefix("/join ") {
+        format!("JOIN {rest}")
+    } else if let Some(rest) = line.strip_prefix("/msg ") {
+        match rest.split_once(' ') {
+            Some((target, message)) => format!("PRIVMSG {target} :{message}"),
+            None => format!("PRIVMSG {rest}"),
+        }
+    } else {
+        line.to_string()
+    }
+}
+
+fn write_line<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    writeln_raw(stream, line)
+}
+
+fn writeln_raw<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\r\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_join_shortcut() {
+        assert_eq!(translate_command("/join #onion"), "JOIN #onion");
+    }
+
+    #[test]
+    fn translates_msg_shortcut() {
+        assert_eq!(translate_command("/msg #onion hello there"), "PRIVMSG #onion :hello there");
+    }
+
+    #[test]
+    fn passes_raw_commands_through() {
+        assert_eq!(translate_command("WHOIS someone"), "WHOIS someone");
+    }
+}
@@ -0,0 +1,337 @@
+//! `orc keys`: import, list, show, and remove Tor v3 onion
+//! client-authorization private keys, the x25519 secret needed to reach
+//! an onion service with `ClientAuth` turned on. Defaults to one
+//! hex-encoded file per onion address under
+//! [`crate::net::onion_auth::default_auth_dir`]; pass `--keyring`
+//! (built with the `keyring-backend` feature) to keep the same keys in
+//! the platform keyring instead. See [`crate::net::onion_auth`] for the
+//! storage itself — this module is just the CLI surface on top of it.
+//!
+//! `orc keys identity` (built with the `serve` feature) is a separate
+//! set of keys entirely: the ed25519 identity a service hosts under,
+//! rather than a client's x25519 credential for reaching one — see
+//! [`crate::net::onion_identity`]. Nested under its own subcommand rather
+//! than a second top-level `Add`/`List`/etc. so the two key kinds can't
+//! be confused for each other at the command line.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::error::{OrcError, Result};
+use crate::net::onion_auth::{self, AuthKeyStore};
+#[cfg(feature = "serve")]
+use crate::net::onion_identity::{self, IdentityKey, IdentityKeyStore};
+use crate::output;
+use crate::secret::{HexSource, SensitiveBytes};
+#[cfg(feature = "serve")]
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct KeysArgs {
+    #[command(subcommand)]
+    pub action: KeysAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KeysAction {
+    /// Import a client-auth key for an onion address.
+    Add(AddArgs),
+    /// List onion addresses with a stored key.
+    List(ListArgs),
+    /// Print a stored key's secret as hex.
+    Show(ShowArgs),
+    /// Remove a stored key.
+    Remove(RemoveArgs),
+    /// Manage onion service identity keys, as opposed to client-auth keys.
+    #[cfg(feature = "serve")]
+    Identity(IdentityArgs),
+}
+
+/// `orc keys identity`: generate, import, export, list, and remove
+/// ed25519 onion service identity keys. See
+/// [`crate::net::onion_identity`] for what these keys are and how
+/// they're stored.
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityArgs {
+    #[command(subcommand)]
+    pub action: IdentityAction,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Subcommand)]
+pub enum IdentityAction {
+    /// Generate a fresh identity key and store it under a name.
+    Generate(IdentityGenerateArgs),
+    /// Import an identity key from a raw 32-byte hex seed.
+    Import(IdentityImportArgs),
+    /// Print a stored identity key's onion address and, optionally, its
+    /// `ADD_ONION`-ready key blob.
+    Export(IdentityExportArgs),
+    /// List stored identity key names.
+    List(IdentityListArgs),
+    /// Remove a stored identity key.
+    Remove(IdentityRemoveArgs),
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityStoreArgs {
+    /// Directory holding one encrypted key file per name, instead of the
+    /// default `~/.config/orc/onion_identity/`.
+    #[arg(long)]
+    pub store_dir: Option<PathBuf>,
+}
+
+#[cfg(feature = "serve")]
+impl IdentityStoreArgs {
+    fn store(&self) -> IdentityKeyStore {
+        IdentityKeyStore::new(self.store_dir.clone().unwrap_or_else(onion_identity::default_identity_dir))
+    }
+}
+
+#[cfg(feature = "serve")]
+fn read_passphrase(prompt: &str) -> Result<SensitiveString> {
+    eprint!("{prompt}: ");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end().to_string()))
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityGenerateArgs {
+    /// Name to store the generated key under.
+    pub name: String,
+
+    #[command(flatten)]
+    pub store: IdentityStoreArgs,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityImportArgs {
+    /// Name to store the imported key under.
+    pub name: String,
+
+    /// The seed as a hex string (e.g. `--seed-hex deadbeef...`). Typing a
+    /// real seed on the command line is discouraged — prefer reading it
+    /// from stdin.
+    #[arg(long, conflicts_with = "seed_stdin")]
+    pub seed_hex: Option<String>,
+
+    /// Read the hex-encoded seed from stdin.
+    #[arg(long, conflicts_with = "seed_hex")]
+    pub seed_stdin: bool,
+
+    #[command(flatten)]
+    pub store: IdentityStoreArgs,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityExportArgs {
+    /// Name of the key to export.
+    pub name: String,
+
+    /// Also print the `ADD_ONION ED25519-V3:<base64>` key blob. Without
+    /// this, only the onion address is printed — the blob is the
+    /// service's actual private key material, so it's opt-in.
+    #[arg(long)]
+    pub show_key_blob: bool,
+
+    #[command(flatten)]
+    pub store: IdentityStoreArgs,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityListArgs {
+    #[command(flatten)]
+    pub store: IdentityStoreArgs,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Debug, Args)]
+pub struct IdentityRemoveArgs {
+    /// Name of the key to remove.
+    pub name: String,
+
+    #[command(flatten)]
+    pub store: IdentityStoreArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct StoreArgs {
+    /// Directory holding one key file per onion address, instead of the
+    /// default `~/.config/orc/client_auth/`. Ignored with `--keyring`.
+    #[arg(long)]
+    pub store_dir: Option<PathBuf>,
+
+    /// Keep this key in the platform keyring instead of a file.
+    #[cfg(feature = "keyring-backend")]
+    #[arg(long)]
+    pub keyring: bool,
+}
+
+impl StoreArgs {
+    fn store(&self) -> AuthKeyStore {
+        #[cfg(feature = "keyring-backend")]
+        if self.keyring {
+            return AuthKeyStore::Keyring;
+        }
+        AuthKeyStore::File(self.store_dir.clone().unwrap_or_else(onion_auth::default_auth_dir))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// Onion address the key authorizes.
+    pub onion: String,
+
+    /// The secret key as a hex string (e.g. `--secret-hex deadbeef...`).
+    /// Typing a real secret on the command line is discouraged — prefer
+    /// one of the other sources below.
+    #[arg(long, conflicts_with_all = ["secret_stdin", "secret_file", "secret_fd"])]
+    pub secret_hex: Option<String>,
+
+    /// Read the hex-encoded secret from stdin.
+    #[arg(long, conflicts_with_all = ["secret_hex", "secret_file", "secret_fd"])]
+    pub secret_stdin: bool,
+
+    /// Read the hex-encoded secret from a file.
+    #[arg(long, conflicts_with_all = ["secret_hex", "secret_stdin", "secret_fd"])]
+    pub secret_file: Option<PathBuf>,
+
+    /// Read the hex-encoded secret from this already-open file
+    /// descriptor (Unix only).
+    #[arg(long, conflicts_with_all = ["secret_hex", "secret_stdin", "secret_file"])]
+    pub secret_fd: Option<u32>,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    /// Onion address to print the key for.
+    pub onion: String,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveArgs {
+    /// Onion address to remove the key for.
+    pub onion: String,
+
+    #[command(flatten)]
+    pub store: StoreArgs,
+}
+
+pub fn run(args: KeysArgs) -> Result<()> {
+    match args.action {
+        KeysAction::Add(args) => {
+            let secret = resolve_secret(&args)?;
+            args.store.store().store(&args.onion, &secret)?;
+            println!("stored a client-auth key for {}", args.onion);
+            Ok(())
+        }
+        KeysAction::List(args) => {
+            for onion in args.store.store().list()? {
+                println!("{onion}");
+            }
+            Ok(())
+        }
+        KeysAction::Show(args) => {
+            let secret = args.store.store().load(&args.onion)?;
+            println!("{}", output::hex_string(secret.as_bytes()));
+            Ok(())
+        }
+        KeysAction::Remove(args) => {
+            args.store.store().remove(&args.onion)?;
+            println!("removed the client-auth key for {}", args.onion);
+            Ok(())
+        }
+        #[cfg(feature = "serve")]
+        KeysAction::Identity(args) => run_identity(args),
+    }
+}
+
+#[cfg(feature = "serve")]
+fn run_identity(args: IdentityArgs) -> Result<()> {
+    match args.action {
+        IdentityAction::Generate(args) => {
+            let key = IdentityKey::generate()?;
+            let passphrase = read_passphrase("passphrase")?;
+            args.store.store().store(&args.name, &key, &passphrase)?;
+            println!("generated identity key `{}`: {}", args.name, key.onion_address());
+            Ok(())
+        }
+        IdentityAction::Import(args) => {
+            let seed = resolve_seed(&args)?;
+            let key = IdentityKey::from_seed(seed)?;
+            let passphrase = read_passphrase("passphrase")?;
+            args.store.store().store(&args.name, &key, &passphrase)?;
+            println!("imported identity key `{}`: {}", args.name, key.onion_address());
+            Ok(())
+        }
+        IdentityAction::Export(args) => {
+            let passphrase = read_passphrase("passphrase")?;
+            let key = args.store.store().load(&args.name, &passphrase)?;
+            println!("{}", key.onion_address());
+            if args.show_key_blob {
+                println!("{}", key.add_onion_key_blob());
+            }
+            Ok(())
+        }
+        IdentityAction::List(args) => {
+            for name in args.store.store().list()? {
+                println!("{name}");
+            }
+            Ok(())
+        }
+        IdentityAction::Remove(args) => {
+            args.store.store().remove(&args.name)?;
+            println!("removed the identity key `{}`", args.name);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "serve")]
+fn resolve_seed(args: &IdentityImportArgs) -> Result<SensitiveBytes> {
+    if let Some(hex) = &args.seed_hex {
+        return Ok(SensitiveBytes::new(output::decode_hex(hex)?));
+    }
+    if args.seed_stdin {
+        return HexSource::Stdin.read();
+    }
+    Err(OrcError::InvalidArgument("one of --seed-hex or --seed-stdin is required".into()))
+}
+
+fn resolve_secret(args: &AddArgs) -> Result<SensitiveBytes> {
+    if let Some(hex) = &args.secret_hex {
+        return Ok(SensitiveBytes::new(output::decode_hex(hex)?));
+    }
+    if args.secret_stdin {
+        return HexSource::Stdin.read();
+    }
+    if let Some(path) = &args.secret_file {
+        return HexSource::File(path.clone()).read();
+    }
+    if let Some(fd) = args.secret_fd {
+        return HexSource::Fd(fd).read();
+    }
+    Err(OrcError::InvalidArgument(
+        "one of --secret-hex, --secret-stdin, --secret-file, or --secret-fd is required".into(),
+    ))
+}
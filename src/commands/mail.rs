@@ -0,0 +1,223 @@
+//! `orc mail`: submit mail to an onion SMTP provider.
+
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use clap::ValueEnum;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::smtp::{self, SendRequest};
+use crate::net::{imap, pop3, tls};
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct MailArgs {
+    #[command(subcommand)]
+    pub action: MailCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MailCommand {
+    /// Submit a message via SMTP with STARTTLS and AUTH LOGIN.
+    Send(SendArgs),
+    /// Retrieve messages from an onion mail server via IMAP or POP3.
+    Fetch(FetchArgs),
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MailProtocol {
+    Imap,
+    Pop3,
+}
+
+#[derive(Debug, Args)]
+pub struct SendArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// SMTP server to submit to, as `host:port`.
+    pub server: String,
+
+    /// Domain name to present in the EHLO greeting.
+    #[arg(long, default_value = "localhost")]
+    pub helo_domain: String,
+
+    /// Envelope sender address.
+    #[arg(long)]
+    pub from: String,
+
+    /// Envelope recipient address; may be given more than once.
+    #[arg(long = "to", required = true)]
+    pub to: Vec<String>,
+
+    /// SMTP AUTH username. Prompted for interactively if omitted but
+    /// `--password-stdin` is given.
+    #[arg(long)]
+    pub username: Option<String>,
+
+    /// Read the AUTH password from stdin instead of accepting it as an
+    /// argument, keeping it out of the process's argv and shell history.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    /// File containing the message to send (headers and body). Reads
+    /// stdin if omitted.
+    #[arg(long)]
+    pub message: Option<PathBuf>,
+
+    /// Where to store trust-on-first-use certificate pins for STARTTLS.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Mail server to retrieve from, as `host:port`.
+    pub server: String,
+
+    /// Which retrieval protocol to speak. Both are assumed to run over
+    /// implicit TLS, as is standard for onion mail providers.
+    #[arg(long, value_enum)]
+    pub protocol: MailProtocol,
+
+    /// Login username.
+    #[arg(long)]
+    pub username: String,
+
+    /// Mailbox to select. Ignored for POP3, which has only one mailbox.
+    #[arg(long, default_value = "INBOX")]
+    pub mailbox: String,
+
+    /// Read the login password from stdin instead of accepting it as an
+    /// argument, keeping it out of the process's argv and shell history.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    /// Directory to write retrieved messages into, one file per message.
+    /// Messages are written to stdout, separated by a form feed, if omitted.
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: MailArgs) -> Result<()> {
+    match args.action {
+        MailCommand::Send(send_args) => run_send(send_args),
+        MailCommand::Fetch(fetch_args) => run_fetch(fetch_args),
+    }
+}
+
+fn run_send(args: SendArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let options = args.timeouts.to_connect_options();
+
+    let password = if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Some(SensitiveString::new(line.trim_end().to_string()))
+    } else {
+        None
+    };
+    let username = args.username.map(SensitiveString::new);
+
+    let mut message = Vec::new();
+    match &args.message {
+        Some(path) => {
+            message = std::fs::read(path)?;
+        }
+        None => {
+            std::io::stdin().read_to_end(&mut message)?;
+        }
+    }
+
+    smtp::send(SendRequest {
+        proxy: args.proxy,
+        host,
+        port,
+        helo_domain: &args.helo_domain,
+        username,
+        password,
+        from: &args.from,
+        to: &args.to,
+        message: &message,
+        pin_file: &pin_file,
+        options: &options,
+    })
+}
+
+fn run_fetch(args: FetchArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let options = args.timeouts.to_connect_options();
+    let username = SensitiveString::new(args.username.clone());
+
+    let password = if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        SensitiveString::new(line.trim_end().to_string())
+    } else {
+        SensitiveString::new(String::new())
+    };
+
+    let messages = match args.protocol {
+        MailProtocol::Imap => imap::fetch_all(imap::FetchRequest {
+            proxy: args.proxy,
+            host,
+            port,
+            username: &username,
+            password: &password,
+            mailbox: &args.mailbox,
+            pin_file: &pin_file,
+            options: &options,
+        })?,
+        MailProtocol::Pop3 => pop3::fetch_all(pop3::FetchRequest {
+            proxy: args.proxy,
+            host,
+            port,
+            username: &username,
+            password: &password,
+            pin_file: &pin_file,
+            options: &options,
+        })?,
+    };
+
+    match &args.output_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            for (i, message) in messages.iter().enumerate() {
+                std::fs::write(dir.join(format!("{}.eml", i + 1)), message)?;
+            }
+        }
+        None => {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            for (i, message) in messages.iter().enumerate() {
+                if i > 0 {
+                    stdout.write_all(b"\x0c")?;
+                }
+                stdout.write_all(message)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! `orc matrix`: log into a Matrix homeserver, sync the timeline, or send
+//! a message. Each invocation logs in fresh; there's no token cache
+//! between runs.
+//!
+//! None of `fetch-batch`, `mirror`, `events`, or `probe --repeat` exist
+//! as commands in this crate — `sync` below is the one command that
+//! already emits results incrementally as a long-running loop rather
+//! than all at once, so its `--ndjson` is where that idea lands here.
+
+use std::net::SocketAddr;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::json::Value;
+use crate::net::matrix::{self, LoginRequest};
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct MatrixArgs {
+    #[command(subcommand)]
+    pub action: MatrixCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MatrixCommand {
+    /// Log in and print timeline messages as they arrive.
+    Sync(SyncArgs),
+    /// Log in and send a single message to a room.
+    Send(SendArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct LoginArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Base URL of the homeserver, e.g. `http://matrix.onion`.
+    pub homeserver: String,
+
+    /// Account username (the localpart, without `@` or the domain).
+    pub username: String,
+
+    /// Read the account password from stdin.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    #[command(flatten)]
+    pub login: LoginArgs,
+
+    /// How long each long-poll waits for new events, in milliseconds.
+    #[arg(long, default_value_t = 30_000)]
+    pub timeout_ms: u64,
+
+    /// Stop after this many sync rounds. Runs until killed if omitted.
+    #[arg(long)]
+    pub count: Option<usize>,
+
+    /// Print each message as its own JSON object (NDJSON — one per
+    /// line, flushed as it arrives) instead of the tab-separated
+    /// default, so a long-running `sync` can be piped into something
+    /// that processes messages incrementally rather than waiting for
+    /// this to exit.
+    #[arg(long)]
+    pub ndjson: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SendArgs {
+    #[command(flatten)]
+    pub login: LoginArgs,
+
+    /// Room ID to send to, e.g. `!abc123:onion.example`.
+    #[arg(long)]
+    pub room: String,
+
+    /// Message body.
+    pub message: String,
+}
+
+pub fn run(args: MatrixArgs) -> Result<()> {
+    match args.action {
+        MatrixCommand::Sync(args) => run_sync(args),
+        MatrixCommand::Send(args) => run_send(args),
+    }
+}
+
+fn login(args: &LoginArgs) -> Result<matrix::MatrixSession> {
+    let password = read_password(args.password_stdin)?;
+    let options = args.timeouts.to_connect_options();
+    matrix::login(LoginRequest { proxy: args.proxy, homeserver_url: &args.homeserver, username: &args.username, password: &password, options: &options })
+}
+
+fn read_password(from_stdin: bool) -> Result<SensitiveString> {
+    if !from_stdin {
+        eprint!("password: ");
+    }
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+fn run_sync(args: SyncArgs) -> Result<()> {
+    let session = login(&args.login)?;
+    let mut since = None;
+    let mut round = 0;
+    loop {
+        let response = session.sync(since.as_deref(), args.timeout_ms)?;
+        for message in &response.messages {
+            if args.ndjson {
+                println!("{}", message_to_json(message));
+            } else {
+                println!("{}\t{}\t{}", message.room_id, message.sender, message.body);
+            }
+        }
+        since = Some(response.next_batch);
+
+        round += 1;
+        if args.count.is_some_and(|count| round >= count) {
+            return Ok(());
+        }
+    }
+}
+
+fn message_to_json(message: &matrix::TimelineMessage) -> Value {
+    Value::Object(vec![
+        ("room_id".to_string(), Value::String(message.room_id.clone())),
+        ("sender".to_string(), Value::String(message.sender.clone())),
+        ("body".to_string(), Value::String(message.body.clone())),
+    ])
+}
+
+fn run_send(args: SendArgs) -> Result<()> {
+    let mut session = login(&args.login)?;
+    session.send_message(&args.room, &args.message)
+}
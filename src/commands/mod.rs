@@ -0,0 +1,37 @@
+pub mod audit;
+pub mod bench;
+#[cfg(feature = "tui")]
+pub mod browse;
+pub mod chat;
+pub mod common;
+pub mod config;
+pub mod decrypt;
+pub mod electrum;
+#[cfg(feature = "http")]
+pub mod feed;
+#[cfg(feature = "http")]
+pub mod fetch;
+#[cfg(feature = "tcp")]
+pub mod forward;
+pub mod gemini;
+pub mod irc;
+pub mod keys;
+pub mod mail;
+pub mod matrix;
+pub mod mqtt;
+#[cfg(feature = "tcp")]
+pub mod nc;
+pub mod nntp;
+pub mod oshare;
+pub mod panic;
+pub mod repl;
+pub mod resolve;
+#[cfg(feature = "http")]
+pub mod rpc;
+pub mod session;
+#[cfg(all(feature = "serve", feature = "control-port"))]
+pub mod serve;
+#[cfg(feature = "tcp")]
+pub mod stream;
+pub mod trail;
+pub mod xmpp;
@@ -0,0 +1,132 @@
+//! `orc mqtt`: publish to or subscribe from an MQTT broker over Tor.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::{OrcError, Result};
+use crate::net::mqtt::MqttConnection;
+use crate::output::{self, OutputFormat};
+
+#[derive(Debug, Args)]
+pub struct MqttArgs {
+    #[command(subcommand)]
+    pub action: MqttCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MqttCommand {
+    /// Publish a single message and disconnect.
+    Pub(PubArgs),
+    /// Subscribe to a topic filter and print messages as they arrive.
+    Sub(SubArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PubArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Broker to connect to, as `host:port`.
+    pub server: String,
+
+    /// Topic to publish to.
+    pub topic: String,
+
+    /// Message payload.
+    pub payload: String,
+
+    /// QoS level (0 or 1).
+    #[arg(long, default_value_t = 0)]
+    pub qos: u8,
+
+    /// MQTT client identifier.
+    #[arg(long, default_value = "orc-pub")]
+    pub client_id: String,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SubArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Broker to connect to, as `host:port`.
+    pub server: String,
+
+    /// Topic filter to subscribe to; may use `+` and `#` wildcards.
+    pub topic: String,
+
+    /// Maximum QoS to request (0 or 1).
+    #[arg(long, default_value_t = 0)]
+    pub qos: u8,
+
+    /// MQTT client identifier.
+    #[arg(long, default_value = "orc-sub")]
+    pub client_id: String,
+
+    /// Stop after receiving this many messages. Runs until killed if
+    /// omitted.
+    #[arg(long)]
+    pub count: Option<usize>,
+
+    /// How to render each payload.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Raw)]
+    pub output: OutputFormat,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: MqttArgs) -> Result<()> {
+    match args.action {
+        MqttCommand::Pub(pub_args) => run_pub(pub_args),
+        MqttCommand::Sub(sub_args) => run_sub(sub_args),
+    }
+}
+
+fn run_pub(args: PubArgs) -> Result<()> {
+    validate_qos(args.qos)?;
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+
+    let mut connection = MqttConnection::connect(args.proxy, host, port, &args.client_id, Duration::from_secs(60), &options)?;
+    connection.publish(&args.topic, args.payload.as_bytes(), args.qos)?;
+    connection.disconnect()?;
+    Ok(())
+}
+
+fn run_sub(args: SubArgs) -> Result<()> {
+    validate_qos(args.qos)?;
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+
+    let mut connection = MqttConnection::connect(args.proxy, host, port, &args.client_id, Duration::from_secs(60), &options)?;
+    connection.subscribe(&args.topic, args.qos)?;
+
+    let mut received = 0;
+    loop {
+        let message = connection.read_message()?;
+        let rendered = output::render(&message.payload, args.output);
+        println!("{}\tqos{}\t{}", message.topic, message.qos, String::from_utf8_lossy(&rendered));
+
+        received += 1;
+        if args.count.is_some_and(|count| received >= count) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn validate_qos(qos: u8) -> Result<()> {
+    if qos > 1 {
+        return Err(OrcError::InvalidArgument("only QoS 0 and 1 are supported".into()));
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! `orc nc`: bridge stdin/stdout to a SOCKS stream with no extra output,
+//! for use as an OpenSSH `ProxyCommand` (`ProxyCommand orc nc %h %p`).
+
+use std::io;
+use std::net::SocketAddr;
+use std::thread;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::tcp::create_socks_stream;
+
+#[derive(Debug, Args)]
+pub struct NcArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Onion or regular hostname to connect to.
+    pub host: String,
+
+    /// Port to connect to.
+    pub port: u16,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: NcArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+    let stream = create_socks_stream(args.proxy, &args.host, args.port, &options)?;
+
+    let mut upload_stream = stream.try_clone()?;
+    let upload = thread::spawn(move || io::copy(&mut io::stdin(), &mut upload_stream));
+
+    let mut download_stream = stream.try_clone()?;
+    io::copy(&mut download_stream, &mut io::stdout())?;
+
+    upload.join().expect("upload thread panicked")?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! `orc nntp`: list newsgroups, fetch headers, and retrieve articles from
+//! an onion NNTP server. Each invocation opens its own connection.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::nntp::{self, ArticleFetchRequest, HeaderFetchRequest};
+
+#[derive(Debug, Args)]
+pub struct NntpArgs {
+    #[command(subcommand)]
+    pub action: NntpCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NntpCommand {
+    /// List every newsgroup the server carries.
+    Groups(GroupsArgs),
+    /// Fetch overview headers for a range of articles in a newsgroup.
+    Headers(HeadersArgs),
+    /// Retrieve the raw text of a single article.
+    Article(ArticleArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct GroupsArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// NNTP server to query, as `host:port`.
+    pub server: String,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct HeadersArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// NNTP server to query, as `host:port`.
+    pub server: String,
+
+    /// Newsgroup to select.
+    pub group: String,
+
+    /// Article range to fetch, in NNTP form (e.g. `1-100` or `3000-`).
+    #[arg(long, default_value = "1-")]
+    pub range: String,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ArticleArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// NNTP server to query, as `host:port`.
+    pub server: String,
+
+    /// Newsgroup to select.
+    pub group: String,
+
+    /// Article number to retrieve.
+    pub number: u64,
+
+    /// File to write the article to. Written to stdout if omitted.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: NntpArgs) -> Result<()> {
+    match args.action {
+        NntpCommand::Groups(groups_args) => run_groups(groups_args),
+        NntpCommand::Headers(headers_args) => run_headers(headers_args),
+        NntpCommand::Article(article_args) => run_article(article_args),
+    }
+}
+
+fn run_groups(args: GroupsArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+    let groups = nntp::list_groups(args.proxy, host, port, &options)?;
+    for group in groups {
+        println!("{} {} {} {}", group.name, group.low, group.high, group.status);
+    }
+    Ok(())
+}
+
+fn run_headers(args: HeadersArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+    let headers = nntp::fetch_headers(HeaderFetchRequest {
+        proxy: args.proxy,
+        host,
+        port,
+        group: &args.group,
+        range: &args.range,
+        options: &options,
+    })?;
+    for header in headers {
+        println!("{}\t{}\t{}\t{}\t{}", header.number, header.date, header.from, header.subject, header.message_id);
+    }
+    Ok(())
+}
+
+fn run_article(args: ArticleArgs) -> Result<()> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let options = args.timeouts.to_connect_options();
+    let article = nntp::fetch_article(ArticleFetchRequest {
+        proxy: args.proxy,
+        host,
+        port,
+        group: &args.group,
+        number: args.number,
+        options: &options,
+    })?;
+
+    match &args.output {
+        Some(path) => std::fs::write(path, article)?,
+        None => {
+            use std::io::Write;
+            std::io::stdout().write_all(&article)?;
+        }
+    }
+    Ok(())
+}
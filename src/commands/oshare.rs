@@ -0,0 +1,136 @@
+//! `orc oshare`: send to and receive from an OnionShare instance.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::download_crypto;
+use crate::download_verify;
+use crate::error::Result;
+use crate::killswitch;
+use crate::net::oshare;
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct OshareArgs {
+    #[command(subcommand)]
+    pub action: OshareCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OshareCommand {
+    /// Upload a file to an OnionShare receive-mode instance.
+    Send(SendArgs),
+    /// Download the shared archive from an OnionShare send-mode instance.
+    Get(GetArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SendArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// File to upload.
+    pub file: PathBuf,
+
+    /// Base URL of the OnionShare instance, e.g. `http://abc.onion`.
+    pub url: String,
+
+    /// Read the share's password (from the end of the OnionShare URL)
+    /// from stdin instead of accepting it as an argument, keeping it
+    /// out of the process's argv and shell history.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct GetArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Base URL of the OnionShare instance, e.g. `http://abc.onion`.
+    pub url: String,
+
+    /// Read the share's password (from the end of the OnionShare URL)
+    /// from stdin instead of accepting it as an argument, keeping it
+    /// out of the process's argv and shell history.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    /// File to write the downloaded archive to.
+    #[arg(long, default_value = "download.zip")]
+    pub output: PathBuf,
+
+    /// Encrypt the archive under a passphrase prompted on stdin before
+    /// it's written to `--output`, so only ciphertext ever touches disk.
+    #[arg(long)]
+    pub passphrase_stdin: bool,
+
+    /// Encrypt the archive to an age recipient instead of a passphrase.
+    /// Not currently supported — see `orc oshare get --help` or
+    /// [`crate::download_crypto`] for why — and rejected with an error
+    /// pointing at `--passphrase-stdin`.
+    #[arg(long, conflicts_with = "passphrase_stdin", value_name = "age1...")]
+    pub encrypt_to: Option<String>,
+
+    /// Verify the downloaded archive's SHA-256 before it's kept on disk,
+    /// securely wiping the file and exiting with an error on a mismatch.
+    #[arg(long, value_name = "HASH")]
+    pub sha256: Option<String>,
+
+    /// Verify a minisign signature over the archive instead. Not
+    /// currently supported — see `orc oshare get --help` or
+    /// [`crate::download_verify`] for why — and rejected with an error
+    /// pointing at `--sha256`.
+    #[arg(long, conflicts_with = "sha256", value_name = "PUBKEY")]
+    pub verify_minisign: Option<String>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: OshareArgs) -> Result<()> {
+    match args.action {
+        OshareCommand::Send(send_args) => run_send(send_args),
+        OshareCommand::Get(get_args) => run_get(get_args),
+    }
+}
+
+fn run_send(args: SendArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+    let password = read_password(args.password_stdin)?;
+    let file_bytes = std::fs::read(&args.file)?;
+    let file_name = args.file.file_name().and_then(|n| n.to_str()).unwrap_or("upload.bin");
+
+    oshare::upload(args.proxy, &args.url, file_name, &file_bytes, &password, &options)
+}
+
+fn run_get(args: GetArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+    let password = read_password(args.password_stdin)?;
+    let encryption = download_crypto::resolve(args.passphrase_stdin, args.encrypt_to.as_deref())?;
+    let verification = download_verify::resolve(args.sha256.as_deref(), args.verify_minisign.as_deref())?;
+    let archive = oshare::download(args.proxy, &args.url, &password, &options)?;
+    std::fs::write(&args.output, download_crypto::apply(&archive, &encryption))?;
+    if let Err(err) = download_verify::verify(&archive, &verification) {
+        killswitch::secure_wipe_file(&args.output)?;
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn read_password(from_stdin: bool) -> Result<SensitiveString> {
+    if !from_stdin {
+        eprint!("password: ");
+    }
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
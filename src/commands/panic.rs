@@ -0,0 +1,20 @@
+//! `orc panic`: trigger [`crate::killswitch::trigger`] as an explicit
+//! command, for anything that isn't already one of the line-oriented
+//! REPLs with a typed kill-switch phrase of its own (`orc repl`, `orc
+//! session`, `orc browse`) — a script that wants to wipe state on some
+//! condition of its own, or a user who'd rather run a command than type
+//! a magic word.
+
+use clap::Args;
+
+use crate::error::Result;
+use crate::killswitch;
+
+#[derive(Debug, Args)]
+pub struct PanicArgs {}
+
+pub fn run(_args: PanicArgs) -> Result<()> {
+    let wipe_paths = killswitch::load_wipe_paths()?;
+    let paths: Vec<&std::path::Path> = wipe_paths.iter().map(std::path::PathBuf::as_path).collect();
+    killswitch::trigger(&mut [], &paths);
+}
@@ -0,0 +1,80 @@
+//! `orc repl`: run any other `orc` command repeatedly in one process.
+//!
+//! Each line on stdin is split on whitespace (no quoting or escapes) and
+//! re-parsed as a full `orc` invocation (`fetch <url>` runs `orc fetch`,
+//! `stream <host:port>` runs `orc stream`, and so on) — whatever
+//! top-level subcommand and flags would work on the real command line
+//! work here too, via the same [`crate::cli::dispatch`] every invocation
+//! of the binary goes through. `orc browse`'s own line commands, such as
+//! `newnym`, are not top-level subcommands and are not available here.
+//! There's no line-editing or persistent history
+//! (no readline-style dependency in this crate), so it reads like the
+//! rest of `orc`'s REPLs rather than a shell.
+//!
+//! A line's first word is also expanded against the default config
+//! file's `"aliases"` section (see [`crate::cli::expand_aliases`]) before
+//! it's parsed, loaded once up front rather than re-read for every line.
+//!
+//! The saving here is real even without a pooled connection: `orc`
+//! doesn't keep a Tor connection open between commands anyway (each one
+//! dials fresh over the SOCKS proxy), so what this avoids is the
+//! per-invocation process startup cost, not a proxy handshake.
+//!
+//! There's also a kill switch: typing the word set by `--kill-switch`
+//! (`panic` by default) alone on a line exits with status 137 instead of
+//! running it as a command. See [`crate::killswitch`].
+
+use std::io::{self, BufRead};
+
+use clap::{Args, Parser};
+
+use crate::cli::{self, Cli};
+use crate::commands::common::KillSwitchArgs;
+use crate::config;
+use crate::error::Result;
+use crate::killswitch;
+
+#[derive(Debug, Args)]
+pub struct ReplArgs {
+    #[command(flatten)]
+    pub kill_switch: KillSwitchArgs,
+}
+
+pub fn run(args: ReplArgs) -> Result<()> {
+    let aliases = config::load_aliases()?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == args.kill_switch.kill_switch {
+            let wipe_paths = killswitch::load_wipe_paths()?;
+            let paths: Vec<&std::path::Path> = wipe_paths.iter().map(std::path::PathBuf::as_path).collect();
+            killswitch::trigger(&mut [], &paths);
+        }
+        if line == "quit" {
+            break;
+        }
+
+        let argv: Vec<String> = std::iter::once("orc".to_string()).chain(line.split_whitespace().map(str::to_string)).collect();
+        let argv = match cli::expand_aliases(argv, &aliases) {
+            Ok(argv) => argv,
+            Err(err) => {
+                eprintln!("orc: {err}");
+                continue;
+            }
+        };
+        match Cli::try_parse_from(argv) {
+            Ok(parsed) => {
+                if let Err(err) = cli::dispatch(parsed.command) {
+                    eprintln!("orc: {err}");
+                }
+            }
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+
+    Ok(())
+}
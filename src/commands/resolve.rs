@@ -0,0 +1,58 @@
+//! `orc resolve`: look up a hostname or IP address through Tor's exit
+//! resolvers via the SOCKS5 RESOLVE/RESOLVE_PTR extension, instead of
+//! leaking the query to the local OS resolver.
+
+use std::net::SocketAddr;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::resolve;
+
+#[derive(Debug, Args)]
+pub struct ResolveArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Hostname to resolve, or an IP address when `--reverse` is given.
+    pub target: String,
+
+    /// Perform a reverse (PTR) lookup of an IP address instead of a
+    /// forward lookup of a hostname.
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Print the result as a JSON object instead of plain text.
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: ResolveArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+
+    let answer = if args.reverse {
+        let addr = args
+            .target
+            .parse()
+            .map_err(|_| crate::error::OrcError::InvalidArgument(format!("`{}` is not an IP address", args.target)))?;
+        resolve::resolve_ptr(args.proxy, addr, &options)?
+    } else {
+        resolve::resolve(args.proxy, &args.target, &options)?.to_string()
+    };
+
+    if args.json {
+        println!("{{\"query\":\"{}\",\"result\":\"{}\"}}", escape_json(&args.target), escape_json(&answer));
+    } else {
+        println!("{answer}");
+    }
+    Ok(())
+}
+
+fn escape_json(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,96 @@
+//! `orc rpc`: call one or more JSON-RPC methods on an onion node over a
+//! single HTTP POST.
+
+use std::net::SocketAddr;
+
+use clap::Args;
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::net::rpc::{self, Call, RpcRequest};
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct RpcArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Full URL of the RPC endpoint, e.g. `http://node.onion:8332/`.
+    #[arg(long)]
+    pub url: String,
+
+    /// RPC method to call. Pass more than once, alongside a matching
+    /// number of `--params`, to send a batch in a single request.
+    #[arg(long = "method", required = true)]
+    pub methods: Vec<String>,
+
+    /// JSON array of parameters for the method in the same position.
+    /// Defaults to `[]` for any method without a matching `--params`.
+    #[arg(long = "params")]
+    pub params: Vec<String>,
+
+    /// HTTP Basic auth username, as used by Bitcoin Core's RPC server.
+    #[arg(long)]
+    pub username: Option<String>,
+
+    /// Read the HTTP Basic auth password from stdin.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+pub fn run(args: RpcArgs) -> Result<()> {
+    if args.params.len() > args.methods.len() {
+        return Err(OrcError::InvalidArgument("more --params given than --method".into()));
+    }
+
+    let mut params = Vec::with_capacity(args.methods.len());
+    for i in 0..args.methods.len() {
+        let raw = args.params.get(i).map(String::as_str).unwrap_or("[]");
+        params.push(json::parse(raw)?);
+    }
+    let calls: Vec<Call> = args.methods.iter().zip(params.iter()).map(|(method, params)| Call { method, params: params.clone() }).collect();
+
+    let password = if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Some(SensitiveString::new(line.trim_end().to_string()))
+    } else {
+        None
+    };
+    let options = args.timeouts.to_connect_options();
+
+    let response = rpc::call(RpcRequest {
+        proxy: args.proxy,
+        url: &args.url,
+        calls: &calls,
+        username: args.username.as_deref(),
+        password: password.as_ref(),
+        options: &options,
+    })?;
+
+    println!("{response}");
+    check_for_errors(&response)
+}
+
+/// Reports a non-zero exit if any response in the batch carries a
+/// top-level JSON-RPC `"error"` field, after printing the full response.
+fn check_for_errors(response: &Value) -> Result<()> {
+    let responses: Vec<&Value> = match response.as_array() {
+        Some(items) => items.iter().collect(),
+        None => vec![response],
+    };
+
+    for item in responses {
+        if let Some(error) = item.get("error") {
+            if *error != Value::Null {
+                return Err(OrcError::Socks(format!("RPC call failed: {error}")));
+            }
+        }
+    }
+    Ok(())
+}
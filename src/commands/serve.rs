@@ -0,0 +1,482 @@
+//! `orc serve`: a minimal embedded HTTP/1.1 server bound to `127.0.0.1`,
+//! published as a Tor v3 onion service over the control port — this
+//! crate's only command that publishes an onion service itself (`orc
+//! chat --listen` relies on one already set up in `torrc`). `orc serve
+//! dir <path>` is the one action
+//! so far: publish a local directory, with directory listings for
+//! anything without its own `index.html` and a small built-in
+//! extension-to-content-type table.
+//!
+//! The embedded server only ever binds `127.0.0.1` — it's Tor's own
+//! process, not this one, that's reachable from the network, and it
+//! reaches this server the same way any other onion service backend is
+//! reached: a plain TCP connection to a local port, forwarded there by
+//! `ADD_ONION`'s `Port=` mapping. None of this goes through `--proxy`;
+//! [`crate::net::torctl`]'s control port is reached directly, the same
+//! way [`crate::commands::browse`]'s `--control-port` already is.
+//!
+//! Ephemeral by default — a fresh key Tor generates and immediately
+//! discards (`Flags=DiscardPK`), so the address changes every run.
+//! `--identity <name>` publishes under a stored
+//! [`crate::net::onion_identity`] key instead, so the address survives
+//! restarting this command. There's no `Flags=Detach` either way:
+//! closing the control connection (this process exiting, however it
+//! exits) tears the service down along with it, rather than leaving an
+//! onion address reachable after the local HTTP server behind it has
+//! already stopped.
+//!
+//! Only `GET`/`HEAD` are handled, one request per connection
+//! (`Connection: close`, no keep-alive) — enough to serve static files,
+//! not a general-purpose web server.
+//!
+//! `--client <name>` (repeatable) turns on Tor's v3 client
+//! authorization instead of leaving the service reachable by anyone who
+//! learns the address: a fresh x25519 keypair is generated per name
+//! with [`crate::net::onion_auth::ClientAuthKeypair`], its public half
+//! goes to `ADD_ONION` as a `ClientAuthV3=` flag, and its secret half is
+//! printed as the line that client drops into their own
+//! `ClientOnionAuthDir` — this crate never stores that secret itself,
+//! since once it's handed off there's nothing left for `orc` to do with it.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use clap::{Args, Subcommand};
+
+use crate::error::{OrcError, Result};
+use crate::net::onion_auth::ClientAuthKeypair;
+use crate::net::onion_identity::{self, IdentityKeyStore};
+use crate::net::torctl::{self, TorControlClient};
+use crate::secret::SensitiveString;
+
+/// How large a request's headers are allowed to grow before this server
+/// gives up on it — generous for any real browser request, small enough
+/// that a misbehaving or hostile client can't make this thread buffer
+/// without bound.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(subcommand)]
+    pub action: ServeAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ServeAction {
+    /// Serve a local directory as an onion website.
+    Dir(ServeDirArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ServeDirArgs {
+    /// Directory to serve.
+    pub path: PathBuf,
+
+    /// Local address to bind the embedded HTTP server to. `:0` (the
+    /// default) asks the OS for any free port — the one `ADD_ONION`'s
+    /// `Port=` then forwards to, so there's no need to pick one by hand.
+    #[arg(long, default_value = "127.0.0.1:0")]
+    pub listen: SocketAddr,
+
+    /// Port the onion address is reached on from the outside. Defaults
+    /// to the usual port for a plain `http://` site.
+    #[arg(long, default_value_t = 80)]
+    pub onion_port: u16,
+
+    /// Address of Tor's control port, used to publish the service.
+    #[arg(long, default_value = "127.0.0.1:9051")]
+    pub control_port: SocketAddr,
+
+    /// Read the control port password from stdin instead of using cookie
+    /// or NULL authentication.
+    #[arg(long)]
+    pub control_password_stdin: bool,
+
+    /// Authenticate to the control port with the cookie at this path
+    /// (`torrc`'s `CookieAuthFile`) instead of a password.
+    #[arg(long)]
+    pub control_cookie_file: Option<PathBuf>,
+
+    /// Publish under a stored identity key instead of a fresh ephemeral
+    /// one, so the onion address survives restarting this command. Reads
+    /// the key's passphrase from stdin.
+    #[arg(long)]
+    pub identity: Option<String>,
+
+    /// Directory holding one encrypted identity key file per name,
+    /// instead of the default `~/.config/orc/onion_identity/`. Ignored
+    /// without `--identity`.
+    #[arg(long)]
+    pub identity_store_dir: Option<PathBuf>,
+
+    /// Require v3 client authorization, naming one client this is for.
+    /// Repeat for more than one client. For each name, a fresh x25519
+    /// keypair is generated and the line that client needs for their own
+    /// `ClientOnionAuthDir` is printed — without at least one `--client`,
+    /// the service is reachable by anyone who learns the address.
+    #[arg(long = "client", value_name = "NAME")]
+    pub clients: Vec<String>,
+}
+
+pub fn run(args: ServeArgs) -> Result<()> {
+    match args.action {
+        ServeAction::Dir(args) => run_dir(args),
+    }
+}
+
+fn run_dir(args: ServeDirArgs) -> Result<()> {
+    let root = args
+        .path
+        .canonicalize()
+        .map_err(|err| OrcError::InvalidArgument(format!("`{}` is not a directory orc can serve: {err}", args.path.display())))?;
+    if !root.is_dir() {
+        return Err(OrcError::InvalidArgument(format!("`{}` is not a directory", args.path.display())));
+    }
+
+    let listener = TcpListener::bind(args.listen)?;
+    let local_addr = listener.local_addr()?;
+
+    let key_blob = resolve_identity_key_blob(&args)?;
+    let clients: Vec<(String, ClientAuthKeypair)> =
+        args.clients.iter().map(|name| Ok((name.clone(), ClientAuthKeypair::generate()?))).collect::<Result<_>>()?;
+    let public_keys: Vec<String> = clients.iter().map(|(_, key)| key.public_base32()).collect();
+
+    let control_password = if args.control_password_stdin { Some(read_line("control port password")?) } else { None };
+    let auth = match (&control_password, &args.control_cookie_file) {
+        (Some(password), _) => torctl::Auth::Password(password),
+        (None, Some(cookie_file)) => torctl::Auth::CookieFile(cookie_file),
+        (None, None) => torctl::Auth::Null,
+    };
+    let mut control = TorControlClient::connect(args.control_port, auth)?;
+    let service_id = control.add_onion(key_blob.as_deref(), args.onion_port, local_addr, &public_keys)?;
+
+    eprintln!("orc: serving {} as http://{service_id}.onion{}", root.display(), onion_port_suffix(args.onion_port));
+    eprintln!("orc: local listener on {local_addr} — ctrl-c stops this process and tears the service down with it");
+    for (name, key) in &clients {
+        eprintln!(
+            "orc: client `{name}` needs this as ~/.local/share/tor/onion_auth/{name}.auth_private — {}",
+            key.client_auth_line(&service_id)
+        );
+    }
+
+    for incoming in listener.incoming() {
+        let stream = match incoming {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("orc: failed to accept connection: {err}");
+                continue;
+            }
+        };
+        let root = root.clone();
+        thread::spawn(move || handle_connection(stream, root));
+    }
+
+    Ok(())
+}
+
+fn onion_port_suffix(port: u16) -> String {
+    if port == 80 {
+        String::new()
+    } else {
+        format!(":{port}")
+    }
+}
+
+fn resolve_identity_key_blob(args: &ServeDirArgs) -> Result<Option<String>> {
+    let Some(name) = &args.identity else {
+        return Ok(None);
+    };
+    let store = IdentityKeyStore::new(args.identity_store_dir.clone().unwrap_or_else(onion_identity::default_identity_dir));
+    let passphrase = read_line("identity passphrase")?;
+    let key = store.load(name, &passphrase)?;
+    Ok(Some(key.add_onion_key_blob()))
+}
+
+fn read_line(prompt: &str) -> Result<SensitiveString> {
+    eprint!("{prompt}: ");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end().to_string()))
+}
+
+fn handle_connection(mut stream: TcpStream, root: PathBuf) {
+    if let Err(err) = serve_request(&mut stream, &root) {
+        eprintln!("orc: request failed: {err}");
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+}
+
+fn serve_request(stream: &mut TcpStream, root: &Path) -> Result<()> {
+    let request = read_request(stream)?;
+    let (status, content_type, body) = respond(&request, root);
+    write_response(stream, status, content_type, &body, request.method == "HEAD")
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut raw = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = std::str::from_utf8(&raw[..end]).map_err(|_| OrcError::InvalidArgument("request headers are not valid UTF-8".into()))?;
+            return parse_request_line(head);
+        }
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Err(OrcError::InvalidArgument("connection closed before a full request was read".into()));
+        }
+        raw.extend_from_slice(&chunk[..read]);
+        if raw.len() > MAX_REQUEST_HEAD {
+            return Err(OrcError::InvalidArgument("request headers exceeded the size limit".into()));
+        }
+    }
+}
+
+fn parse_request_line(head: &str) -> Result<Request> {
+    let line = head.lines().next().ok_or_else(|| OrcError::InvalidArgument("empty request".into()))?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| OrcError::InvalidArgument("request line has no method".into()))?.to_string();
+    let path = parts.next().ok_or_else(|| OrcError::InvalidArgument("request line has no path".into()))?.to_string();
+    Ok(Request { method, path })
+}
+
+fn respond(request: &Request, root: &Path) -> (u16, &'static str, Vec<u8>) {
+    if request.method != "GET" && request.method != "HEAD" {
+        return (405, "text/plain", b"405 Method Not Allowed\n".to_vec());
+    }
+    match resolve_path(root, &request.path) {
+        Some(path) if path.is_dir() => {
+            let index = path.join("index.html");
+            if index.is_file() {
+                read_file(&index).unwrap_or_else(not_found)
+            } else {
+                (200, "text/html", directory_listing(&path, &request.path))
+            }
+        }
+        Some(path) if path.is_file() => read_file(&path).unwrap_or_else(not_found),
+        _ => not_found(),
+    }
+}
+
+fn not_found() -> (u16, &'static str, Vec<u8>) {
+    (404, "text/plain", b"404 Not Found\n".to_vec())
+}
+
+fn read_file(path: &Path) -> Option<(u16, &'static str, Vec<u8>)> {
+    std::fs::read(path).ok().map(|bytes| (200, content_type_for(path), bytes))
+}
+
+/// Maps a request path onto a file under `root`, refusing anything that
+/// would escape it — a `..` path segment is rejected outright, and the
+/// joined path is double-checked to still start with `root` afterward,
+/// the same belt-and-suspenders [`crate::security::check_host`] already
+/// applies to every other target this crate touches. Doesn't follow
+/// symlinks specially: one that points outside `root` is served as-is,
+/// the same risk any static file server that doesn't special-case
+/// symlinks carries.
+fn resolve_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let path_only = request_path.split('?').next().unwrap_or(request_path);
+    let decoded = percent_decode(path_only);
+    let relative = decoded.trim_start_matches('/');
+    if relative.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    let candidate = if relative.is_empty() { root.to_path_buf() } else { root.join(relative) };
+    if !candidate.starts_with(root) || !candidate.exists() {
+        return None;
+    }
+    Some(candidate)
+}
+
+/// Decodes `%XX` escapes in a request path. Falls back to the original
+/// text if the decoded bytes aren't valid UTF-8, rather than failing the
+/// request over a malformed escape.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Ok(value) = u8::from_str_radix(std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or(""), 16) {
+                out.push(value);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| input.to_string())
+}
+
+fn directory_listing(dir: &Path, request_path: &str) -> Vec<u8> {
+    let mut entries: Vec<String> = std::fs::read_dir(dir)
+        .map(|read| {
+            read.filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if entry.path().is_dir() {
+                        format!("{name}/")
+                    } else {
+                        name
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+
+    let mut html = format!("<!doctype html>\n<title>{0}</title>\n<h1>{0}</h1>\n<ul>\n", html_escape(request_path));
+    for entry in entries {
+        let escaped = html_escape(&entry);
+        html.push_str(&format!("<li><a href=\"{escaped}\">{escaped}</a></li>\n"));
+    }
+    html.push_str("</ul>\n");
+    html.into_bytes()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("pdf") => "application/pdf",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("wasm") => "application/wasm",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, content_type: &str, body: &[u8], head_only: bool) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reason_phrase(status),
+        body.len()
+    );
+    stream.write_all(head.as_bytes())?;
+    if !head_only {
+        stream.write_all(body)?;
+    }
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("orc-serve-test-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn resolve_path_serves_a_file_inside_root() {
+        let dir = temp_dir("file");
+        std::fs::write(dir.join("hello.txt"), b"hi").unwrap();
+        let resolved = resolve_path(&dir, "/hello.txt").unwrap();
+        assert_eq!(resolved, dir.join("hello.txt"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn resolve_path_rejects_a_parent_directory_escape() {
+        let dir = temp_dir("escape");
+        assert!(resolve_path(&dir, "/../etc/passwd").is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn resolve_path_rejects_a_missing_file() {
+        let dir = temp_dir("missing");
+        assert!(resolve_path(&dir, "/nope.txt").is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn percent_decode_handles_escaped_spaces() {
+        assert_eq!(percent_decode("/a%20b.txt"), "/a b.txt");
+    }
+
+    #[test]
+    fn percent_decode_leaves_a_malformed_escape_alone() {
+        assert_eq!(percent_decode("/100%off"), "/100%off");
+    }
+
+    #[test]
+    fn content_type_for_recognizes_common_extensions() {
+        assert_eq!(content_type_for(Path::new("index.html")), "text/html");
+        assert_eq!(content_type_for(Path::new("style.css")), "text/css");
+        assert_eq!(content_type_for(Path::new("data.bin")), "application/octet-stream");
+    }
+
+    #[test]
+    fn respond_rejects_a_non_get_head_method() {
+        let dir = temp_dir("method");
+        let request = Request { method: "POST".to_string(), path: "/".to_string() };
+        let (status, _, _) = respond(&request, &dir);
+        assert_eq!(status, 405);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn respond_lists_a_directory_without_an_index() {
+        let dir = temp_dir("listing");
+        std::fs::write(dir.join("a.txt"), b"a").unwrap();
+        let request = Request { method: "GET".to_string(), path: "/".to_string() };
+        let (status, content_type, body) = respond(&request, &dir);
+        assert_eq!(status, 200);
+        assert_eq!(content_type, "text/html");
+        assert!(String::from_utf8(body).unwrap().contains("a.txt"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn respond_serves_index_html_when_present() {
+        let dir = temp_dir("index");
+        std::fs::write(dir.join("index.html"), b"<p>hi</p>").unwrap();
+        let request = Request { method: "GET".to_string(), path: "/".to_string() };
+        let (status, content_type, body) = respond(&request, &dir);
+        assert_eq!(status, 200);
+        assert_eq!(content_type, "text/html");
+        assert_eq!(body, b"<p>hi</p>");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn respond_returns_404_for_a_missing_file() {
+        let dir = temp_dir("notfound");
+        let request = Request { method: "GET".to_string(), path: "/nope".to_string() };
+        let (status, _, _) = respond(&request, &dir);
+        assert_eq!(status, 404);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,172 @@
+//! `orc session`: a line-oriented REPL over [`SessionManager`] that keeps
+//! several named SOCKS connections open at once, so protocols that need
+//! more than one request/response round trip don't have to reconnect
+//! every time.
+//!
+//! Commands, one per line on stdin:
+//!
+//! ```text
+//! open <id> <host:port>
+//! send <id> hex|text <data>
+//! recv <id> <max-bytes> [hex|text|hexdump|base64]
+//! close <id>
+//! quit
+//! ```
+//!
+//! There's also a kill switch: typing the word set by `--kill-switch`
+//! (`panic` by default) alone on a line wipes state and exits with
+//! status 137 instead of running it as a command. See
+//! [`crate::killswitch`].
+
+use std::io::{self, BufRead, Write};
+use std::net::SocketAddr;
+
+use clap::Args;
+
+use crate::commands::common::{KillSwitchArgs, TimeoutArgs};
+use crate::error::{OrcError, Result};
+use crate::killswitch;
+use crate::net::split_host_port;
+use crate::output::{self, OutputFormat};
+use crate::session::SessionManager;
+
+#[derive(Debug, Args)]
+pub struct SessionArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+
+    #[command(flatten)]
+    pub kill_switch: KillSwitchArgs,
+}
+
+pub fn run(args: SessionArgs) -> Result<()> {
+    let options = args.timeouts.to_connect_options();
+    let mut manager = SessionManager::new();
+    let stdin = io::stdin();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == args.kill_switch.kill_switch {
+            let wipe_paths = killswitch::load_wipe_paths()?;
+            let paths: Vec<&std::path::Path> = wipe_paths.iter().map(std::path::PathBuf::as_path).collect();
+            killswitch::trigger(&mut [], &paths);
+        }
+        if line == "quit" {
+            break;
+        }
+
+        match execute(&mut manager, line, args.proxy, &options) {
+            Ok(Some(output)) => println!("{output}"),
+            Ok(None) => {}
+            Err(err) => eprintln!("orc: {err}"),
+        }
+        io::stdout().flush()?;
+    }
+
+    Ok(())
+}
+
+fn execute(
+    manager: &mut SessionManager,
+    line: &str,
+    proxy: SocketAddr,
+    options: &crate::net::tcp::ConnectOptions,
+) -> Result<Option<String>> {
+    let mut parts = line.split_whitespace();
+    let verb = parts
+        .next()
+        .ok_or_else(|| OrcError::InvalidArgument("empty command".into()))?;
+
+    match verb {
+        "open" => {
+            let id = next_arg(&mut parts, "open <id> <host:port>")?;
+            let target = next_arg(&mut parts, "open <id> <host:port>")?;
+            let (host, port) = split_host_port(target)?;
+            manager.open(id, proxy, host, port, options)?;
+            Ok(None)
+        }
+        "send" => {
+            let id = next_arg(&mut parts, "send <id> hex|text <data>")?;
+            let encoding = next_arg(&mut parts, "send <id> hex|text <data>")?;
+            let rest: Vec<&str> = parts.collect();
+            let payload = rest.join(" ");
+            let data = match encoding {
+                "hex" => output::decode_hex(&payload)?,
+                "text" => payload.into_bytes(),
+                other => {
+                    return Err(OrcError::InvalidArgument(format!(
+                        "unknown encoding `{other}`, expected hex or text"
+                    )))
+                }
+            };
+            manager.send(id, &data)?;
+            Ok(None)
+        }
+        "recv" => {
+            let id = next_arg(&mut parts, "recv <id> <max-bytes> [format]")?;
+            let max_bytes: usize = next_arg(&mut parts, "recv <id> <max-bytes> [format]")?
+                .parse()
+                .map_err(|_| OrcError::InvalidArgument("max-bytes must be a number".into()))?;
+            let format = match parts.next() {
+                Some("hex") | None => OutputFormat::Hex,
+                Some("text") | Some("raw") => OutputFormat::Raw,
+                Some("hexdump") => OutputFormat::Hexdump,
+                Some("base64") => OutputFormat::Base64,
+                Some(other) => {
+                    return Err(OrcError::InvalidArgument(format!(
+                        "unknown output format `{other}`"
+                    )))
+                }
+            };
+            let data = manager.recv(id, max_bytes)?;
+            let rendered = output::render(&data, format);
+            Ok(Some(String::from_utf8_lossy(&rendered).into_owned()))
+        }
+        "close" => {
+            let id = next_arg(&mut parts, "close <id>")?;
+            manager.close(id)?;
+            Ok(None)
+        }
+        other => Err(OrcError::InvalidArgument(format!(
+            "unknown session command `{other}`"
+        ))),
+    }
+}
+
+fn next_arg<'a>(
+    parts: &mut std::str::SplitWhitespace<'a>,
+    usage: &str,
+) -> Result<&'a str> {
+    parts
+        .next()
+        .ok_or_else(|| OrcError::InvalidArgument(format!("usage: {usage}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_command() {
+        let mut manager = SessionManager::new();
+        let options = crate::net::tcp::ConnectOptions::default();
+        let proxy: SocketAddr = "127.0.0.1:9050".parse().unwrap();
+        assert!(execute(&mut manager, "frobnicate", proxy, &options).is_err());
+    }
+
+    #[test]
+    fn close_without_open_is_an_error() {
+        let mut manager = SessionManager::new();
+        let options = crate::net::tcp::ConnectOptions::default();
+        let proxy: SocketAddr = "127.0.0.1:9050".parse().unwrap();
+        assert!(execute(&mut manager, "close missing", proxy, &options).is_err());
+    }
+}
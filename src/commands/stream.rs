@@ -0,0 +1,135 @@
+//! `orc stream`: connect to an arbitrary host:port through Tor, optionally
+//! send some bytes, and print whatever comes back. Useful for poking at
+//! protocols `orc` doesn't have a dedicated client for yet.
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::commands::common::{PollArgs, TimeoutArgs};
+use crate::error::Result;
+use crate::net::script;
+use crate::net::split_host_port;
+use crate::net::tcp::create_socks_stream;
+use crate::output::{self, OutputFormat};
+use crate::secret::HexSource;
+
+#[derive(Debug, Args)]
+pub struct StreamArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// Target to connect to, as `host:port`. `host` may be an onion
+    /// address, a regular hostname, or an IP literal.
+    pub target: String,
+
+    /// Bytes to send, given as a hex string (e.g. `48656c6c6f`).
+    #[arg(long, conflicts_with = "send_text")]
+    pub send_hex: Option<String>,
+
+    /// Bytes to send, given as plain text.
+    #[arg(long, conflicts_with = "send_hex")]
+    pub send_text: Option<String>,
+
+    /// Read the hex payload from stdin instead of `--send-hex`, so it
+    /// never appears in `ps` output or shell history.
+    #[arg(long, conflicts_with_all = ["send_hex", "send_text", "hex_file", "secret_fd"])]
+    pub hex_stdin: bool,
+
+    /// Read the hex payload from a file instead of `--send-hex`.
+    #[arg(long, conflicts_with_all = ["send_hex", "send_text", "hex_stdin", "secret_fd"])]
+    pub hex_file: Option<PathBuf>,
+
+    /// Read the hex payload from this already-open file descriptor
+    /// instead of `--send-hex` (Unix only) — e.g. `--secret-fd 3`
+    /// alongside a caller's own `exec 3<secret.hex`.
+    #[arg(long, conflicts_with_all = ["send_hex", "send_text", "hex_stdin", "hex_file"])]
+    pub secret_fd: Option<u32>,
+
+    /// How to render the bytes read back from the peer.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Hex)]
+    pub output: OutputFormat,
+
+    /// Shut down the write half of the connection after sending, so the
+    /// peer sees EOF before we start reading its response.
+    #[arg(long)]
+    pub shutdown_write: bool,
+
+    /// Run a send/expect script (see `net::script`) instead of a single
+    /// send-then-receive exchange. Conflicts with `--send-hex`,
+    /// `--send-text` and `--shutdown-write`; `--hex-stdin`, `--hex-file`,
+    /// `--secret-fd` and `--output` are ignored for scripted exchanges.
+    #[arg(long, conflicts_with_all = ["send_hex", "send_text", "shutdown_write"])]
+    pub script: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+
+    #[command(flatten)]
+    pub poll: PollArgs,
+}
+
+pub fn run(args: StreamArgs) -> Result<()> {
+    args.poll.run_polled(|_| run_once(&args))
+}
+
+fn run_once(args: &StreamArgs) -> Result<()> {
+    let (host, port) = split_host_port(&args.target)?;
+    let options = args.timeouts.to_connect_options();
+    let mut stream = create_socks_stream(args.proxy, host, port, &options)?;
+
+    if let Some(script_path) = &args.script {
+        let contents = std::fs::read_to_string(script_path)?;
+        let steps = script::parse(&contents)?;
+        let timeout_handle = stream.try_clone()?;
+        let transcript = script::run(&mut stream, &steps, |timeout| {
+            timeout_handle.set_read_timeout(Some(timeout))?;
+            Ok(())
+        })?;
+        print!("{transcript}");
+        return Ok(());
+    }
+
+    let payload = resolve_payload(args)?;
+
+    if let Some(payload) = payload {
+        stream.write_all(&payload)?;
+    }
+
+    if args.shutdown_write {
+        stream.shutdown_write()?;
+    }
+
+    let mut received = Vec::new();
+    stream.read_to_end(&mut received)?;
+
+    let rendered = output::render(&received, args.output);
+    std::io::stdout().write_all(&rendered)?;
+    Ok(())
+}
+
+/// Picks whichever of `--send-hex`/`--send-text`/`--hex-stdin`/
+/// `--hex-file`/`--secret-fd` the user gave, clap's `conflicts_with_all`
+/// having already ruled out more than one at once.
+fn resolve_payload(args: &StreamArgs) -> Result<Option<Vec<u8>>> {
+    if let Some(hex) = &args.send_hex {
+        return Ok(Some(output::decode_hex(hex)?));
+    }
+    if let Some(text) = &args.send_text {
+        return Ok(Some(text.clone().into_bytes()));
+    }
+    if args.hex_stdin {
+        return Ok(Some(HexSource::Stdin.read()?.into_bytes()));
+    }
+    if let Some(path) = &args.hex_file {
+        return Ok(Some(HexSource::File(path.clone()).read()?.into_bytes()));
+    }
+    if let Some(fd) = args.secret_fd {
+        return Ok(Some(HexSource::Fd(fd).read()?.into_bytes()));
+    }
+    Ok(None)
+}
+
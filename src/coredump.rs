@@ -0,0 +1,40 @@
+//! Disables core dumps before any command runs, so a crash can't leave
+//! plaintext secrets — a decrypted config, a passphrase still live in a
+//! [`crate::secret::SensitiveString`] — sitting in a core file on disk.
+//!
+//! [`disable`] is called from [`crate::run_cli`], the same as
+//! [`crate::zeroize::install_panic_hook`], unless `--allow-core-dumps`
+//! was passed — read directly off `std::env::args` in `run_cli` rather
+//! than through a [`clap`] flag, since it has to take effect before
+//! anything else (including alias expansion) runs, not just before
+//! whichever subcommand ends up parsed.
+//!
+//! On Linux, `disable` also marks the process non-dumpable with
+//! `prctl(PR_SET_DUMPABLE, 0)`, which additionally blocks
+//! `/proc/<pid>/mem` reads and ptrace attachment from anything but
+//! root — `RLIMIT_CORE` alone only stops the kernel from writing a core
+//! file, not another process from reading this one's memory directly.
+//! Windows has no equivalent exposed without a dependency this crate
+//! doesn't carry, so `disable` is a no-op there; a crash there can still
+//! leave a Windows Error Reporting minidump behind.
+
+/// Sets `RLIMIT_CORE` to zero and, on Linux, marks the process
+/// non-dumpable. Best-effort: a failed `setrlimit`/`prctl` call is
+/// dropped rather than turned into a startup error, since refusing to
+/// run `orc` over an OS that won't let it harden itself would be worse
+/// than the gap it's closing.
+#[cfg(unix)]
+pub fn disable() {
+    let limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    unsafe {
+        libc::setrlimit(libc::RLIMIT_CORE, &limit);
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe {
+        libc::prctl(libc::PR_SET_DUMPABLE, 0);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn disable() {}
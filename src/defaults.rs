@@ -0,0 +1,87 @@
+//! Process-wide defaults for network behavior — connect timeout, HTTP
+//! response size cap, user agent, proxy-connect retries, and the
+//! [`crate::OrcClient::request`] redirect limit — overridable with
+//! `ORC_*` environment variables rather than threaded through every
+//! command's own flags. That's the same approach `ORC_SOCKS_SOCKET` and
+//! `ORC_SOCKS_ADDR` use in [`crate::net::tcp`] to reach code no
+//! individual command's argument struct has a clean path to; a command's
+//! own `--connect-timeout` and the like still take priority over these
+//! when it has one.
+
+use std::time::Duration;
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+const DEFAULT_USER_AGENT: &str = concat!("orc/", env!("CARGO_PKG_VERSION"));
+const DEFAULT_RETRIES: u32 = 1;
+const DEFAULT_MAX_REDIRECTS: u32 = 5;
+
+/// How long [`crate::net::tcp::ConnectOptions::default`] waits for the
+/// SOCKS proxy's own handshake, unless `ORC_CONNECT_TIMEOUT` names a
+/// different number of seconds.
+pub fn connect_timeout() -> Duration {
+    Duration::from_secs(env_u64("ORC_CONNECT_TIMEOUT").unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS))
+}
+
+/// The largest HTTP response body [`crate::net::http::send`] will read
+/// before giving up, unless `ORC_MAX_RESPONSE_BYTES` says otherwise.
+/// Onion HTTP endpoints are read to EOF with no `Content-Length` framing
+/// to trust ahead of time, so without a cap a misbehaving or hostile
+/// server could make `orc` buffer an unbounded response.
+pub fn max_response_bytes() -> usize {
+    env_u64("ORC_MAX_RESPONSE_BYTES").map(|bytes| bytes as usize).unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+}
+
+/// The `User-Agent` [`crate::net::http::send`] sends on every request
+/// that doesn't already set its own, unless `ORC_USER_AGENT` overrides
+/// it — e.g. to blend in with ordinary browser traffic on a capsule that
+/// checks for one.
+pub fn user_agent() -> String {
+    std::env::var("ORC_USER_AGENT").ok().filter(|value| !value.is_empty()).unwrap_or_else(|| DEFAULT_USER_AGENT.to_string())
+}
+
+/// How many times [`crate::net::tcp::create_socks_stream`] tries to
+/// reach the proxy itself before giving up, unless `ORC_RETRIES` says
+/// otherwise. 1 (the default) means no retry — the behavior before this
+/// existed. Only the connect to the proxy is retried, not the SOCKS
+/// handshake or whatever the caller does with the stream afterwards.
+pub fn retries() -> u32 {
+    env_u64("ORC_RETRIES").map(|count| count.max(1) as u32).unwrap_or(DEFAULT_RETRIES)
+}
+
+/// How many `3xx` responses [`crate::OrcClient::request`]'s builder will
+/// follow before giving up, unless `ORC_MAX_REDIRECTS` says otherwise.
+/// Mirrors [`retries`]'s reasoning: a hostile or looping onion endpoint
+/// shouldn't be able to make `orc` chase `Location` headers forever.
+pub fn max_redirects() -> u32 {
+    env_u64("ORC_MAX_REDIRECTS").map(|count| count as u32).unwrap_or(DEFAULT_MAX_REDIRECTS)
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_agent_defaults_to_the_crate_name_and_version() {
+        assert!(user_agent().starts_with("orc/"));
+    }
+
+    #[test]
+    fn retries_is_never_less_than_one() {
+        assert!(retries() >= 1);
+    }
+
+    #[test]
+    fn connect_timeout_defaults_to_thirty_seconds() {
+        assert_eq!(connect_timeout(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn max_redirects_defaults_to_five() {
+        assert_eq!(max_redirects(), 5);
+    }
+}
@@ -0,0 +1,184 @@
+//! An in-memory `tracing` [`Subscriber`] — diagnostic events land in a
+//! bounded ring buffer instead of a log file, so `--trace-export` can
+//! dump them to stderr on exit without this crate ever writing logs to
+//! disk by default, the same no-disk-state guarantee
+//! [`crate::audit_trail`] and [`crate::session`] already keep.
+//!
+//! This only covers genuinely diagnostic events — a connect attempt
+//! starting, a retry being scheduled, a host being refused by policy —
+//! not a command's actual output (`orc resolve` printing the resolved
+//! address, `orc rpc` printing a JSON-RPC result). Those still go to
+//! stdout via `println!`, same as before this module existed; routing
+//! them through `tracing` too would make `--trace-export` capture a
+//! command's real output twice.
+//!
+//! No `tracing-subscriber` dependency: a single fixed-capacity buffer
+//! behind one lock is all `--trace-export` needs, in keeping with this
+//! crate's habit of hand-rolling small parsers and formatters
+//! ([`crate::net::json`], [`crate::output`]) rather than pulling in a
+//! larger crate for them.
+
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// How many formatted lines [`MemorySubscriber`] keeps before dropping
+/// the oldest — generous enough for one command's worth of diagnostics,
+/// small enough that a long-running `orc chat`/`orc browse` session
+/// doesn't grow this without bound.
+const CAPACITY: usize = 1000;
+
+struct MemorySubscriber {
+    level: Level,
+    lines: Mutex<Vec<String>>,
+}
+
+impl MemorySubscriber {
+    fn push(&self, line: String) {
+        let mut lines = self.lines.lock().expect("diagnostics lock");
+        if lines.len() >= CAPACITY {
+            lines.remove(0);
+        }
+        lines.push(line);
+    }
+}
+
+/// Spans aren't meaningfully tracked — every event is recorded as a flat
+/// line rather than nested under whatever span it happened in — so every
+/// span this hands out is the same placeholder [`Id`]; [`MemorySubscriber`]
+/// doesn't implement per-span storage at all.
+const PLACEHOLDER_SPAN: u64 = 1;
+
+impl Subscriber for MemorySubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= &self.level
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(PLACEHOLDER_SPAN)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}", metadata.level(), metadata.target());
+        let mut visitor = FieldFormatter(&mut line);
+        event.record(&mut visitor);
+        self.push(crate::redact::redact(&line));
+    }
+
+    fn enter(&self, _span: &Id) {}
+    fn exit(&self, _span: &Id) {}
+}
+
+struct FieldFormatter<'a>(&'a mut String);
+
+impl Visit for FieldFormatter<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+}
+
+fn subscriber() -> &'static OnceLock<Arc<MemorySubscriber>> {
+    static SUBSCRIBER: OnceLock<Arc<MemorySubscriber>> = OnceLock::new();
+    &SUBSCRIBER
+}
+
+/// Installs the in-memory subscriber as the process-wide default —
+/// `--verbose` raises the level from [`Level::INFO`] to [`Level::DEBUG`],
+/// same as it already does for [`crate::redact`]. Idempotent: called
+/// once from [`crate::run_cli`], harmless if called again (e.g. by a
+/// future embedder) since [`tracing::subscriber::set_global_default`]
+/// simply reports that a default is already set.
+pub fn install(verbose: bool) {
+    let level = if verbose { Level::DEBUG } else { Level::INFO };
+    let subscriber = subscriber().get_or_init(|| Arc::new(MemorySubscriber { level, lines: Mutex::new(Vec::new()) }));
+    let _ = tracing::subscriber::set_global_default(subscriber.clone());
+}
+
+/// A snapshot of every line recorded so far, oldest first. Empty if
+/// [`install`] was never called (the default-unset `tracing` dispatcher
+/// drops every event).
+pub fn exported_lines() -> Vec<String> {
+    match subscriber().get() {
+        Some(subscriber) => subscriber.lines.lock().expect("diagnostics lock").clone(),
+        None => Vec::new(),
+    }
+}
+
+/// Writes every recorded line to stderr, one per line — `--trace-export`'s
+/// whole implementation. Stderr rather than a file, so this never adds a
+/// disk write `orc audit` would need to account for. Each line is
+/// coloured by its level (see [`crate::color`]) — red for `ERROR`,
+/// yellow for `WARN`, the rest left plain.
+pub fn export_to_stderr() {
+    for line in exported_lines() {
+        eprintln!("{}", colorize(&line));
+    }
+}
+
+fn colorize(line: &str) -> String {
+    if line.starts_with("ERROR") {
+        crate::color::paint("31", line)
+    } else if line.starts_with("WARN") {
+        crate::color::paint("33", line)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorize_leaves_non_error_non_warn_lines_alone() {
+        assert_eq!(colorize("INFO orc::cli detail=1"), "INFO orc::cli detail=1");
+        assert_eq!(colorize("DEBUG orc::net::tcp"), "DEBUG orc::net::tcp");
+    }
+
+    #[test]
+    fn push_keeps_the_buffer_within_capacity() {
+        let subscriber = MemorySubscriber { level: Level::DEBUG, lines: Mutex::new(Vec::new()) };
+        for i in 0..CAPACITY + 10 {
+            subscriber.push(format!("line {i}"));
+        }
+        let lines = subscriber.lines.lock().unwrap();
+        assert_eq!(lines.len(), CAPACITY);
+        assert_eq!(lines.first().unwrap(), "line 10");
+        assert_eq!(lines.last().unwrap(), &format!("line {}", CAPACITY + 9));
+    }
+
+    /// Uses [`tracing::dispatcher::with_default`] to scope the subscriber
+    /// to this test's thread rather than touching the real process-wide
+    /// default [`install`] sets — two tests doing that in the same run
+    /// would otherwise race on which one's `set_global_default` wins.
+    #[test]
+    fn event_at_or_above_the_configured_level_is_recorded() {
+        let subscriber = Arc::new(MemorySubscriber { level: Level::INFO, lines: Mutex::new(Vec::new()) });
+        let dispatch = tracing::Dispatch::from(subscriber.clone());
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!(target: "orc::diagnostics::tests", detail = "connecting");
+        });
+        let lines = subscriber.lines.lock().unwrap();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("INFO orc::diagnostics::tests"));
+        assert!(lines[0].contains("detail=\"connecting\""));
+    }
+
+    #[test]
+    fn event_below_the_configured_level_is_dropped() {
+        let subscriber = Arc::new(MemorySubscriber { level: Level::INFO, lines: Mutex::new(Vec::new()) });
+        let dispatch = tracing::Dispatch::from(subscriber.clone());
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::debug!(target: "orc::diagnostics::tests", "should not be recorded");
+        });
+        assert!(subscriber.lines.lock().unwrap().is_empty());
+    }
+}
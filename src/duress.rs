@@ -0,0 +1,144 @@
+//! A second "duress" passphrase for `orc`'s passphrase-protected
+//! encrypted stores ([`crate::config`], [`crate::session_store`]):
+//! entering it in place of the real passphrase silently wipes the store
+//! and hands back an empty result instead of failing, so someone
+//! compelled to unlock under duress isn't told apart, from the outside,
+//! from someone who simply forgot their passphrase — there's no "duress
+//! passphrase accepted" message, just silence and an empty config or tab
+//! list where a wrong passphrase would otherwise have errored.
+//!
+//! Stored as a salted hash next to an encrypted blob's envelope
+//! (`"duress_salt"`/`"duress_hash"`, both hex, alongside the envelope's
+//! own `"salt"`/`"ciphertext"`) rather than as a second ciphertext: a
+//! duress passphrase only ever needs to be compared against, never
+//! decrypted back into anything.
+//!
+//! `orc` has no bookmarks feature to extend this to — the request this
+//! implements named one, but nothing in this crate saves bookmarks
+//! anywhere, so [`Duress`] only covers the two encrypted stores that
+//! actually exist.
+
+#[cfg(not(feature = "serve"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{OrcError, Result};
+use crate::net::json::Value;
+use crate::output;
+use crate::secret::SensitiveString;
+
+const SALT_LEN: usize = 16;
+
+/// A configured duress passphrase's salted hash, read off or written
+/// into an encrypted blob's envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duress {
+    salt: Vec<u8>,
+    hash: [u8; 32],
+}
+
+impl Duress {
+    /// Hashes `passphrase` under a fresh salt, for writing into a blob's
+    /// envelope the first time a duress passphrase is configured.
+    pub fn set(passphrase: &SensitiveString) -> Self {
+        let salt = fresh_salt();
+        let hash = hash_passphrase(passphrase.as_str(), &salt);
+        Duress { salt: salt.to_vec(), hash }
+    }
+
+    /// True if `passphrase` hashes to the same value this was [`set`]
+    /// with.
+    pub fn matches(&self, passphrase: &SensitiveString) -> bool {
+        crate::constant_time::eq(&hash_passphrase(passphrase.as_str(), &self.salt), &self.hash)
+    }
+
+    /// The `"duress_salt"`/`"duress_hash"` fields to merge into an
+    /// encrypted blob's envelope.
+    pub fn fields(&self) -> Vec<(String, Value)> {
+        vec![
+            ("duress_salt".to_string(), Value::String(output::hex_string(&self.salt))),
+            ("duress_hash".to_string(), Value::String(output::hex_string(&self.hash))),
+        ]
+    }
+
+    /// Reads an encrypted blob's `"duress_salt"`/`"duress_hash"` fields,
+    /// if both are present. `None` means no duress passphrase is
+    /// configured for this blob — not an error, since most blobs never
+    /// set one.
+    pub fn from_envelope(envelope: &Value) -> Result<Option<Self>> {
+        let (Some(salt_hex), Some(hash_hex)) = (envelope.get("duress_salt").and_then(Value::as_str), envelope.get("duress_hash").and_then(Value::as_str)) else {
+            return Ok(None);
+        };
+        let salt = output::decode_hex(salt_hex)?;
+        let hash = output::decode_hex(hash_hex)?;
+        let hash: [u8; 32] = hash.try_into().map_err(|_| OrcError::InvalidArgument("duress hash has the wrong length".into()))?;
+        Ok(Some(Duress { salt, hash }))
+    }
+}
+
+fn hash_passphrase(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-duress-v1");
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    hasher.finalize().into()
+}
+
+/// A fresh salt from the OS's CSPRNG via [`getrandom`] — see
+/// [`crate::config`]'s identically-shaped `fresh_salt`.
+#[cfg(feature = "serve")]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).expect("the OS's CSPRNG should not fail");
+    salt
+}
+
+/// Falls back to a salt built from wall-clock time and the process id
+/// when built without `getrandom` (`--no-default-features` without
+/// `serve`) — see [`crate::config`]'s identically-shaped fallback
+/// `fresh_salt`.
+#[cfg(not(feature = "serve"))]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_be_bytes());
+    hasher.update(std::process::id().to_be_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&digest[..SALT_LEN]);
+    salt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_passphrase_it_was_set_with() {
+        let duress = Duress::set(&SensitiveString::new("let me in".to_string()));
+        assert!(duress.matches(&SensitiveString::new("let me in".to_string())));
+        assert!(!duress.matches(&SensitiveString::new("something else".to_string())));
+    }
+
+    #[test]
+    fn round_trips_through_an_envelope() {
+        let duress = Duress::set(&SensitiveString::new("let me in".to_string()));
+        let envelope = Value::Object(duress.fields());
+
+        let read_back = Duress::from_envelope(&envelope).unwrap().unwrap();
+        assert_eq!(read_back, duress);
+    }
+
+    #[test]
+    fn from_envelope_is_none_without_duress_fields() {
+        let envelope = Value::Object(vec![("orc_encrypted".to_string(), Value::Bool(true))]);
+        assert!(Duress::from_envelope(&envelope).unwrap().is_none());
+    }
+
+    #[test]
+    fn from_envelope_rejects_a_malformed_hash() {
+        let envelope = Value::Object(vec![("duress_salt".to_string(), Value::String("ab".to_string())), ("duress_hash".to_string(), Value::String("ab".to_string()))]);
+        assert!(Duress::from_envelope(&envelope).is_err());
+    }
+}
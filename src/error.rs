@@ -0,0 +1,220 @@
+use std::fmt;
+use std::io;
+
+use crate::net::json::Value;
+use crate::redact;
+
+/// Errors that can occur anywhere in `orc`.
+///
+/// This is intentionally small for now; as more subsystems land we expect
+/// this to grow additional variants rather than being replaced.
+#[derive(Debug)]
+pub enum OrcError {
+    /// An I/O failure talking to the local SOCKS proxy or the remote peer.
+    Io(io::Error),
+    /// The SOCKS5 proxy rejected or could not complete the handshake.
+    Socks(String),
+    /// A command-line argument or option was invalid.
+    InvalidArgument(String),
+    /// [`crate::security::check_host`] refused a connection under the
+    /// configured allow/deny policy — distinct from [`OrcError::InvalidArgument`]
+    /// since nothing the caller typed was wrong, the target is just not
+    /// permitted right now.
+    Denied(String),
+    /// The platform keyring (built with the `keyring-backend` feature)
+    /// refused or failed an operation — e.g. no Secret Service is
+    /// running, or the user declined a Keychain access prompt.
+    #[cfg(feature = "keyring-backend")]
+    Keyring(String),
+    /// A [`crate::cancellation::CancellationToken`] was cancelled while
+    /// this operation was in progress.
+    Cancelled,
+}
+
+impl ErrorKind {
+    /// The name [`OrcError::to_json`] reports this kind under — `snake_case`,
+    /// and stable across releases since a `--json` wrapper scripts against
+    /// this string rather than [`fmt::Debug`]'s derived spelling.
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Protocol => "protocol",
+            ErrorKind::InvalidArgument => "invalid_argument",
+            ErrorKind::Denied => "denied",
+            #[cfg(feature = "keyring-backend")]
+            ErrorKind::Keyring => "keyring",
+            ErrorKind::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for OrcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrcError::Io(err) => write!(f, "i/o error: {err}"),
+            OrcError::Socks(msg) => write!(f, "socks5 error: {msg}"),
+            OrcError::InvalidArgument(msg) => write!(f, "invalid argument: {msg}"),
+            OrcError::Denied(msg) => write!(f, "denied: {msg}"),
+            #[cfg(feature = "keyring-backend")]
+            OrcError::Keyring(msg) => write!(f, "keyring error: {msg}"),
+            OrcError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for OrcError {}
+
+impl From<io::Error> for OrcError {
+    fn from(err: io::Error) -> Self {
+        OrcError::Io(err)
+    }
+}
+
+/// The coarse category [`OrcError::kind`] groups every variant into, for
+/// a caller that wants to react by category — retry, report, exit —
+/// without matching each [`OrcError`] variant individually.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// [`OrcError::Io`] or [`OrcError::Socks`]: something went wrong
+    /// talking to the proxy or the remote peer, not with what the
+    /// caller asked for.
+    Protocol,
+    /// [`OrcError::InvalidArgument`]: the caller needs to change what it
+    /// asked for before trying again.
+    InvalidArgument,
+    /// [`OrcError::Denied`]: refused by policy, not by a mistake in the
+    /// request itself.
+    Denied,
+    /// [`OrcError::Keyring`] (`keyring-backend` feature only).
+    #[cfg(feature = "keyring-backend")]
+    Keyring,
+    /// [`OrcError::Cancelled`]: the caller asked for this, not a
+    /// failure of the operation itself.
+    Cancelled,
+}
+
+impl OrcError {
+    /// This error's [`ErrorKind`].
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            OrcError::Io(_) | OrcError::Socks(_) => ErrorKind::Protocol,
+            OrcError::InvalidArgument(_) => ErrorKind::InvalidArgument,
+            OrcError::Denied(_) => ErrorKind::Denied,
+            #[cfg(feature = "keyring-backend")]
+            OrcError::Keyring(_) => ErrorKind::Keyring,
+            OrcError::Cancelled => ErrorKind::Cancelled,
+        }
+    }
+
+    /// Whether trying the same operation again, unchanged, might
+    /// succeed — true only for [`ErrorKind::Protocol`], since a bad
+    /// argument or a policy denial will fail exactly the same way every
+    /// time. [`crate::net::tcp::create_socks_stream`]'s own retry of the
+    /// proxy connect (see [`crate::defaults::retries`]) is the one place
+    /// this crate already retries on its own; this is for a caller
+    /// deciding whether to do the same at a higher level.
+    pub fn retryable(&self) -> bool {
+        self.kind() == ErrorKind::Protocol
+    }
+
+    /// The process exit code [`crate::run_cli`] reports for this error,
+    /// following the `sysexits.h` convention other Unix CLIs use rather
+    /// than this crate inventing its own numbering: `EX_USAGE` (64) for
+    /// a bad argument — including a malformed onion address, since
+    /// [`crate::net::onion::validate_onion_host`] reports that as
+    /// [`OrcError::InvalidArgument`] — `EX_NOPERM` (77) for a policy
+    /// denial, `EX_UNAVAILABLE` (69) for a keyring that isn't there,
+    /// `EX_TEMPFAIL` (75) for a connect/read/write that timed out
+    /// (trying again, or against a different proxy, might succeed), 130
+    /// (128 + `SIGINT`, the same convention a shell uses for Ctrl-C) for
+    /// a cancellation, and plain `1` for every other protocol failure
+    /// (a refused connection, a malformed SOCKS5 reply, ...) — there's no
+    /// one `io::Error` shape that reliably tells "the local Tor proxy
+    /// isn't running" apart from every other kind of connection failure,
+    /// so those all share the generic code rather than this guessing.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            OrcError::Io(err) if err.kind() == io::ErrorKind::TimedOut => 75,
+            _ => match self.kind() {
+                ErrorKind::InvalidArgument => 64,
+                ErrorKind::Denied => 77,
+                #[cfg(feature = "keyring-backend")]
+                ErrorKind::Keyring => 69,
+                ErrorKind::Cancelled => 130,
+                ErrorKind::Protocol => 1,
+            },
+        }
+    }
+
+    /// The object [`crate::cli::dispatch`] prints to stderr for this error
+    /// when `--json` is active (see [`crate::audit_trail::json_mode`]) —
+    /// `kind`, a redacted `message`, [`Self::retryable`], and `target`
+    /// (also redacted, `null` if the command had none) — so a wrapper
+    /// script can tell a denial from a timed-out connect without matching
+    /// against this crate's free-form [`fmt::Display`] text.
+    pub fn to_json(&self, target: Option<&str>) -> Value {
+        Value::Object(vec![
+            ("kind".to_string(), Value::String(self.kind().as_str().to_string())),
+            ("message".to_string(), Value::String(redact::redact(&self.to_string()))),
+            ("retryable".to_string(), Value::Bool(self.retryable())),
+            ("target".to_string(), target.map(|t| Value::String(redact::redact(t))).unwrap_or(Value::Null)),
+        ])
+    }
+}
+
+pub type Result<T> = std::result::Result<T, OrcError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_errors_are_retryable() {
+        assert!(OrcError::Socks("timed out".into()).retryable());
+        assert!(OrcError::Io(io::Error::other("broken pipe")).retryable());
+    }
+
+    #[test]
+    fn invalid_argument_and_denied_are_not_retryable() {
+        assert!(!OrcError::InvalidArgument("bad".into()).retryable());
+        assert!(!OrcError::Denied("no".into()).retryable());
+    }
+
+    #[test]
+    fn exit_codes_follow_sysexits() {
+        assert_eq!(OrcError::InvalidArgument("bad".into()).exit_code(), 64);
+        assert_eq!(OrcError::Denied("no".into()).exit_code(), 77);
+        assert_eq!(OrcError::Socks("timed out".into()).exit_code(), 1);
+        assert_eq!(OrcError::Cancelled.exit_code(), 130);
+    }
+
+    #[test]
+    fn io_timeout_gets_its_own_exit_code() {
+        let timed_out = OrcError::Io(io::Error::new(io::ErrorKind::TimedOut, "deadline exceeded"));
+        assert_eq!(timed_out.exit_code(), 75);
+    }
+
+    #[test]
+    fn other_io_errors_use_the_generic_protocol_exit_code() {
+        let refused = OrcError::Io(io::Error::new(io::ErrorKind::ConnectionRefused, "refused"));
+        assert_eq!(refused.exit_code(), 1);
+    }
+
+    #[test]
+    fn cancelled_is_not_retryable() {
+        assert!(!OrcError::Cancelled.retryable());
+    }
+
+    #[test]
+    fn to_json_reports_kind_message_retryable_and_target() {
+        let json = OrcError::Denied("not on the allow list".into()).to_json(Some("example.onion"));
+        assert_eq!(json.get("kind"), Some(&Value::String("denied".to_string())));
+        assert_eq!(json.get("retryable"), Some(&Value::Bool(false)));
+        assert_eq!(json.get("target"), Some(&Value::String("example.onion".to_string())));
+    }
+
+    #[test]
+    fn to_json_reports_a_null_target_when_there_is_none() {
+        let json = OrcError::Cancelled.to_json(None);
+        assert_eq!(json.get("target"), Some(&Value::Null));
+    }
+}
@@ -0,0 +1,51 @@
+//! Lifecycle events a proxied connection can report as it happens — for
+//! a CLI progress bar or an embedding application to subscribe to,
+//! rather than only finding out how a connection went once it's over.
+//! Attach a sink via [`crate::net::tcp::ConnectOptions::events`] (or
+//! [`crate::OrcClient::with_events`], which sets that same field) and it
+//! is called from [`crate::net::tcp::create_socks_stream`] and
+//! [`crate::net::http::send`] as they work.
+//!
+//! There's no channel or async notification here — this crate has no
+//! async runtime anywhere (see [`crate::client`]'s doc comment) —
+//! [`EventSink::handle`] is called synchronously, on whichever thread is
+//! doing the connecting, the same way a `Drop` impl runs on whichever
+//! thread drops the value.
+//!
+//! Byte-level events are only reported for [`crate::net::http::send`]'s
+//! request/response cycle today, not for a raw [`crate::net::tcp::TorStream`]
+//! a command like `orc chat`/`orc browse` reads and writes on its own —
+//! instrumenting every protocol module's own read/write loop is a
+//! larger, separate change.
+
+use std::net::SocketAddr;
+
+/// One moment in a proxied connection's life, passed to
+/// [`EventSink::handle`].
+#[derive(Debug, Clone)]
+pub enum OrcEvent {
+    /// About to dial `proxy` to reach `target`.
+    ConnectStarted { proxy: SocketAddr, target: String },
+    /// The SOCKS5 handshake with `proxy` completed; `target` is now
+    /// reachable on the stream [`crate::net::tcp::create_socks_stream`]
+    /// is about to return.
+    SocksEstablished { proxy: SocketAddr, target: String },
+    /// The proxy connect to `proxy` failed and is about to be retried —
+    /// this is attempt `attempt` of [`crate::defaults::retries`].
+    RetryScheduled { proxy: SocketAddr, attempt: u32 },
+    /// `bytes` bytes were sent to or received from `target`.
+    BytesTransferred { target: String, bytes: usize, direction: Direction },
+}
+
+/// Which way [`OrcEvent::BytesTransferred`]'s bytes moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// Receives [`OrcEvent`]s as they happen. Implement this for a progress
+/// bar, a metrics counter, or a test harness's call recorder.
+pub trait EventSink: Send + Sync {
+    fn handle(&self, event: OrcEvent);
+}
@@ -0,0 +1,185 @@
+//! An opt-in delay inserted before each dispatched command — a shell
+//! loop, an `orc repl`/`orc session` line, or one of `orc browse`'s
+//! queued downloads — so a run of several orc invocations doesn't have
+//! the same tight, predictable cadence a network observer could use to
+//! fingerprint it. Off by default: every call that sleeps pays that
+//! latency for real, there's no way around it, which is exactly why this
+//! isn't on unless `"jitter_enabled"` says so.
+//!
+//! `"jitter_min_ms"`/`"jitter_max_ms"` are read the same ambient way
+//! [`crate::killswitch::load_wipe_options`] reads `"wipe_pattern"`: a
+//! missing or encrypted config file means "use the defaults", a present,
+//! unencrypted, malformed one is an error.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::error::{OrcError, Result};
+use crate::net::json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JitterOptions {
+    pub enabled: bool,
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Default for JitterOptions {
+    fn default() -> Self {
+        JitterOptions { enabled: false, min_ms: 200, max_ms: 2000 }
+    }
+}
+
+/// Loads `"jitter_enabled"`/`"jitter_min_ms"`/`"jitter_max_ms"` from the
+/// default config file.
+pub fn load_jitter_options() -> Result<JitterOptions> {
+    let text = match std::fs::read_to_string(config::default_config_file()) {
+        Ok(text) => text,
+        Err(_) => return Ok(JitterOptions::default()),
+    };
+    let parsed = crate::net::json::parse(&text)?;
+    if config::is_encrypted(&parsed) {
+        return Ok(JitterOptions::default());
+    }
+
+    let defaults = JitterOptions::default();
+    let enabled = match parsed.get("jitter_enabled") {
+        None => defaults.enabled,
+        Some(Value::Bool(enabled)) => *enabled,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"jitter_enabled\" must be a boolean".into())),
+    };
+    let min_ms = match parsed.get("jitter_min_ms") {
+        None => defaults.min_ms,
+        Some(Value::Number(n)) => *n as u64,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"jitter_min_ms\" must be a number".into())),
+    };
+    let max_ms = match parsed.get("jitter_max_ms") {
+        None => defaults.max_ms,
+        Some(Value::Number(n)) => *n as u64,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"jitter_max_ms\" must be a number".into())),
+    };
+    if min_ms > max_ms {
+        return Err(OrcError::InvalidArgument("config file's \"jitter_min_ms\" must not be greater than \"jitter_max_ms\"".into()));
+    }
+
+    Ok(JitterOptions { enabled, min_ms, max_ms })
+}
+
+/// Sleeps for a pseudo-random duration in `[options.min_ms,
+/// options.max_ms]` when `options.enabled`, otherwise returns
+/// immediately. Meant to be called once per dispatched command, not
+/// inside a per-byte or per-packet loop — see this module's doc comment
+/// for the latency this actually costs.
+pub fn delay(options: &JitterOptions) {
+    if !options.enabled {
+        return;
+    }
+    thread::sleep(Duration::from_millis(pick_delay_ms(options.min_ms, options.max_ms)));
+}
+
+fn pick_delay_ms(min_ms: u64, max_ms: u64) -> u64 {
+    let span = max_ms.saturating_sub(min_ms);
+    if span == 0 {
+        return min_ms;
+    }
+    min_ms + pseudo_random_u64() % (span + 1)
+}
+
+/// A process-wide counter so two calls landing in the same clock tick
+/// still get different seeds — the same disambiguator role
+/// [`crate::killswitch::pseudo_random_bytes`]'s `disambiguator` argument
+/// plays, just tracked automatically instead of passed in by hand since
+/// every caller here wants an independent value.
+static CALL_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn pseudo_random_u64() -> u64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let counter = CALL_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_be_bytes());
+    hasher.update(std::process::id().to_be_bytes());
+    hasher.update(counter.to_be_bytes());
+    let digest = hasher.finalize();
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_does_nothing_when_disabled() {
+        let started = std::time::Instant::now();
+        delay(&JitterOptions { enabled: false, min_ms: 5_000, max_ms: 5_000 });
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn pick_delay_ms_stays_within_bounds() {
+        for _ in 0..50 {
+            let ms = pick_delay_ms(10, 20);
+            assert!((10..=20).contains(&ms));
+        }
+    }
+
+    #[test]
+    fn pick_delay_ms_handles_an_equal_min_and_max() {
+        assert_eq!(pick_delay_ms(7, 7), 7);
+    }
+
+    #[test]
+    fn load_jitter_options_is_the_default_when_the_config_file_is_absent() {
+        let home = std::env::temp_dir().join(format!("orc-jitter-test-no-home-{}", std::process::id()));
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let options = load_jitter_options().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        assert_eq!(options, JitterOptions::default());
+    }
+
+    #[test]
+    fn load_jitter_options_reads_the_settings_from_a_given_home() {
+        let home = std::env::temp_dir().join(format!("orc-jitter-test-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"jitter_enabled": true, "jitter_min_ms": 50, "jitter_max_ms": 150}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let options = load_jitter_options().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(options, JitterOptions { enabled: true, min_ms: 50, max_ms: 150 });
+    }
+
+    #[test]
+    fn load_jitter_options_rejects_a_min_greater_than_max() {
+        let home = std::env::temp_dir().join(format!("orc-jitter-test-bad-range-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"jitter_min_ms": 200, "jitter_max_ms": 100}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let result = load_jitter_options();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.is_err());
+    }
+}
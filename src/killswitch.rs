@@ -0,0 +1,498 @@
+//! A typed-phrase kill switch for the line-oriented REPLs, and `orc
+//! panic`, the same thing as an explicit command for anything that isn't
+//! one of those.
+//!
+//! There's no raw-terminal dependency in this crate to bind an actual
+//! hotkey to, so the nearest equivalent in a stdin-driven REPL is a
+//! configurable word that must appear alone on its own line. Triggering
+//! it wipes any secrets it's handed, sweeps `crate::zeroize`'s global
+//! registry for anything else still alive, best-effort [`secure_wipe_path_with`]s
+//! a list of configured paths (e.g. a TLS pin store), and exits
+//! immediately with status 137 — the code a `kill -9` leaves behind —
+//! instead of returning control to the caller.
+//!
+//! [`secure_wipe_file`] is the same overwrite-then-remove idea applied to
+//! a file on disk rather than an in-memory secret, for callers that want
+//! to delete something more thoroughly than a plain `remove_file` without
+//! pulling the whole process down — `orc browse`'s `session delete` uses
+//! it on a saved session blob. [`secure_wipe_path_with`] extends it to a
+//! whole directory tree, for [`load_wipe_paths`]'s `"wipe_paths"` entries
+//! that might name either.
+//!
+//! [`WipePattern`] picks how many passes a wipe makes and with what
+//! bytes, and [`WipeOptions::verify`] asks for a read-back after the
+//! last one; [`load_wipe_options`] reads both from the same config file
+//! [`load_wipe_paths`] does (`"wipe_pattern"`/`"wipe_verify"`), so
+//! [`trigger`] and every duress-triggered wipe in this crate
+//! ([`crate::config::load`], [`crate::session_store::load`], via
+//! [`wipe_configured`]) apply whatever's configured without each caller
+//! threading options through by hand.
+//!
+//! None of this changes what every doc comment in this module already
+//! says: a pass of any pattern, verified or not, doesn't defeat a
+//! copy-on-write filesystem (the old blocks are still referenced from a
+//! snapshot or reflink), SSD wear levelling (the drive remaps writes to
+//! different physical cells rather than overwriting in place), or a
+//! backup taken before the wipe ran. More passes and a read-back make
+//! this crate's own confidence that the file's current bytes changed
+//! higher — they say nothing about what the underlying storage kept.
+//!
+//! On Windows, the final removal also scrambles the file's name first —
+//! see [`remove_after_overwrite`] for NTFS's particular reason that's
+//! worth doing there and not on the other platforms this crate builds
+//! for.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+#[cfg(not(feature = "serve"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(not(feature = "serve"))]
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::error::{OrcError, Result};
+use crate::net::json::Value;
+#[cfg(windows)]
+use crate::output;
+use crate::secret::SensitiveString;
+
+/// Wipes `secrets`, removes `paths` best-effort (using whatever
+/// [`load_wipe_options`] finds configured), and exits the process with
+/// status 137. Never returns.
+pub fn trigger(secrets: &mut [SensitiveString], paths: &[&Path]) -> ! {
+    for secret in secrets {
+        secret.wipe();
+    }
+    crate::zeroize::zeroize_all();
+    let options = load_wipe_options().unwrap_or_default();
+    for path in paths {
+        let _ = secure_wipe_path_with(path, options);
+    }
+    std::process::exit(137);
+}
+
+/// How many passes a wipe makes over a file and with what bytes. `Zeros`
+/// is the default — what every wipe in this crate did before the other
+/// patterns existed — so a config file that never sets `"wipe_pattern"`
+/// sees no change in behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WipePattern {
+    /// A single pass of zeros.
+    #[default]
+    Zeros,
+    /// A single pass of pseudo-random bytes.
+    Random,
+    /// Zeros, then pseudo-random bytes.
+    ZerosThenRandom,
+    /// The classic DoD 5220.22-M three-pass scheme: zeros, then the
+    /// bitwise complement of zeros (`0xFF`), then pseudo-random bytes.
+    Dod3Pass,
+}
+
+impl WipePattern {
+    fn from_config_str(name: &str) -> Option<WipePattern> {
+        match name {
+            "zeros" => Some(WipePattern::Zeros),
+            "random" => Some(WipePattern::Random),
+            "zeros-random" => Some(WipePattern::ZerosThenRandom),
+            "dod3" => Some(WipePattern::Dod3Pass),
+            _ => None,
+        }
+    }
+
+    /// The byte buffer for each pass, in order, `len` bytes long.
+    fn passes(self, len: usize) -> Vec<Vec<u8>> {
+        match self {
+            WipePattern::Zeros => vec![vec![0u8; len]],
+            WipePattern::Random => vec![pseudo_random_bytes(len, 0)],
+            WipePattern::ZerosThenRandom => vec![vec![0u8; len], pseudo_random_bytes(len, 1)],
+            WipePattern::Dod3Pass => vec![vec![0u8; len], vec![0xFFu8; len], pseudo_random_bytes(len, 2)],
+        }
+    }
+}
+
+/// [`WipePattern`] plus whether to read a file back after its last pass
+/// to confirm the bytes on disk actually changed — see the module doc
+/// comment for what that can and can't prove.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WipeOptions {
+    pub pattern: WipePattern,
+    pub verify: bool,
+}
+
+/// Overwrites `path`'s contents (see [`WipePattern`]) before unlinking
+/// it, the same best-effort spirit as [`SensitiveString::wipe`].
+/// Equivalent to [`secure_wipe_file_with`] with the default options
+/// (a single zero pass, no verification).
+pub fn secure_wipe_file(path: &Path) -> std::io::Result<()> {
+    secure_wipe_file_with(path, WipeOptions::default())
+}
+
+/// [`secure_wipe_file`], with the pattern and verification `options`
+/// asks for. If `options.verify` is set, the file is read back after its
+/// last pass and an error is returned (without removing the file) if
+/// what's on disk doesn't match what was just written — a best-effort
+/// check of this crate's own write path, not of the underlying storage.
+pub fn secure_wipe_file_with(path: &Path, options: WipeOptions) -> std::io::Result<()> {
+    if let Ok(metadata) = std::fs::metadata(path) {
+        let len = metadata.len() as usize;
+        let passes = options.pattern.passes(len);
+        if let Ok(mut file) = std::fs::OpenOptions::new().write(true).open(path) {
+            for (index, pass) in passes.iter().enumerate() {
+                let _ = file.seek(SeekFrom::Start(0));
+                let _ = file.write_all(pass);
+                let _ = file.sync_all();
+                if options.verify && index + 1 == passes.len() {
+                    let readback = std::fs::read(path)?;
+                    if readback != *pass {
+                        return Err(std::io::Error::other(format!(
+                            "wipe verification failed: {} doesn't match its last pass after writing",
+                            path.display()
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    remove_after_overwrite(path)
+}
+
+/// [`std::fs::remove_file`], except on Windows, where the file is first
+/// renamed to a pseudo-random name in the same directory.
+///
+/// NTFS keeps the old file name (and, for a small enough file, its data)
+/// resident directly in its MFT record rather than only in the directory
+/// entry this crate's overwrite passes above already touch — the
+/// filesystem-specific gap this function closes what it can of.
+/// Scrambling the name first means whatever of that MFT record survives
+/// deletion doesn't also hand back the original name for free. This is
+/// still no substitute for the caveats the rest of this module's doc
+/// comment already lists: it doesn't reach a Shadow Copy, a prior
+/// snapshot, or (same as every other pass in this module) an SSD's
+/// remapped-instead-of-overwritten physical cells.
+///
+/// Enumerating and overwriting any alternate data streams the file might
+/// carry (`file.txt:hidden`, say) would need `FindFirstStreamW`, which
+/// isn't exposed by anything in this crate's dependency set — the same
+/// kind of platform-API gap [`crate::coredump`] and [`crate::signals`]
+/// already document for other Windows-only APIs this crate doesn't link
+/// against. A stream created by something other than `orc` itself is
+/// left untouched.
+#[cfg(windows)]
+fn remove_after_overwrite(path: &Path) -> std::io::Result<()> {
+    let scrambled_name = output::hex_string(&pseudo_random_bytes(16, 99));
+    let scrambled_path = path.with_file_name(scrambled_name);
+    std::fs::rename(path, &scrambled_path)?;
+    std::fs::remove_file(&scrambled_path)
+}
+
+#[cfg(not(windows))]
+fn remove_after_overwrite(path: &Path) -> std::io::Result<()> {
+    std::fs::remove_file(path)
+}
+
+/// [`secure_wipe_file_with`], extended to a whole directory: every entry
+/// is wiped first (recursing into subdirectories) with the pattern and
+/// verification `options` asks for, then the now-empty directory itself
+/// is removed. A plain file is handled the same as calling
+/// [`secure_wipe_file_with`] directly. Best-effort at every level — one
+/// entry failing partway through a directory doesn't stop the rest from
+/// being attempted, consistent with [`trigger`]'s own best-effort
+/// handling of the paths it's given.
+pub fn secure_wipe_path_with(path: &Path, options: WipeOptions) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            let _ = secure_wipe_path_with(&entry?.path(), options);
+        }
+        std::fs::remove_dir(path)
+    } else {
+        secure_wipe_file_with(path, options)
+    }
+}
+
+/// [`secure_wipe_file_with`] using whatever [`load_wipe_options`] finds
+/// configured, falling back to the default pattern if the config can't
+/// be read at all. The one helper every duress-triggered wipe outside
+/// [`trigger`] itself goes through ([`crate::config::load`],
+/// [`crate::session_store::load`]), so `"wipe_pattern"`/`"wipe_verify"`
+/// apply to a silent duress wipe the same as to an explicit one.
+pub fn wipe_configured(path: &Path) -> std::io::Result<()> {
+    secure_wipe_file_with(path, load_wipe_options().unwrap_or_default())
+}
+
+/// Filler bytes for [`WipePattern::Random`] and its siblings, read
+/// straight from the OS's CSPRNG via [`getrandom`] — see
+/// [`crate::config`]'s identically-reasoned `fresh_salt`. `disambiguator`
+/// goes unused here: true randomness doesn't need help not to repeat
+/// across a multi-pass wipe's passes the way the fallback below does.
+#[cfg(feature = "serve")]
+fn pseudo_random_bytes(len: usize, _disambiguator: u32) -> Vec<u8> {
+    let mut out = vec![0u8; len];
+    getrandom::getrandom(&mut out).expect("the OS's CSPRNG should not fail");
+    out
+}
+
+/// Falls back to wall-clock time and the process id, hashed together
+/// and stretched with a SHA-256 counter stream, when built without
+/// `getrandom` (`--no-default-features` without `serve`).
+/// `disambiguator` keeps a multi-pass wipe's passes from repeating the
+/// same bytes. Good enough that a wipe doesn't write the same bytes
+/// twice in a row; not a substitute for real randomness, and not what
+/// makes any of these patterns defeat the filesystem caveats in the
+/// module doc comment.
+#[cfg(not(feature = "serve"))]
+fn pseudo_random_bytes(len: usize, disambiguator: u32) -> Vec<u8> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut seed_hasher = Sha256::new();
+    seed_hasher.update(nanos.to_be_bytes());
+    seed_hasher.update(std::process::id().to_be_bytes());
+    seed_hasher.update(disambiguator.to_be_bytes());
+    let seed: [u8; 32] = seed_hasher.finalize().into();
+
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// Loads the `"wipe_paths"` section of the default config file (see
+/// [`config::default_config_file`]): `{"wipe_paths": ["/path/to/pins",
+/// "/path/to/sessions"]}`, a list of files or directories [`trigger`]
+/// should [`secure_wipe_path_with`] on top of whatever a specific caller (like
+/// `orc browse`'s TLS pin store) already passes it by hand. Read the same
+/// ambient way [`crate::security::load_policy`] reads `"security"`: a
+/// missing or encrypted config file means "nothing extra to wipe", not
+/// an error, since most commands that can trigger the kill switch never
+/// load a config file otherwise.
+pub fn load_wipe_paths() -> Result<Vec<PathBuf>> {
+    let text = match std::fs::read_to_string(config::default_config_file()) {
+        Ok(text) => text,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let parsed = crate::net::json::parse(&text)?;
+    if config::is_encrypted(&parsed) {
+        return Ok(Vec::new());
+    }
+    match parsed.get("wipe_paths") {
+        None => Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .as_str()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| OrcError::InvalidArgument("config file's \"wipe_paths\" entries must be strings".into()))
+            })
+            .collect(),
+        Some(_) => Err(OrcError::InvalidArgument("config file's \"wipe_paths\" must be an array".into())),
+    }
+}
+
+/// Loads the `"wipe_pattern"`/`"wipe_verify"` sections of the default
+/// config file as a [`WipeOptions`], the same ambient way
+/// [`load_wipe_paths`] reads `"wipe_paths"`: a missing or encrypted
+/// config file means "use the defaults", not an error; a present,
+/// unencrypted, malformed one is. `"wipe_pattern"` must be one of
+/// `"zeros"`, `"random"`, `"zeros-random"`, or `"dod3"`.
+pub fn load_wipe_options() -> Result<WipeOptions> {
+    let text = match std::fs::read_to_string(config::default_config_file()) {
+        Ok(text) => text,
+        Err(_) => return Ok(WipeOptions::default()),
+    };
+    let parsed = crate::net::json::parse(&text)?;
+    if config::is_encrypted(&parsed) {
+        return Ok(WipeOptions::default());
+    }
+
+    let pattern = match parsed.get("wipe_pattern") {
+        None => WipePattern::default(),
+        Some(Value::String(name)) => WipePattern::from_config_str(name)
+            .ok_or_else(|| OrcError::InvalidArgument(format!("unknown \"wipe_pattern\" `{name}` (expected zeros, random, zeros-random, or dod3)")))?,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"wipe_pattern\" must be a string".into())),
+    };
+
+    let verify = match parsed.get("wipe_verify") {
+        None => false,
+        Some(Value::Bool(verify)) => *verify,
+        Some(_) => return Err(OrcError::InvalidArgument("config file's \"wipe_verify\" must be a boolean".into())),
+    };
+
+    Ok(WipeOptions { pattern, verify })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secure_wipe_file_overwrites_and_removes_the_file() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("orc-killswitch-test-file-{}", std::process::id()));
+        std::fs::write(&path, b"hunter2").unwrap();
+
+        secure_wipe_file(&path).unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn secure_wipe_path_recursively_wipes_a_directory() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("orc-killswitch-test-dir-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("top.txt"), b"secret").unwrap();
+        std::fs::write(dir.join("nested/leaf.txt"), b"secret").unwrap();
+
+        secure_wipe_path_with(&dir, WipeOptions::default()).unwrap();
+
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn load_wipe_paths_is_empty_when_the_default_config_file_is_absent() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-no-home-{}", std::process::id()));
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let paths = load_wipe_paths().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn load_wipe_paths_reads_the_list_from_a_given_home() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"wipe_paths": ["/tmp/pins", "/tmp/sessions"]}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let paths = load_wipe_paths().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(paths, vec![PathBuf::from("/tmp/pins"), PathBuf::from("/tmp/sessions")]);
+    }
+
+    #[test]
+    fn load_wipe_paths_rejects_a_non_array_value() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-bad-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"wipe_paths": "/tmp/pins"}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let result = load_wipe_paths();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn secure_wipe_file_with_dod3_pass_overwrites_and_removes_the_file() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("orc-killswitch-test-dod3-{}", std::process::id()));
+        std::fs::write(&path, b"hunter2").unwrap();
+
+        secure_wipe_file_with(&path, WipeOptions { pattern: WipePattern::Dod3Pass, verify: false }).unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn secure_wipe_file_with_verify_succeeds_on_an_ordinary_file() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("orc-killswitch-test-verify-{}", std::process::id()));
+        std::fs::write(&path, b"hunter2").unwrap();
+
+        secure_wipe_file_with(&path, WipeOptions { pattern: WipePattern::ZerosThenRandom, verify: true }).unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn zeros_then_random_and_dod3_produce_their_documented_passes() {
+        let zeros_then_random = WipePattern::ZerosThenRandom.passes(16);
+        assert_eq!(zeros_then_random[0], vec![0u8; 16]);
+        assert_ne!(zeros_then_random[1], vec![0u8; 16]);
+
+        let dod3 = WipePattern::Dod3Pass.passes(16);
+        assert_eq!(dod3.len(), 3);
+        assert_eq!(dod3[0], vec![0u8; 16]);
+        assert_eq!(dod3[1], vec![0xFFu8; 16]);
+    }
+
+    #[test]
+    fn load_wipe_options_defaults_to_zeros_and_no_verification() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-no-options-home-{}", std::process::id()));
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let options = load_wipe_options().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        assert_eq!(options.pattern, WipePattern::Zeros);
+        assert!(!options.verify);
+    }
+
+    #[test]
+    fn load_wipe_options_reads_the_pattern_and_verify_flag() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-options-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"wipe_pattern": "dod3", "wipe_verify": true}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let options = load_wipe_options().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(options.pattern, WipePattern::Dod3Pass);
+        assert!(options.verify);
+    }
+
+    #[test]
+    fn load_wipe_options_rejects_an_unknown_pattern_name() {
+        let home = std::env::temp_dir().join(format!("orc-killswitch-test-bad-pattern-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"wipe_pattern": "shred"}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let result = load_wipe_options();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.is_err());
+    }
+}
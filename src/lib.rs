@@ -0,0 +1,272 @@
+//! `orc`'s library crate: the Tor-only `TorClient` plumbing, protocol
+//! modules, and config/security layers behind the `orc` CLI, split out
+//! so another Rust program can depend on `orc` directly — and get the
+//! same "every connection goes through a local SOCKS5 proxy, nothing
+//! resolves DNS locally" guarantee the CLI already has — instead of
+//! shelling out to the binary and scraping its output.
+//!
+//! [`OrcClient`] and [`testing::MockTransport`] are the two pieces of API
+//! actually meant for an embedder — the real transport and a stand-in
+//! for its own tests; everything else is `pub` because `src/main.rs`
+//! (now a thin wrapper around [`run_cli`]) needs it, not because it's
+//! meant to be a stable surface on its own. A protocol-specific need —
+//! speaking Gemini, calling a JSON-RPC method, reading an NNTP newsgroup
+//! — is still reached through the matching [`net`] submodule directly,
+//! the same way [`commands`] already does internally.
+
+pub mod audit_trail;
+pub mod blocking;
+pub mod cancellation;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod cli;
+pub mod color;
+pub mod commands;
+pub mod config;
+pub mod constant_time;
+pub mod coredump;
+pub mod defaults;
+pub mod diagnostics;
+pub mod download_crypto;
+pub mod download_verify;
+pub mod duress;
+pub mod error;
+pub mod events;
+pub mod jitter;
+pub mod killswitch;
+pub mod net;
+pub mod output;
+pub mod persistence;
+pub mod privilege;
+pub mod procscrub;
+pub mod redact;
+pub mod sandbox;
+pub mod secret;
+pub mod security;
+pub mod session;
+pub mod session_store;
+pub mod signals;
+pub mod testing;
+pub mod zeroize;
+
+mod client;
+pub use client::OrcClient;
+
+#[cfg(test)]
+mod test_support;
+
+use clap::Parser;
+
+use cli::Cli;
+
+/// Everything `main` used to do directly, before the CLI's own logic
+/// moved into this library crate alongside the code it drives. Reads
+/// `std::env::args`, exits the process directly on any startup or
+/// dispatch error (a dispatch error with [`crate::error::OrcError::exit_code`]'s
+/// status), same as before this split existed — an
+/// embedder linking this crate for [`OrcClient`] has no reason to call
+/// this at all.
+pub fn run_cli() {
+    // rustls needs a crypto provider installed process-wide before any
+    // TLS connection (e.g. `orc gemini`) can be made.
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    zeroize::install_panic_hook();
+    signals::install();
+
+    // `--allow-core-dumps` is handled here, before anything else,
+    // rather than as a clap flag on some subcommand's `Args` — it has
+    // to take effect (or not) before alias expansion and parsing even
+    // run. Stripped out of `argv` below so it never reaches clap, which
+    // otherwise has no subcommand that knows what to do with it.
+    let mut argv: Vec<String> = std::env::args().collect();
+    let allow_core_dumps = take_flag(&mut argv, "--allow-core-dumps");
+    if !allow_core_dumps {
+        coredump::disable();
+    }
+
+    // `--allow-root` is checked just as early, for the same reason: it
+    // has to refuse before any subcommand gets a chance to do anything
+    // at all, not just before whichever one clap eventually parses.
+    let allow_root = take_flag(&mut argv, "--allow-root");
+    if let Err(err) = privilege::guard(allow_root) {
+        eprintln!("orc: {}", redact::redact(&err.to_string()));
+        std::process::exit(1);
+    }
+
+    // `--sandbox` is opt-in rather than on-by-default like core dump
+    // disabling above: it's new enough that a command nobody has run
+    // under it yet could need a syscall the allowlist doesn't have, so
+    // an explicit ask gets a loud failure instead of every invocation
+    // risking a silent one.
+    if take_flag(&mut argv, "--sandbox") {
+        if let Err(err) = sandbox::enable() {
+            eprintln!("orc: {}", redact::redact(&err.to_string()));
+            std::process::exit(1);
+        }
+    }
+
+    // `--verbose` and `--no-redact` are pre-clap flags like the two
+    // above, not a subcommand's `Args`, since they need to govern how
+    // *every* error from this point on is printed, including one from
+    // alias expansion or `Cli::parse_from` itself. Redaction of onion
+    // addresses in error output stays on unless both are given together
+    // — `--verbose` alone asking for more detail shouldn't also hand out
+    // addresses nobody asked to expose.
+    let verbose = take_flag(&mut argv, "--verbose");
+    let no_redact = take_flag(&mut argv, "--no-redact");
+    redact::set_enabled(!(verbose && no_redact));
+
+    // `--color always|never|auto` is a pre-clap flag for the same
+    // reason `--verbose` is, and takes a value rather than being a bare
+    // boolean like the flags above, so it goes through `take_value_flag`
+    // instead of `take_flag`. Left at `color::ColorMode::Auto` (the
+    // static default) if absent, so TTY/`NO_COLOR` detection is what
+    // most invocations get.
+    if let Some(value) = take_value_flag(&mut argv, "--color") {
+        match color::ColorMode::parse(&value) {
+            Some(mode) => color::set_mode(mode),
+            None => {
+                eprintln!("orc: --color must be one of always, never, auto");
+                std::process::exit(64);
+            }
+        }
+    }
+
+    // `--trace-export` is handled here too, rather than as a clap flag,
+    // so it can be stripped before `Cli::parse_from` the same way the
+    // flags above are, and so `diagnostics::install` runs before
+    // anything else has a chance to emit an event that would otherwise
+    // be dropped by the unset default `tracing` dispatcher. `verbose`
+    // reuses the flag already read above: `--verbose` raises both the
+    // redaction threshold and the diagnostic level together, since both
+    // are "I want more detail" in the same sense.
+    let trace_export = take_flag(&mut argv, "--trace-export");
+    diagnostics::install(verbose);
+
+    // `--json` is a pre-clap flag for the same reason `--verbose` is:
+    // it has to govern how `cli::dispatch` reports *every* command's
+    // outcome, not just one command's own `Args`. See
+    // `audit_trail`'s doc comment for what this does and doesn't cover.
+    audit_trail::set_json_mode(take_flag(&mut argv, "--json"));
+
+    // `--allow-remote-socks` is another pre-clap flag for the same
+    // reason: it has to govern every command's own `--proxy` flag from
+    // one place rather than being bolted onto each of their `Args`
+    // structs. See `security::check_proxy_addr`.
+    security::set_allow_remote_socks(take_flag(&mut argv, "--allow-remote-socks"));
+
+    let argv = match config::load_aliases().and_then(|aliases| cli::expand_aliases(argv, &aliases)) {
+        Ok(argv) => argv,
+        Err(err) => {
+            eprintln!("orc: {}", redact::redact(&err.to_string()));
+            std::process::exit(1);
+        }
+    };
+
+    let cli = Cli::parse_from(argv);
+
+    // `Cli::parse_from` above has already copied every argument into
+    // owned `String`s of its own; nothing still needs the kernel's
+    // original argv bytes, so zero them now rather than let a secret
+    // that made it onto the command line (e.g. a password embedded in a
+    // URL) sit in `/proc/<pid>/cmdline` for the rest of this run.
+    procscrub::scrub_argv();
+
+    let result = cli::dispatch(cli.command);
+    if trace_export {
+        diagnostics::export_to_stderr();
+    }
+    if let Err(err) = result {
+        eprintln!("orc: {}", redact::redact(&err.to_string()));
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Removes the first occurrence of `flag` from `argv` (if present, and if
+/// it appears before the subcommand name) and reports whether it was
+/// there.
+///
+/// Only searches the prefix before the first argument that doesn't start
+/// with `--` — the subcommand name, by convention always where these
+/// global pre-clap flags stop applying — so e.g. `orc --json resolve
+/// ...` strips the global flag this module defined, but `orc feed --url
+/// ... --json` leaves `FeedArgs`'s own same-named flag alone for clap to
+/// parse. Every flag this function is used for is a boolean with no
+/// value of its own, so nothing before the subcommand name is mistaken
+/// for one.
+fn take_flag(argv: &mut Vec<String>, flag: &str) -> bool {
+    let boundary = argv.iter().skip(1).position(|arg| !arg.starts_with("--")).map(|i| i + 1).unwrap_or(argv.len());
+    match argv[..boundary].iter().position(|arg| arg == flag) {
+        Some(index) => {
+            argv.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Like [`take_flag`], but for a flag that takes a value rather than
+/// being a bare boolean — accepts either `--flag value` or `--flag=value`
+/// form, scoped to the same before-the-subcommand prefix, and returns
+/// the value (removing both the flag and, for the `--flag value` form,
+/// its separate value argument from `argv`).
+fn take_value_flag(argv: &mut Vec<String>, flag: &str) -> Option<String> {
+    let boundary = argv.iter().skip(1).position(|arg| !arg.starts_with("--")).map(|i| i + 1).unwrap_or(argv.len());
+    let prefix = format!("{flag}=");
+    let index = argv[..boundary].iter().position(|arg| arg == flag || arg.starts_with(&prefix))?;
+    let arg = argv.remove(index);
+    if let Some(value) = arg.strip_prefix(&prefix) {
+        return Some(value.to_string());
+    }
+    if index < argv.len() {
+        Some(argv.remove(index))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_a_global_flag_before_the_subcommand() {
+        let mut argv: Vec<String> = ["orc", "--json", "resolve", "example.onion"].iter().map(|s| s.to_string()).collect();
+        assert!(take_flag(&mut argv, "--json"));
+        assert_eq!(argv, vec!["orc", "resolve", "example.onion"]);
+    }
+
+    #[test]
+    fn leaves_a_same_named_flag_after_the_subcommand_alone() {
+        let mut argv: Vec<String> = ["orc", "feed", "--url", "http://news.onion/feed.xml", "--json"].iter().map(|s| s.to_string()).collect();
+        assert!(!take_flag(&mut argv, "--json"));
+        assert_eq!(argv, vec!["orc", "feed", "--url", "http://news.onion/feed.xml", "--json"]);
+    }
+
+    #[test]
+    fn reports_false_when_the_flag_is_absent() {
+        let mut argv: Vec<String> = ["orc", "resolve", "example.onion"].iter().map(|s| s.to_string()).collect();
+        assert!(!take_flag(&mut argv, "--verbose"));
+    }
+
+    #[test]
+    fn takes_a_value_flag_given_as_two_arguments() {
+        let mut argv: Vec<String> = ["orc", "--color", "always", "fetch", "http://x.onion"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(take_value_flag(&mut argv, "--color"), Some("always".to_string()));
+        assert_eq!(argv, vec!["orc", "fetch", "http://x.onion"]);
+    }
+
+    #[test]
+    fn takes_a_value_flag_given_with_an_equals_sign() {
+        let mut argv: Vec<String> = ["orc", "--color=never", "fetch", "http://x.onion"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(take_value_flag(&mut argv, "--color"), Some("never".to_string()));
+        assert_eq!(argv, vec!["orc", "fetch", "http://x.onion"]);
+    }
+
+    #[test]
+    fn value_flag_returns_none_when_absent() {
+        let mut argv: Vec<String> = ["orc", "resolve", "example.onion"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(take_value_flag(&mut argv, "--color"), None);
+    }
+}
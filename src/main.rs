@@ -0,0 +1,9 @@
+//! The `orc` binary: nothing but a call into the library crate's
+//! [`orc::run_cli`], which is where every module this used to declare
+//! directly now lives — see that crate's root doc comment for why, and
+//! [`orc::OrcClient`] for the API an embedder reaches for instead of
+//! this binary.
+
+fn main() {
+    orc::run_cli();
+}
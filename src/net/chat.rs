@@ -0,0 +1,183 @@
+//! A tiny encrypted line-chat protocol for two peers meeting over onion
+//! addresses.
+//!
+//! Chat doesn't publish an onion service itself — for chat that remains
+//! the Tor daemon's job (`torrc` `HiddenServiceDir`/`HiddenServicePort`,
+//! pointed at the port `chat --listen` binds). Likewise, no
+//! Diffie-Hellman exchange is done here, so this is not a real Noise
+//! handshake: both sides
+//! instead share a passphrase out of band (read aloud over a separate
+//! channel) and derive a session key from it with SHA-256. Every line is
+//! then encrypted with a SHA-256 keystream and authenticated with
+//! HMAC-SHA256. That gives confidentiality and tamper-evidence against a
+//! network observer but, unlike Noise, no forward secrecy: compromising
+//! the passphrase after the fact still decrypts a captured transcript.
+
+use std::io::{Read, Write};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{OrcError, Result};
+use crate::secret::SensitiveString;
+
+const HMAC_BLOCK_SIZE: usize = 64;
+const TAG_LEN: usize = 32;
+
+/// The sending half of a chat session. Independent of [`ChatReader`] so
+/// the two can live on separate threads reading/writing a cloned stream
+/// concurrently, the same split-direction shape as `orc nc` and `orc
+/// forward`.
+pub struct ChatWriter<W> {
+    stream: W,
+    key: [u8; 32],
+    counter: u64,
+}
+
+/// The receiving half of a chat session. See [`ChatWriter`].
+pub struct ChatReader<R> {
+    stream: R,
+    key: [u8; 32],
+    counter: u64,
+}
+
+impl<W: Write> ChatWriter<W> {
+    pub fn new(stream: W, passphrase: &SensitiveString) -> Self {
+        ChatWriter { stream, key: derive_key(passphrase.as_str()), counter: 0 }
+    }
+
+    /// Encrypts and sends one line of text as a length-prefixed frame.
+    pub fn send_line(&mut self, line: &str) -> Result<()> {
+        let keystream = keystream(&self.key, self.counter, line.len());
+        let mut ciphertext: Vec<u8> = line.bytes().zip(keystream).map(|(b, k)| b ^ k).collect();
+        let tag = hmac_sha256(&self.key, &self.counter.to_be_bytes(), &ciphertext);
+        ciphertext.extend_from_slice(&tag);
+
+        self.stream.write_all(&(ciphertext.len() as u32).to_be_bytes())?;
+        self.stream.write_all(&ciphertext)?;
+        self.counter += 1;
+        Ok(())
+    }
+}
+
+impl<R: Read> ChatReader<R> {
+    pub fn new(stream: R, passphrase: &SensitiveString) -> Self {
+        ChatReader { stream, key: derive_key(passphrase.as_str()), counter: 0 }
+    }
+
+    /// Reads and decrypts one frame, checking its authentication tag.
+    pub fn recv_line(&mut self) -> Result<String> {
+        let mut len_bytes = [0u8; 4];
+        self.stream.read_exact(&mut len_bytes)?;
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        if len < TAG_LEN {
+            return Err(OrcError::Socks("chat frame is shorter than its authentication tag".into()));
+        }
+
+        let mut frame = vec![0u8; len];
+        self.stream.read_exact(&mut frame)?;
+        let (ciphertext, tag) = frame.split_at(len - TAG_LEN);
+
+        let expected = hmac_sha256(&self.key, &self.counter.to_be_bytes(), ciphertext);
+        if !crate::constant_time::eq(&expected, tag) {
+            return Err(OrcError::Socks("chat frame failed authentication".into()));
+        }
+
+        let keystream = keystream(&self.key, self.counter, ciphertext.len());
+        let plaintext: Vec<u8> = ciphertext.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+        self.counter += 1;
+        String::from_utf8(plaintext).map_err(|_| OrcError::Socks("decrypted chat frame is not valid UTF-8".into()))
+    }
+}
+
+fn derive_key(passphrase: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-chat-v1");
+    hasher.update(passphrase.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Produces `len` bytes of keystream by hashing the key, a per-message
+/// counter, and a block counter together, one SHA-256 block at a time.
+fn keystream(key: &[u8; 32], message_counter: u64, len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        hasher.update(message_counter.to_be_bytes());
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// A textbook HMAC-SHA256: `H((key XOR opad) || H((key XOR ipad) || message))`.
+fn hmac_sha256(key: &[u8; 32], nonce: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; HMAC_BLOCK_SIZE];
+    key_block[..key.len()].copy_from_slice(key);
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(nonce);
+    inner.update(message);
+    let inner_digest = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_digest);
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn keystream_is_deterministic_and_long_enough() {
+        let key = derive_key("correct horse battery staple");
+        let a = keystream(&key, 0, 100);
+        let b = keystream(&key, 0, 100);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 100);
+    }
+
+    #[test]
+    fn different_counters_give_different_keystreams() {
+        let key = derive_key("correct horse battery staple");
+        assert_ne!(keystream(&key, 0, 32), keystream(&key, 1, 32));
+    }
+
+    #[test]
+    fn round_trips_a_line_between_a_writer_and_a_reader() {
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let mut writer = ChatWriter::new(Cursor::new(Vec::new()), &passphrase);
+        writer.send_line("hello there").unwrap();
+        let written = writer.stream.into_inner();
+
+        let mut reader = ChatReader::new(Cursor::new(written), &passphrase);
+        assert_eq!(reader.recv_line().unwrap(), "hello there");
+    }
+
+    #[test]
+    fn rejects_a_tampered_frame() {
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let mut writer = ChatWriter::new(Cursor::new(Vec::new()), &passphrase);
+        writer.send_line("hello there").unwrap();
+        let mut written = writer.stream.into_inner();
+        let last = written.len() - 1;
+        written[last] ^= 0xff;
+
+        let mut reader = ChatReader::new(Cursor::new(written), &passphrase);
+        assert!(reader.recv_line().is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! A minimal Electrum server client: newline-delimited JSON-RPC over TLS,
+//! trust-on-first-use pinned like Gemini. Only balance queries and
+//! transaction broadcast — no header sync, no subscriptions. Balance
+//! queries take a scripthash directly (the sha256 of the output script,
+//! reversed); deriving one from an address would need base58/bech32
+//! decoding this crate doesn't implement.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls::{self, TofuTlsStream};
+use crate::net::tcp::Socks5Stream;
+
+pub struct ElectrumConnection {
+    reader: BufReader<TofuTlsStream<Socks5Stream>>,
+    next_id: u64,
+}
+
+impl ElectrumConnection {
+    /// Connects through the SOCKS proxy and upgrades to TLS, pinning the
+    /// server's certificate on first use.
+    pub fn connect(proxy: SocketAddr, host: &str, port: u16, pin_file: &Path, options: &ConnectOptions) -> Result<ElectrumConnection> {
+        let socks_stream = create_socks_stream(proxy, host, port, options)?;
+        let tls_stream = tls::connect_tofu(socks_stream, host, pin_file)?;
+        Ok(ElectrumConnection { reader: BufReader::new(tls_stream), next_id: 1 })
+    }
+
+    /// Looks up the confirmed and unconfirmed balance, in satoshis, of a
+    /// given scripthash.
+    pub fn get_balance(&mut self, scripthash: &str) -> Result<Value> {
+        self.call("blockchain.scripthash.get_balance", Value::Array(vec![Value::String(scripthash.to_string())]))
+    }
+
+    /// Broadcasts a raw transaction (as hex) and returns its txid.
+    pub fn broadcast_transaction(&mut self, raw_tx_hex: &str) -> Result<Value> {
+        self.call("blockchain.transaction.broadcast", Value::Array(vec![Value::String(raw_tx_hex.to_string())]))
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let request = build_request(self.next_id, method, params);
+        self.next_id += 1;
+
+        let mut line = request.to_string();
+        line.push('\n');
+        self.reader.get_mut().write_all(line.as_bytes())?;
+
+        let mut response_line = String::new();
+        self.reader.read_line(&mut response_line)?;
+        extract_result(&response_line)
+    }
+}
+
+fn build_request(id: u64, method: &str, params: Value) -> Value {
+    Value::Object(vec![
+        ("id".to_string(), Value::Number(id as f64)),
+        ("method".to_string(), Value::String(method.to_string())),
+        ("params".to_string(), params),
+    ])
+}
+
+fn extract_result(response_line: &str) -> Result<Value> {
+    let response = json::parse(response_line.trim_end())?;
+
+    if let Some(error) = response.get("error") {
+        if *error != Value::Null {
+            return Err(OrcError::Socks(format!("Electrum call failed: {error}")));
+        }
+    }
+
+    response.get("result").cloned().ok_or_else(|| OrcError::Socks("Electrum response is missing a result".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_request_with_an_id() {
+        let request = build_request(3, "server.version", Value::Array(vec![]));
+        assert_eq!(request.get("id"), Some(&Value::Number(3.0)));
+        assert_eq!(request.get("method"), Some(&Value::String("server.version".to_string())));
+    }
+
+    #[test]
+    fn extracts_the_result_field() {
+        let result = extract_result(r#"{"id":1,"result":{"confirmed":100}}"#).unwrap();
+        assert_eq!(result.get("confirmed"), Some(&Value::Number(100.0)));
+    }
+
+    #[test]
+    fn rejects_an_error_response() {
+        assert!(extract_result(r#"{"id":1,"error":{"message":"unknown method"}}"#).is_err());
+    }
+}
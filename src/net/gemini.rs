@@ -0,0 +1,280 @@
+//! A minimal Gemini protocol (gemini://) client: TLS with trust-on-first-
+//! use pinning, a single CRLF-terminated request line, a two-digit status
+//! + meta header line, and a body.
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls::{self, PinStore};
+
+pub struct GeminiResponse {
+    pub status: u8,
+    pub meta: String,
+    pub body: Vec<u8>,
+}
+
+/// Fetches `url` (a `gemini://host[:port]/path` URL) through the SOCKS
+/// proxy at `proxy`, pinning the server's TLS certificate in `pin_store`.
+pub fn fetch(
+    proxy: SocketAddr,
+    url: &str,
+    pin_store: &PinStore,
+    options: &ConnectOptions,
+) -> Result<GeminiResponse> {
+    let (host, port) = parse_url(url)?;
+    let socks_stream = create_socks_stream(proxy, host, port, options)?;
+    let mut tls_stream = tls::connect_tofu_with(socks_stream, host, pin_store)?;
+
+    tls_stream.write_all(format!("{url}\r\n").as_bytes())?;
+
+    let mut response = Vec::new();
+    tls_stream.read_to_end(&mut response)?;
+
+    parse_response(&response)
+}
+
+/// True for status codes `1x`: Gemini's form equivalent. Instead of an
+/// HTML form with typed fields, the server asks for a single line of
+/// text (or sensitive text, for `11`) in `meta`, which the client
+/// resubmits as a percent-encoded query on the same URL.
+pub fn is_input_status(status: u8) -> bool {
+    (10..=19).contains(&status)
+}
+
+/// Builds the URL a client re-requests after answering an input prompt:
+/// `url`'s path with any existing query replaced by the percent-encoded
+/// answer.
+pub fn build_query_url(url: &str, answer: &str) -> String {
+    let base = url.split('?').next().unwrap_or(url);
+    format!("{base}?{}", percent_encode(answer))
+}
+
+/// Percent-encodes `value` for a Gemini query string: everything outside
+/// RFC 3986's unreserved set becomes `%XX`.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Pulls the host (and port, defaulting to 1965) out of a `gemini://`
+/// URL — `pub(crate)` so callers like [`crate::commands::browse`] can
+/// look up per-host config without duplicating the parsing.
+pub(crate) fn parse_url(url: &str) -> Result<(&str, u16)> {
+    let rest = url
+        .strip_prefix("gemini://")
+        .ok_or_else(|| OrcError::InvalidArgument(format!("`{url}` is not a gemini:// URL")))?;
+    let authority = rest.split('/').next().unwrap_or(rest);
+    match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port: u16 = port
+                .parse()
+                .map_err(|_| OrcError::InvalidArgument(format!("bad port in `{url}`")))?;
+            Ok((host, port))
+        }
+        None => Ok((authority, 1965)),
+    }
+}
+
+fn parse_response(raw: &[u8]) -> Result<GeminiResponse> {
+    let header_end = raw
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .ok_or_else(|| OrcError::Socks("gemini response is missing a header line".into()))?;
+    let header = std::str::from_utf8(&raw[..header_end])
+        .map_err(|_| OrcError::Socks("gemini header is not valid UTF-8".into()))?;
+
+    let (status_str, meta) = header
+        .split_once(' ')
+        .unwrap_or((header, ""));
+    let status: u8 = status_str
+        .parse()
+        .map_err(|_| OrcError::Socks(format!("invalid gemini status `{status_str}`")))?;
+
+    Ok(GeminiResponse {
+        status,
+        meta: meta.to_string(),
+        body: raw[header_end + 2..].to_vec(),
+    })
+}
+
+/// Renders a text/gemini body into plain text: line-prefix markup (`#`,
+/// `=>`, `*`, etc.) is shown as-is, and only the preformat toggle lines
+/// are dropped, for readability.
+pub fn render_gemtext(body: &str) -> String {
+    let mut out = String::new();
+    let mut preformatted = false;
+    for line in body.lines() {
+        if line.starts_with("```") {
+            preformatted = !preformatted;
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    let _ = preformatted;
+    out
+}
+
+/// Parses a `=>` link line into its URL and display label, the label
+/// falling back to the URL itself when the line doesn't carry one.
+/// Returns `None` for a line that isn't a link line at all.
+pub fn parse_link_line(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix("=>")?.trim_start();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut parts = rest.splitn(2, char::is_whitespace);
+    let url = parts.next()?.to_string();
+    let label = parts.next().map(str::trim).filter(|s| !s.is_empty()).unwrap_or(&url).to_string();
+    Some((url, label))
+}
+
+/// A readability-style pass over already-rendered gemtext lines: drops
+/// `=>` link lines (a capsule's navigation, not its content) and reflows
+/// runs of plain paragraph text to `width` columns, while leaving
+/// headings (`#`), quotes (`>`), and list items (`*`) exactly as they
+/// were — they're structure, not boilerplate, and rewrapping them tends
+/// to make them harder to read rather than easier.
+pub fn reader_mode(lines: &[String], width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    let flush = |paragraph: &mut Vec<&str>, out: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            out.extend(wrap_words(&paragraph.join(" "), width));
+            paragraph.clear();
+        }
+    };
+
+    for line in lines {
+        if line.starts_with("=>") {
+            flush(&mut paragraph, &mut out);
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with('>') || line.starts_with('*') {
+            flush(&mut paragraph, &mut out);
+            out.push(line.clone());
+            continue;
+        }
+        paragraph.push(line);
+    }
+    flush(&mut paragraph, &mut out);
+
+    out
+}
+
+/// Greedily packs whitespace-separated words from `text` into lines no
+/// longer than `width` columns (a single word longer than `width` still
+/// gets its own line rather than being split).
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_url_with_default_port() {
+        assert_eq!(parse_url("gemini://example.onion/index.gmi").unwrap(), ("example.onion", 1965));
+    }
+
+    #[test]
+    fn parses_url_with_explicit_port() {
+        assert_eq!(parse_url("gemini://example.onion:1966/").unwrap(), ("example.onion", 1966));
+    }
+
+    #[test]
+    fn rejects_non_gemini_url() {
+        assert!(parse_url("https://example.com").is_err());
+    }
+
+    #[test]
+    fn parses_success_response() {
+        let raw = b"20 text/gemini\r\n# Hello\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 20);
+        assert_eq!(response.meta, "text/gemini");
+        assert_eq!(response.body, b"# Hello\n");
+    }
+
+    #[test]
+    fn strips_preformat_fences() {
+        let rendered = render_gemtext("before\n```\ncode\n```\nafter\n");
+        assert_eq!(rendered, "before\ncode\nafter\n");
+    }
+
+    #[test]
+    fn parse_link_line_extracts_url_and_label() {
+        assert_eq!(parse_link_line("=> gemini://a/b Read more").unwrap(), ("gemini://a/b".to_string(), "Read more".to_string()));
+    }
+
+    #[test]
+    fn parse_link_line_falls_back_to_the_url_as_its_own_label() {
+        assert_eq!(parse_link_line("=> gemini://a/b").unwrap(), ("gemini://a/b".to_string(), "gemini://a/b".to_string()));
+    }
+
+    #[test]
+    fn parse_link_line_rejects_non_link_lines() {
+        assert!(parse_link_line("# heading").is_none());
+    }
+
+    #[test]
+    fn reader_mode_drops_link_lines_and_reflows_paragraphs() {
+        let lines: Vec<String> = vec![
+            "# Title".to_string(),
+            "".to_string(),
+            "This is a long sentence that".to_string(),
+            "should be rejoined and then rewrapped".to_string(),
+            "to a narrower width than the source had.".to_string(),
+            "=> gemini://example.onion/other see also".to_string(),
+        ];
+        let reader = reader_mode(&lines, 20);
+        assert_eq!(reader[0], "# Title");
+        assert_eq!(reader[1], "");
+        assert!(reader[2..].iter().all(|line| line.len() <= 20));
+        assert!(reader.iter().any(|line| line.contains("sentence")));
+        assert!(!reader.iter().any(|line| line.starts_with("=>")));
+    }
+
+    #[test]
+    fn reader_mode_keeps_headings_quotes_and_list_items_unwrapped() {
+        let lines: Vec<String> = vec!["> a quote that is certainly longer than the wrap width given".to_string(), "* a list item also longer than that width".to_string()];
+        let reader = reader_mode(&lines, 10);
+        assert_eq!(reader, lines);
+    }
+
+    #[test]
+    fn recognizes_input_statuses() {
+        assert!(is_input_status(10));
+        assert!(is_input_status(11));
+        assert!(!is_input_status(20));
+    }
+
+    #[test]
+    fn builds_a_query_url_replacing_any_existing_query() {
+        assert_eq!(build_query_url("gemini://example.onion/search?old", "a query"), "gemini://example.onion/search?a%20query");
+    }
+}
@@ -0,0 +1,294 @@
+//! A minimal HTTP/1.1 client: a single request over a SOCKS connection,
+//! always `Connection: close`, no chunked transfer-encoding. Just enough
+//! for `orc rpc`, `orc feed`, `orc fetch`, `orc matrix` and `orc oshare`;
+//! not a general-purpose HTTP client.
+//!
+//! The response is read in chunks rather than in one call so
+//! [`ConnectOptions::cancellation`] can stop a long transfer between
+//! chunks — see [`crate::cancellation`].
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use crate::error::{OrcError, Result};
+use crate::events::{Direction, OrcEvent};
+use crate::net::json;
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+
+pub struct HttpResponse {
+    pub status: u16,
+    /// Headers in the order the server sent them, as a `Vec` rather than
+    /// a `HashMap` — a header like `Set-Cookie` can legally appear more
+    /// than once, and a map keyed on the header name would silently keep
+    /// only the last one. [`Self::header`] and [`Self::headers_all`] both
+    /// search this list rather than a faster lookup table, since a
+    /// response here is at most a handful of headers.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// The first header matching `name`, case-insensitively — e.g.
+    /// `response.header("location")` when following a redirect.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// Every header matching `name`, case-insensitively, in the order the
+    /// server sent them — for a header like `Set-Cookie` where only the
+    /// first match isn't enough.
+    pub fn headers_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers.iter().filter(move |(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// The `Content-Type` header, with any `;charset=...` or other
+    /// parameters left attached — a caller that cares about just the
+    /// media type can split on `;` itself.
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    /// The body as text, rejecting it if it isn't valid UTF-8. Doesn't
+    /// look at `Content-Type`'s charset parameter — this crate only ever
+    /// talks to plain onion HTTP endpoints, not the wider web.
+    pub fn text(&self) -> Result<&str> {
+        std::str::from_utf8(&self.body).map_err(|_| OrcError::Socks("response body is not valid UTF-8".into()))
+    }
+
+    /// Parses the body as JSON using [`crate::net::json`]'s dependency-free
+    /// parser. There's no `serde` anywhere in this crate (see its own
+    /// module doc comment), so this returns a generic [`json::Value`] to
+    /// walk rather than deserializing into a caller-supplied type.
+    pub fn json(&self) -> Result<json::Value> {
+        json::parse(self.text()?)
+    }
+}
+
+pub struct HttpRequest<'a> {
+    pub proxy: SocketAddr,
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: &'a [(String, String)],
+    pub body: &'a [u8],
+    pub options: &'a ConnectOptions,
+}
+
+/// Sends a single HTTP request and reads the response to EOF, relying on
+/// the server closing the connection rather than parsing `Content-Length`
+/// or chunked framing.
+pub fn send(request: HttpRequest<'_>) -> Result<HttpResponse> {
+    let (host, port, path) = parse_url(request.url)?;
+    let mut stream = create_socks_stream(request.proxy, host, port, request.options)?;
+
+    let mut head = format!("{} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n", request.method);
+    if !request.headers.iter().any(|(key, _)| key.eq_ignore_ascii_case("user-agent")) {
+        head.push_str(&format!("User-Agent: {}\r\n", crate::defaults::user_agent()));
+    }
+    for (key, value) in request.headers {
+        head.push_str(&format!("{key}: {value}\r\n"));
+    }
+    if !request.body.is_empty() {
+        head.push_str(&format!("Content-Length: {}\r\n", request.body.len()));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(request.body)?;
+    if let Some(sink) = &request.options.events {
+        let target = format!("{host}:{port}");
+        sink.handle(OrcEvent::BytesTransferred { target, bytes: head.len() + request.body.len(), direction: Direction::Sent });
+    }
+
+    // Read in chunks rather than one `read_to_end` so a long response
+    // can be cancelled between chunks, and so a cancelled or
+    // over-the-limit response's partial bytes can be zeroized before
+    // this returns rather than left sitting in `raw`.
+    let limit = crate::defaults::max_response_bytes();
+    let mut raw = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        if let Some(token) = &request.options.cancellation {
+            if let Err(err) = token.check() {
+                zeroize_in_place(&mut raw);
+                return Err(err);
+            }
+        }
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        raw.extend_from_slice(&chunk[..read]);
+        if raw.len() as u64 > limit as u64 {
+            zeroize_in_place(&mut raw);
+            return Err(OrcError::Socks(format!("response exceeded the {limit}-byte limit")));
+        }
+    }
+    if let Some(sink) = &request.options.events {
+        let target = format!("{host}:{port}");
+        sink.handle(OrcEvent::BytesTransferred { target, bytes: raw.len(), direction: Direction::Received });
+    }
+    parse_response(&raw)
+}
+
+/// Overwrites `buf`'s bytes with zero in place and clears it — the same
+/// best-effort pattern as [`crate::secret::SensitiveBytes::wipe`], for a
+/// response buffer that's being discarded because the read was
+/// cancelled or went over the size limit, not returned to the caller.
+fn zeroize_in_place(buf: &mut Vec<u8>) {
+    for byte in buf.iter_mut() {
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    buf.clear();
+}
+
+/// Parses `http://host[:port][/path]`; only the scheme this crate's
+/// commands need, since onion HTTP endpoints rarely have public TLS
+/// certificates to validate against anyway.
+pub fn parse_url(url: &str) -> Result<(&str, u16, &str)> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| OrcError::InvalidArgument(format!("`{url}` is not an http:// URL")))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port: u16 = port
+                .parse()
+                .map_err(|_| OrcError::InvalidArgument(format!("bad port in `{url}`")))?;
+            Ok((host, port, path))
+        }
+        None => Ok((authority, 80, path)),
+    }
+}
+
+/// Resolves a `Location` header against the URL the response came from.
+/// An absolute `http://...` URL is used as-is; anything else — most
+/// commonly a same-origin absolute path like `/login`, but also a path
+/// relative to the current one — is resolved against `url`'s host and
+/// port, since servers very often send a `Location` without repeating
+/// the scheme and authority for a redirect that stays on the same site.
+pub fn resolve_location(url: &str, location: &str) -> Result<String> {
+    if location.starts_with("http://") {
+        return Ok(location.to_string());
+    }
+    if location.contains("://") {
+        return Err(OrcError::InvalidArgument(format!("`{location}` is not an http:// URL")));
+    }
+
+    let (host, port, path) = parse_url(url)?;
+    let resolved_path = if location.starts_with('/') {
+        location.to_string()
+    } else {
+        let dir = path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        format!("{dir}/{location}")
+    };
+    if port == 80 {
+        Ok(format!("http://{host}{resolved_path}"))
+    } else {
+        Ok(format!("http://{host}:{port}{resolved_path}"))
+    }
+}
+
+fn parse_response(raw: &[u8]) -> Result<HttpResponse> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| OrcError::Socks("HTTP response is missing a header/body separator".into()))?;
+    let header_text =
+        std::str::from_utf8(&raw[..header_end]).map_err(|_| OrcError::Socks("HTTP response headers are not valid UTF-8".into()))?;
+    let mut lines = header_text.lines();
+    let status_line = lines.next().ok_or_else(|| OrcError::Socks("HTTP response has no status line".into()))?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| OrcError::Socks(format!("malformed HTTP status line: {status_line}")))?;
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(HttpResponse { status, headers, body: raw[header_end + 4..].to_vec() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_url_with_explicit_port_and_path() {
+        let (host, port, path) = parse_url("http://node.onion:8332/rpc").unwrap();
+        assert_eq!((host, port, path), ("node.onion", 8332, "/rpc"));
+    }
+
+    #[test]
+    fn parses_url_without_path() {
+        let (host, port, path) = parse_url("http://node.onion").unwrap();
+        assert_eq!((host, port, path), ("node.onion", 80, "/"));
+    }
+
+    #[test]
+    fn parses_status_code_from_response() {
+        let raw = b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn header_lookup_is_case_insensitive() {
+        let raw = b"HTTP/1.1 302 Found\r\nLocation: http://node.onion/next\r\n\r\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.header("location"), Some("http://node.onion/next"));
+        assert_eq!(response.header("LOCATION"), Some("http://node.onion/next"));
+    }
+
+    #[test]
+    fn header_lookup_returns_none_when_absent() {
+        let raw = b"HTTP/1.1 200 OK\r\n\r\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.header("location"), None);
+    }
+
+    #[test]
+    fn headers_all_returns_every_matching_header_in_order() {
+        let raw = b"HTTP/1.1 200 OK\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n\r\n";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.headers_all("set-cookie").collect::<Vec<_>>(), vec!["a=1", "b=2"]);
+    }
+
+    #[test]
+    fn resolve_location_leaves_an_absolute_url_alone() {
+        let resolved = resolve_location("http://node.onion/old", "http://other.onion/new").unwrap();
+        assert_eq!(resolved, "http://other.onion/new");
+    }
+
+    #[test]
+    fn resolve_location_resolves_an_absolute_path_against_the_same_host() {
+        let resolved = resolve_location("http://node.onion:8332/rpc", "/login").unwrap();
+        assert_eq!(resolved, "http://node.onion:8332/login");
+    }
+
+    #[test]
+    fn resolve_location_resolves_a_relative_path_against_the_current_directory() {
+        let resolved = resolve_location("http://node.onion/a/b", "c").unwrap();
+        assert_eq!(resolved, "http://node.onion/a/c");
+    }
+
+    #[test]
+    fn resolve_location_omits_the_default_port() {
+        let resolved = resolve_location("http://node.onion/old", "/new").unwrap();
+        assert_eq!(resolved, "http://node.onion/new");
+    }
+
+    #[test]
+    fn text_and_json_read_the_body() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"ok\":true}";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.content_type(), Some("application/json"));
+        assert_eq!(response.text().unwrap(), "{\"ok\":true}");
+        assert_eq!(response.json().unwrap().get("ok"), Some(&json::Value::Bool(true)));
+    }
+}
@@ -0,0 +1,56 @@
+//! Encodes image bytes for inline display in a terminal, using whichever
+//! graphics protocol the user picked with `--image-protocol`. Both
+//! protocols this crate supports — kitty's and iTerm2's — embed the
+//! original file bytes (PNG, JPEG, whatever a capsule's image link
+//! returned) base64-encoded rather than raw pixels, so there's no image
+//! decoder anywhere in this crate's dependency tree: the terminal itself
+//! decodes the format. Sixel isn't supported for exactly that reason — a
+//! sixel stream is pixel data, which would mean shipping (or writing) a
+//! PNG/JPEG decoder just to draw a picture in a terminal.
+//!
+//! This also means there's no way to downscale a large image before
+//! sending it, or split a kitty payload across the protocol's documented
+//! 4096-byte chunks — both are left for a future pass if a real image
+//! turns out to need them.
+
+use base64::Engine;
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImageProtocol {
+    /// kitty's graphics protocol (`ESC _G ... ESC \`).
+    Kitty,
+    /// iTerm2's inline image protocol (`ESC ]1337;File=... BEL`).
+    Iterm,
+}
+
+/// Returns the terminal escape sequence that displays `data` (a whole
+/// image file's raw bytes) inline, per `protocol`.
+pub fn render(protocol: ImageProtocol, data: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    match protocol {
+        ImageProtocol::Kitty => format!("\x1b_Ga=T,f=100;{encoded}\x1b\\"),
+        ImageProtocol::Iterm => format!("\x1b]1337;File=inline=1;size={}:{encoded}\x07", data.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kitty_escape_wraps_the_base64_payload_with_the_expected_markers() {
+        let escape = render(ImageProtocol::Kitty, b"hi");
+        assert!(escape.starts_with("\x1b_Ga=T,f=100;"));
+        assert!(escape.ends_with("\x1b\\"));
+        assert!(escape.contains("aGk="));
+    }
+
+    #[test]
+    fn iterm_escape_includes_the_byte_count_and_payload() {
+        let escape = render(ImageProtocol::Iterm, b"hi");
+        assert!(escape.starts_with("\x1b]1337;File=inline=1;size=2:"));
+        assert!(escape.ends_with('\x07'));
+        assert!(escape.contains("aGk="));
+    }
+}
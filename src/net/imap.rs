@@ -0,0 +1,141 @@
+//! A minimal IMAP client: implicit TLS, LOGIN, SELECT, and a FETCH of
+//! whole messages. No IDLE, no partial fetches, no non-INBOX support
+//! beyond SELECTing a different mailbox name.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls;
+use crate::secret::SensitiveString;
+
+const TAG: &str = "a1";
+
+pub struct FetchRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub username: &'a SensitiveString,
+    pub password: &'a SensitiveString,
+    pub mailbox: &'a str,
+    pub pin_file: &'a Path,
+    pub options: &'a ConnectOptions,
+}
+
+/// Logs into an IMAP server, selects a mailbox, and fetches the raw
+/// RFC 822 source of every message in it.
+pub fn fetch_all(request: FetchRequest<'_>) -> Result<Vec<Vec<u8>>> {
+    let socks_stream = create_socks_stream(request.proxy, request.host, request.port, request.options)?;
+    let tls_stream = tls::connect_tofu(socks_stream, request.host, request.pin_file)?;
+    let mut reader = BufReader::new(tls_stream);
+
+    let mut greeting = String::new();
+    reader.read_line(&mut greeting)?;
+
+    command(
+        &mut reader,
+        &format!("LOGIN {} {}", request.username.as_str(), request.password.as_str()),
+    )?;
+    let select_reply = command(&mut reader, &format!("SELECT {}", request.mailbox))?;
+    let count = parse_message_count(&select_reply);
+
+    let mut messages = Vec::with_capacity(count);
+    if count > 0 {
+        let reply = command(&mut reader, &format!("FETCH 1:{count} (RFC822)"))?;
+        messages = split_fetch_literals(&reply);
+    }
+
+    let _ = command(&mut reader, "LOGOUT");
+    Ok(messages)
+}
+
+fn command<R: std::io::Read + Write>(reader: &mut BufReader<R>, body: &str) -> Result<String> {
+    send_line(reader.get_mut(), &format!("{TAG} {body}"))?;
+    read_until_tagged(reader)
+}
+
+fn send_line<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\r\n")?;
+    Ok(())
+}
+
+fn read_until_tagged<R: std::io::Read>(reader: &mut BufReader<R>) -> Result<String> {
+    let mut collected = String::new();
+    loop {
+        let mut line = String::new();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 {
+            return Err(OrcError::Socks("IMAP connection closed unexpectedly".into()));
+        }
+        let tagged = line.starts_with(TAG);
+        collected.push_str(&line);
+        if tagged {
+            if line[TAG.len()..].trim_start().starts_with("OK") {
+                return Ok(collected);
+            }
+            return Err(OrcError::Socks(format!("IMAP command failed: {}", line.trim_end())));
+        }
+    }
+}
+
+fn parse_message_count(select_reply: &str) -> usize {
+    select_reply
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.trim_start_matches('*').trim().splitn(2, ' ');
+            let count: usize = parts.next()?.parse().ok()?;
+            if parts.next()? == "EXISTS" {
+                Some(count)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(0)
+}
+
+/// Extracts each `{n}`-prefixed literal body from a FETCH response.
+fn split_fetch_literals(reply: &str) -> Vec<Vec<u8>> {
+    let bytes = reply.as_bytes();
+    let mut messages = Vec::new();
+    let mut i = 0;
+    while let Some(open) = bytes[i..].iter().position(|&b| b == b'{') {
+        let start = i + open;
+        let Some(close) = bytes[start..].iter().position(|&b| b == b'}') else {
+            break;
+        };
+        let len_str = std::str::from_utf8(&bytes[start + 1..start + close]).unwrap_or("");
+        let Ok(len) = len_str.parse::<usize>() else {
+            i = start + close + 1;
+            continue;
+        };
+        // Literal data starts right after "}\r\n".
+        let data_start = start + close + 1 + 2;
+        if data_start + len > bytes.len() {
+            break;
+        }
+        messages.push(bytes[data_start..data_start + len].to_vec());
+        i = data_start + len;
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exists_count() {
+        let reply = "* 4 EXISTS\r\n* 1 RECENT\r\na1 OK SELECT completed\r\n";
+        assert_eq!(parse_message_count(reply), 4);
+    }
+
+    #[test]
+    fn extracts_single_literal() {
+        let reply = "* 1 FETCH (RFC822 {5}\r\nhello)\r\na1 OK FETCH completed\r\n";
+        let messages = split_fetch_literals(reply);
+        assert_eq!(messages, vec![b"hello".to_vec()]);
+    }
+}
@@ -0,0 +1,311 @@
+//! A small, dependency-free JSON value type: just enough parsing and
+//! serialization for JSON-RPC and Electrum's JSON-lines protocol. Not a
+//! general-purpose JSON library — no streaming, no arbitrary precision
+//! numbers, no preserving insertion order beyond what a `Vec` gives us.
+
+use std::fmt;
+
+use crate::error::{OrcError, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a complete JSON document from `input`, rejecting trailing
+/// non-whitespace content.
+pub fn parse(input: &str) -> Result<Value> {
+    let mut parser = Parser { chars: input.char_indices().peekable(), source: input };
+    parser.skip_whitespace();
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err(malformed("trailing data after JSON value"));
+    }
+    Ok(value)
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => {
+                if n.fract() == 0.0 && n.abs() < 1e15 {
+                    write!(f, "{}", *n as i64)
+                } else {
+                    write!(f, "{n}")
+                }
+            }
+            Value::String(s) => write!(f, "{}", escape_string(s)),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{value}", escape_string(key))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    source: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some((_, c)) if c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some((_, '{')) => self.parse_object(),
+            Some((_, '[')) => self.parse_array(),
+            Some((_, '"')) => self.parse_string().map(Value::String),
+            Some((_, 't')) | Some((_, 'f')) => self.parse_bool(),
+            Some((_, 'n')) => self.parse_null(),
+            Some((_, c)) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            _ => Err(malformed("unexpected end of input")),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            _ => Err(malformed(&format!("expected `{expected}`"))),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if matches!(self.chars.peek(), Some((_, '}'))) {
+            self.chars.next();
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => break,
+                _ => return Err(malformed("expected `,` or `}` in object")),
+            }
+        }
+        Ok(Value::Object(fields))
+    }
+
+    fn parse_array(&mut self) -> Result<Value> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if matches!(self.chars.peek(), Some((_, ']'))) {
+            self.chars.next();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, ']')) => break,
+                _ => return Err(malformed("expected `,` or `]` in array")),
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(out),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '"')) => out.push('"'),
+                    Some((_, '\\')) => out.push('\\'),
+                    Some((_, '/')) => out.push('/'),
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 'r')) => out.push('\r'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, 'b')) => out.push('\u{8}'),
+                    Some((_, 'f')) => out.push('\u{c}'),
+                    Some((_, 'u')) => out.push(self.parse_unicode_escape()?),
+                    _ => return Err(malformed("invalid escape sequence")),
+                },
+                Some((_, c)) => out.push(c),
+                None => return Err(malformed("unterminated string")),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char> {
+        let mut hex = String::with_capacity(4);
+        for _ in 0..4 {
+            match self.chars.next() {
+                Some((_, c)) => hex.push(c),
+                None => return Err(malformed("truncated unicode escape")),
+            }
+        }
+        let code = u32::from_str_radix(&hex, 16).map_err(|_| malformed("invalid unicode escape"))?;
+        char::from_u32(code).ok_or_else(|| malformed("invalid unicode escape"))
+    }
+
+    fn parse_bool(&mut self) -> Result<Value> {
+        if self.consume_literal("true") {
+            Ok(Value::Bool(true))
+        } else if self.consume_literal("false") {
+            Ok(Value::Bool(false))
+        } else {
+            Err(malformed("invalid literal"))
+        }
+    }
+
+    fn parse_null(&mut self) -> Result<Value> {
+        if self.consume_literal("null") {
+            Ok(Value::Null)
+        } else {
+            Err(malformed("invalid literal"))
+        }
+    }
+
+    fn consume_literal(&mut self, literal: &str) -> bool {
+        let start = match self.chars.peek() {
+            Some((i, _)) => *i,
+            None => return false,
+        };
+        if self.source[start..].starts_with(literal) {
+            for _ in 0..literal.len() {
+                self.chars.next();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value> {
+        let start = match self.chars.peek() {
+            Some((i, _)) => *i,
+            None => return Err(malformed("expected a number")),
+        };
+        let mut end = start;
+        while matches!(self.chars.peek(), Some((_, c)) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            end = self.chars.next().unwrap().0 + 1;
+        }
+        self.source[start..end].parse::<f64>().map(Value::Number).map_err(|_| malformed("invalid number"))
+    }
+}
+
+fn malformed(reason: &str) -> OrcError {
+    OrcError::InvalidArgument(format!("malformed JSON: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_flat_object() {
+        let value = parse(r#"{"a": 1, "b": "two", "c": true}"#).unwrap();
+        assert_eq!(value.get("a"), Some(&Value::Number(1.0)));
+        assert_eq!(value.get("b"), Some(&Value::String("two".into())));
+        assert_eq!(value.get("c"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn parses_nested_arrays() {
+        let value = parse("[1, [2, 3], null]").unwrap();
+        let items = value.as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2], Value::Null);
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let value = parse(r#"{"id":1,"method":"ping"}"#).unwrap();
+        let reparsed = parse(&value.to_string()).unwrap();
+        assert_eq!(value, reparsed);
+    }
+
+    #[test]
+    fn rejects_trailing_garbage() {
+        assert!(parse("1 2").is_err());
+    }
+
+    #[test]
+    fn as_str_only_matches_strings() {
+        assert_eq!(Value::String("hi".into()).as_str(), Some("hi"));
+        assert_eq!(Value::Number(1.0).as_str(), None);
+    }
+}
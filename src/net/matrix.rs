@@ -0,0 +1,178 @@
+//! Minimal Matrix client-server API support: password login, `/sync`
+//! long-polling, and sending `m.room.message` events — just enough to
+//! read and post to rooms on an onion-hosted homeserver. No end-to-end
+//! encryption (Olm/Megolm), no media upload, no account registration.
+
+use std::net::SocketAddr;
+
+use crate::error::{OrcError, Result};
+use crate::net::http::{self, HttpRequest};
+use crate::net::json::{self, Value};
+use crate::net::tcp::ConnectOptions;
+use crate::secret::SensitiveString;
+
+pub struct LoginRequest<'a> {
+    pub proxy: SocketAddr,
+    pub homeserver_url: &'a str,
+    pub username: &'a str,
+    pub password: &'a SensitiveString,
+    pub options: &'a ConnectOptions,
+}
+
+/// A logged-in session: the access token and homeserver needed for every
+/// later `/sync` or `/send` call.
+pub struct MatrixSession {
+    proxy: SocketAddr,
+    homeserver_url: String,
+    access_token: SensitiveString,
+    options: ConnectOptions,
+    next_txn: u64,
+}
+
+pub struct TimelineMessage {
+    pub room_id: String,
+    pub sender: String,
+    pub body: String,
+}
+
+pub struct SyncResponse {
+    pub next_batch: String,
+    pub messages: Vec<TimelineMessage>,
+}
+
+/// Logs in with a username and password via `m.login.password`.
+pub fn login(request: LoginRequest<'_>) -> Result<MatrixSession> {
+    let homeserver_url = request.homeserver_url.trim_end_matches('/').to_string();
+    let url = format!("{homeserver_url}/_matrix/client/v3/login");
+    let body = Value::Object(vec![
+        ("type".to_string(), Value::String("m.login.password".to_string())),
+        (
+            "identifier".to_string(),
+            Value::Object(vec![
+                ("type".to_string(), Value::String("m.id.user".to_string())),
+                ("user".to_string(), Value::String(request.username.to_string())),
+            ]),
+        ),
+        ("password".to_string(), Value::String(request.password.as_str().to_string())),
+    ]);
+    let body_bytes = body.to_string().into_bytes();
+    let headers = [("Content-Type".to_string(), "application/json".to_string())];
+
+    let response = http::send(HttpRequest { proxy: request.proxy, method: "POST", url: &url, headers: &headers, body: &body_bytes, options: request.options })?;
+    if response.status != 200 {
+        return Err(OrcError::Socks(format!("login failed with HTTP status {}", response.status)));
+    }
+
+    let parsed = json::parse(&String::from_utf8_lossy(&response.body))?;
+    let access_token = parsed
+        .get("access_token")
+        .and_then(Value::as_str)
+        .ok_or_else(|| OrcError::Socks("login response is missing access_token".into()))?;
+
+    Ok(MatrixSession {
+        proxy: request.proxy,
+        homeserver_url,
+        access_token: SensitiveString::new(access_token.to_string()),
+        options: request.options.clone(),
+        next_txn: 0,
+    })
+}
+
+impl MatrixSession {
+    /// Long-polls `/sync`, returning the new batch token to pass as
+    /// `since` on the next call, plus any `m.room.message` events seen
+    /// in joined rooms.
+    pub fn sync(&self, since: Option<&str>, timeout_ms: u64) -> Result<SyncResponse> {
+        let mut url = format!("{}/_matrix/client/v3/sync?timeout={timeout_ms}", self.homeserver_url);
+        if let Some(since) = since {
+            url.push_str(&format!("&since={since}"));
+        }
+
+        let parsed = self.get(&url)?;
+        let next_batch = parsed.get("next_batch").and_then(Value::as_str).unwrap_or_default().to_string();
+        Ok(SyncResponse { next_batch, messages: extract_messages(&parsed) })
+    }
+
+    /// Sends an `m.text` `m.room.message` event to `room_id`.
+    pub fn send_message(&mut self, room_id: &str, body: &str) -> Result<()> {
+        let txn_id = self.next_txn;
+        self.next_txn += 1;
+
+        let url = format!("{}/_matrix/client/v3/rooms/{room_id}/send/m.room.message/orc-{txn_id}", self.homeserver_url);
+        let payload = Value::Object(vec![
+            ("msgtype".to_string(), Value::String("m.text".to_string())),
+            ("body".to_string(), Value::String(body.to_string())),
+        ]);
+        let body_bytes = payload.to_string().into_bytes();
+        let headers = [self.auth_header(), ("Content-Type".to_string(), "application/json".to_string())];
+
+        let response = http::send(HttpRequest { proxy: self.proxy, method: "PUT", url: &url, headers: &headers, body: &body_bytes, options: &self.options })?;
+        if response.status != 200 {
+            return Err(OrcError::Socks(format!("send failed with HTTP status {}", response.status)));
+        }
+        Ok(())
+    }
+
+    fn get(&self, url: &str) -> Result<Value> {
+        let headers = [self.auth_header()];
+        let response = http::send(HttpRequest { proxy: self.proxy, method: "GET", url, headers: &headers, body: &[], options: &self.options })?;
+        if response.status != 200 {
+            return Err(OrcError::Socks(format!("request to {url} failed with HTTP status {}", response.status)));
+        }
+        json::parse(&String::from_utf8_lossy(&response.body))
+    }
+
+    fn auth_header(&self) -> (String, String) {
+        ("Authorization".to_string(), format!("Bearer {}", self.access_token.as_str()))
+    }
+}
+
+/// Walks `rooms.join.*.timeline.events` in a `/sync` response, keeping
+/// only `m.room.message` events.
+fn extract_messages(sync_response: &Value) -> Vec<TimelineMessage> {
+    let Some(Value::Object(rooms)) = sync_response.get("rooms").and_then(|r| r.get("join")) else {
+        return Vec::new();
+    };
+
+    let mut messages = Vec::new();
+    for (room_id, room) in rooms {
+        let Some(events) = room.get("timeline").and_then(|t| t.get("events")).and_then(Value::as_array) else {
+            continue;
+        };
+        for event in events {
+            if event.get("type").and_then(Value::as_str) != Some("m.room.message") {
+                continue;
+            }
+            let sender = event.get("sender").and_then(Value::as_str).unwrap_or_default().to_string();
+            let body = event.get("content").and_then(|c| c.get("body")).and_then(Value::as_str).unwrap_or_default().to_string();
+            messages.push(TimelineMessage { room_id: room_id.clone(), sender, body });
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_messages_from_joined_rooms() {
+        let sync = json::parse(
+            r#"{"next_batch":"s1","rooms":{"join":{"!room:onion":{"timeline":{"events":[
+                {"type":"m.room.message","sender":"@a:onion","content":{"body":"hi"}},
+                {"type":"m.room.member","sender":"@a:onion","content":{}}
+            ]}}}}}"#,
+        )
+        .unwrap();
+        let messages = extract_messages(&sync);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].room_id, "!room:onion");
+        assert_eq!(messages[0].body, "hi");
+    }
+
+    #[test]
+    fn returns_no_messages_without_joined_rooms() {
+        let sync = json::parse(r#"{"next_batch":"s1"}"#).unwrap();
+        assert!(extract_messages(&sync).is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+pub mod chat;
+pub mod electrum;
+#[cfg(feature = "http")]
+pub mod feed;
+pub mod gemini;
+pub mod http;
+#[cfg(feature = "tui")]
+pub mod imageterm;
+pub mod imap;
+pub mod json;
+pub mod matrix;
+pub mod mqtt;
+pub mod nntp;
+pub mod onion;
+pub mod onion_auth;
+#[cfg(feature = "serve")]
+pub mod onion_identity;
+pub mod oshare;
+pub mod pop3;
+pub mod resolve;
+#[cfg(feature = "http")]
+pub mod rpc;
+pub mod script;
+pub mod smtp;
+pub mod tcp;
+pub mod tls;
+#[cfg(feature = "control-port")]
+pub mod torctl;
+pub mod transport;
+pub mod xmpp;
+
+use crate::error::{OrcError, Result};
+
+/// Splits a `host:port` string as used throughout the `orc` CLI. `host`
+/// may be an onion address, a regular hostname, or an IP literal.
+pub fn split_host_port(target: &str) -> Result<(&str, u16)> {
+    let (host, port) = target.rsplit_once(':').ok_or_else(|| {
+        OrcError::InvalidArgument(format!("target `{target}` must be in host:port form"))
+    })?;
+    let port: u16 = port
+        .parse()
+        .map_err(|_| OrcError::InvalidArgument(format!("`{port}` is not a valid port number")))?;
+    Ok((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_host_and_port() {
+        assert_eq!(split_host_port("example.onion:80").unwrap(), ("example.onion", 80));
+    }
+
+    #[test]
+    fn rejects_target_without_port() {
+        assert!(split_host_port("example.onion").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! A minimal MQTT 3.1.1 client: CONNECT, PUBLISH, SUBSCRIBE, and the
+//! PUBACK bookkeeping QoS 1 needs. No PINGREQ is ever sent (a PINGRESP
+//! that arrives is skipped). No QoS 2, no retained-message
+//! bookkeeping beyond what the broker does itself, no persistent sessions.
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions, Socks5Stream};
+
+const PACKET_CONNECT: u8 = 1;
+const PACKET_CONNACK: u8 = 2;
+const PACKET_PUBLISH: u8 = 3;
+const PACKET_PUBACK: u8 = 4;
+const PACKET_SUBSCRIBE: u8 = 8;
+const PACKET_SUBACK: u8 = 9;
+const PACKET_PINGRESP: u8 = 13;
+const PACKET_DISCONNECT: u8 = 14;
+
+const PROTOCOL_LEVEL: u8 = 4; // MQTT 3.1.1
+
+/// An open, CONNACK-acknowledged MQTT session. Owns the underlying SOCKS
+/// stream so callers can keep publishing or reading without reconnecting.
+pub struct MqttConnection {
+    stream: Socks5Stream,
+    next_packet_id: u16,
+}
+
+/// A message delivered by the broker in response to a subscription.
+pub struct Message {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub qos: u8,
+}
+
+impl MqttConnection {
+    /// Opens the SOCKS connection and performs the MQTT CONNECT handshake.
+    pub fn connect(
+        proxy: SocketAddr,
+        host: &str,
+        port: u16,
+        client_id: &str,
+        keep_alive: Duration,
+        options: &ConnectOptions,
+    ) -> Result<MqttConnection> {
+        let mut stream = create_socks_stream(proxy, host, port, options)?;
+
+        let mut variable_header = Vec::new();
+        write_utf8_str(&mut variable_header, "MQTT");
+        variable_header.push(PROTOCOL_LEVEL);
+        variable_header.push(0x02); // connect flags: clean session
+        variable_header.extend_from_slice(&(keep_alive.as_secs() as u16).to_be_bytes());
+
+        let mut payload = Vec::new();
+        write_utf8_str(&mut payload, client_id);
+
+        write_packet(&mut stream, PACKET_CONNECT, 0x00, &variable_header, &payload)?;
+
+        let (packet_type, _flags, body) = read_packet(&mut stream)?;
+        if packet_type != PACKET_CONNACK {
+            return Err(OrcError::Socks(format!("expected CONNACK, got packet type {packet_type}")));
+        }
+        if body.len() < 2 || body[1] != 0x00 {
+            return Err(OrcError::Socks(format!("broker refused the connection (code {})", body.get(1).copied().unwrap_or(0xFF))));
+        }
+
+        Ok(MqttConnection { stream, next_packet_id: 1 })
+    }
+
+    /// Publishes `payload` to `topic` at QoS 0 or 1.
+    pub fn publish(&mut self, topic: &str, payload: &[u8], qos: u8) -> Result<()> {
+        let mut variable_header = Vec::new();
+        write_utf8_str(&mut variable_header, topic);
+
+        let flags = (qos & 0x03) << 1;
+        let packet_id = if qos > 0 {
+            let id = self.take_packet_id();
+            variable_header.extend_from_slice(&id.to_be_bytes());
+            Some(id)
+        } else {
+            None
+        };
+
+        write_packet(&mut self.stream, PACKET_PUBLISH, flags, &variable_header, payload)?;
+
+        if packet_id.is_some() {
+            let (packet_type, _flags, body) = read_packet(&mut self.stream)?;
+            if packet_type != PACKET_PUBACK {
+                return Err(OrcError::Socks(format!("expected PUBACK, got packet type {packet_type}")));
+            }
+            let _ = body;
+        }
+        Ok(())
+    }
+
+    /// Subscribes to `topic_filter` (which may use MQTT's `+`/`#` wildcards)
+    /// at the given maximum QoS and waits for the broker's SUBACK.
+    pub fn subscribe(&mut self, topic_filter: &str, qos: u8) -> Result<()> {
+        let packet_id = self.take_packet_id();
+        let mut variable_header = Vec::new();
+        variable_header.extend_from_slice(&packet_id.to_be_bytes());
+
+        let mut payload = Vec::new();
+        write_utf8_str(&mut payload, topic_filter);
+        payload.push(qos & 0x03);
+
+        write_packet(&mut self.stream, PACKET_SUBSCRIBE, 0x02, &variable_header, &payload)?;
+
+        let (packet_type, _flags, body) = read_packet(&mut self.stream)?;
+        if packet_type != PACKET_SUBACK {
+            return Err(OrcError::Socks(format!("expected SUBACK, got packet type {packet_type}")));
+        }
+        if body.get(2) == Some(&0x80) {
+            return Err(OrcError::Socks(format!("broker rejected subscription to {topic_filter}")));
+        }
+        Ok(())
+    }
+
+    /// Blocks until the broker delivers the next PUBLISH, acknowledging it
+    /// with a PUBACK if it was sent at QoS 1.
+    pub fn read_message(&mut self) -> Result<Message> {
+        loop {
+            let (packet_type, flags, body) = read_packet(&mut self.stream)?;
+            match packet_type {
+                PACKET_PUBLISH => return self.handle_publish(flags, &body),
+                PACKET_PINGRESP => continue,
+                other => return Err(OrcError::Socks(format!("unexpected packet type {other} while waiting for a message"))),
+            }
+        }
+    }
+
+    fn handle_publish(&mut self, flags: u8, body: &[u8]) -> Result<Message> {
+        let qos = (flags >> 1) & 0x03;
+        let mut cursor = 0;
+        let topic_len = read_u16(body, &mut cursor)? as usize;
+        let topic = String::from_utf8_lossy(&body[cursor..cursor + topic_len]).into_owned();
+        cursor += topic_len;
+
+        let packet_id = if qos > 0 {
+            let id = read_u16(body, &mut cursor)?;
+            Some(id)
+        } else {
+            None
+        };
+
+        let payload = body[cursor..].to_vec();
+
+        if let Some(id) = packet_id {
+            let mut variable_header = Vec::new();
+            variable_header.extend_from_slice(&id.to_be_bytes());
+            write_packet(&mut self.stream, PACKET_PUBACK, 0x00, &variable_header, &[])?;
+        }
+
+        Ok(Message { topic, payload, qos })
+    }
+
+    /// Sends a clean DISCONNECT.
+    pub fn disconnect(mut self) -> Result<()> {
+        write_packet(&mut self.stream, PACKET_DISCONNECT, 0x00, &[], &[])
+    }
+
+    fn take_packet_id(&mut self) -> u16 {
+        let id = self.next_packet_id;
+        self.next_packet_id = self.next_packet_id.wrapping_add(1).max(1);
+        id
+    }
+}
+
+fn write_utf8_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn read_u16(body: &[u8], cursor: &mut usize) -> Result<u16> {
+    if *cursor + 2 > body.len() {
+        return Err(OrcError::Socks("truncated MQTT packet".into()));
+    }
+    let value = u16::from_be_bytes([body[*cursor], body[*cursor + 1]]);
+    *cursor += 2;
+    Ok(value)
+}
+
+fn write_packet<W: Write>(stream: &mut W, packet_type: u8, flags: u8, variable_header: &[u8], payload: &[u8]) -> Result<()> {
+    let remaining_len = variable_header.len() + payload.len();
+    let mut packet = Vec::with_capacity(1 + 4 + remaining_len);
+    packet.push((packet_type << 4) | flags);
+    encode_remaining_length(&mut packet, remaining_len);
+    packet.extend_from_slice(variable_header);
+    packet.extend_from_slice(payload);
+    stream.write_all(&packet)?;
+    Ok(())
+}
+
+fn encode_remaining_length(buf: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+fn read_packet<R: Read>(stream: &mut R) -> Result<(u8, u8, Vec<u8>)> {
+    let mut first_byte = [0u8; 1];
+    stream.read_exact(&mut first_byte)?;
+    let packet_type = first_byte[0] >> 4;
+    let flags = first_byte[0] & 0x0F;
+
+    let mut remaining_len = 0usize;
+    let mut multiplier = 1usize;
+    loop {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte)?;
+        remaining_len += (byte[0] & 0x7F) as usize * multiplier;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        multiplier *= 128;
+    }
+
+    let mut body = vec![0u8; remaining_len];
+    stream.read_exact(&mut body)?;
+    Ok((packet_type, flags, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_small_remaining_length() {
+        let mut buf = Vec::new();
+        encode_remaining_length(&mut buf, 127);
+        assert_eq!(buf, vec![0x7F]);
+    }
+
+    #[test]
+    fn encodes_multi_byte_remaining_length() {
+        let mut buf = Vec::new();
+        encode_remaining_length(&mut buf, 321);
+        assert_eq!(buf, vec![0xC1, 0x02]);
+    }
+
+    #[test]
+    fn round_trips_a_packet() {
+        let mut buf = Vec::new();
+        write_packet(&mut buf, PACKET_PUBLISH, 0x00, b"hdr", b"payload").unwrap();
+        let mut cursor = std::io::Cursor::new(buf);
+        let (packet_type, flags, body) = read_packet(&mut cursor).unwrap();
+        assert_eq!(packet_type, PACKET_PUBLISH);
+        assert_eq!(flags, 0x00);
+        assert_eq!(body, b"hdrpayload");
+    }
+}
@@ -0,0 +1,180 @@
+//! A minimal NNTP client: LIST, GROUP, XOVER, and ARTICLE. No posting, no
+//! authentication, no persistent state between invocations — every call
+//! opens its own connection and closes it when done.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+
+/// One line of a `LIST` response: a newsgroup and its article range.
+pub struct GroupInfo {
+    pub name: String,
+    pub high: u64,
+    pub low: u64,
+    pub status: String,
+}
+
+/// One line of an `XOVER` response, as commonly returned by NNTP servers.
+pub struct ArticleHeader {
+    pub number: u64,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+    pub message_id: String,
+}
+
+pub struct HeaderFetchRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub group: &'a str,
+    pub range: &'a str,
+    pub options: &'a ConnectOptions,
+}
+
+pub struct ArticleFetchRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub group: &'a str,
+    pub number: u64,
+    pub options: &'a ConnectOptions,
+}
+
+/// Lists every newsgroup the server carries.
+pub fn list_groups(proxy: SocketAddr, host: &str, port: u16, options: &ConnectOptions) -> Result<Vec<GroupInfo>> {
+    let mut reader = connect(proxy, host, port, options)?;
+    send_line(reader.get_mut(), "LIST")?;
+    read_reply(&mut reader, "215")?;
+    read_dot_terminated(&mut reader)?
+        .lines()
+        .map(parse_group_line)
+        .collect()
+}
+
+/// Selects `request.group` and fetches overview headers for `request.range`
+/// (an NNTP article range such as `1-100` or `3000-`).
+pub fn fetch_headers(request: HeaderFetchRequest<'_>) -> Result<Vec<ArticleHeader>> {
+    let mut reader = connect(request.proxy, request.host, request.port, request.options)?;
+    send_line(reader.get_mut(), &format!("GROUP {}", request.group))?;
+    read_reply(&mut reader, "211")?;
+
+    send_line(reader.get_mut(), &format!("XOVER {}", request.range))?;
+    read_reply(&mut reader, "224")?;
+    read_dot_terminated(&mut reader)?
+        .lines()
+        .map(parse_overview_line)
+        .collect()
+}
+
+/// Selects `request.group` and retrieves the raw article text (headers and
+/// body) for `request.number`.
+pub fn fetch_article(request: ArticleFetchRequest<'_>) -> Result<Vec<u8>> {
+    let mut reader = connect(request.proxy, request.host, request.port, request.options)?;
+    send_line(reader.get_mut(), &format!("GROUP {}", request.group))?;
+    read_reply(&mut reader, "211")?;
+
+    send_line(reader.get_mut(), &format!("ARTICLE {}", request.number))?;
+    read_reply(&mut reader, "220")?;
+    read_dot_terminated_bytes(&mut reader)
+}
+
+fn connect(
+    proxy: SocketAddr,
+    host: &str,
+    port: u16,
+    options: &ConnectOptions,
+) -> Result<BufReader<crate::net::tcp::Socks5Stream>> {
+    let stream = create_socks_stream(proxy, host, port, options)?;
+    let mut reader = BufReader::new(stream);
+    read_reply(&mut reader, "20")?;
+    Ok(reader)
+}
+
+fn send_line<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// Reads a single reply line and checks it starts with `expected_code`
+/// (a prefix, so callers can pass e.g. `"20"` to accept both 200 and 201).
+fn read_reply<R: std::io::Read>(reader: &mut BufReader<R>, expected_code: &str) -> Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.starts_with(expected_code) {
+        Ok(line)
+    } else {
+        Err(OrcError::Socks(format!("unexpected NNTP reply: {}", line.trim_end())))
+    }
+}
+
+fn read_dot_terminated<R: std::io::Read>(reader: &mut BufReader<R>) -> Result<String> {
+    let bytes = read_dot_terminated_bytes(reader)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn read_dot_terminated_bytes<R: std::io::Read>(reader: &mut BufReader<R>) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        let n = reader.read_until(b'\n', &mut line)?;
+        if n == 0 || line == b".\r\n" || line == b".\n" {
+            break;
+        }
+        body.extend_from_slice(&line);
+    }
+    Ok(body)
+}
+
+fn parse_group_line(line: &str) -> Result<GroupInfo> {
+    let mut parts = line.split_whitespace();
+    let name = parts.next().ok_or_else(|| malformed("LIST", line))?;
+    let high: u64 = parts.next().and_then(|n| n.parse().ok()).ok_or_else(|| malformed("LIST", line))?;
+    let low: u64 = parts.next().and_then(|n| n.parse().ok()).ok_or_else(|| malformed("LIST", line))?;
+    let status = parts.next().unwrap_or("y").to_string();
+    Ok(GroupInfo { name: name.to_string(), high, low, status })
+}
+
+fn parse_overview_line(line: &str) -> Result<ArticleHeader> {
+    let mut fields = line.split('\t');
+    let number: u64 = fields.next().and_then(|n| n.parse().ok()).ok_or_else(|| malformed("XOVER", line))?;
+    let subject = fields.next().unwrap_or_default().to_string();
+    let from = fields.next().unwrap_or_default().to_string();
+    let date = fields.next().unwrap_or_default().to_string();
+    let message_id = fields.next().unwrap_or_default().to_string();
+    Ok(ArticleHeader { number, subject, from, date, message_id })
+}
+
+fn malformed(command: &str, line: &str) -> OrcError {
+    OrcError::Socks(format!("malformed {command} response line: {line}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_group_line() {
+        let group = parse_group_line("alt.privacy 100 1 y").unwrap();
+        assert_eq!(group.name, "alt.privacy");
+        assert_eq!(group.high, 100);
+        assert_eq!(group.low, 1);
+        assert_eq!(group.status, "y");
+    }
+
+    #[test]
+    fn parses_overview_line() {
+        let header = parse_overview_line("42\tHello\tuser@example\tToday\t<id@example>").unwrap();
+        assert_eq!(header.number, 42);
+        assert_eq!(header.subject, "Hello");
+        assert_eq!(header.message_id, "<id@example>");
+    }
+
+    #[test]
+    fn rejects_malformed_group_line() {
+        assert!(parse_group_line("").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Validates Tor v3 `.onion` addresses the way `orc` sends them to the
+//! SOCKS proxy: base32-decodes the address, checks its version byte and
+//! truncated SHA3-256 checksum, and rejects the old (and now-dead) v2
+//! address format rather than silently accepting whatever 16 characters
+//! happen to precede `.onion`.
+//!
+//! Called from [`crate::security::HostPolicy::check`], so every protocol
+//! module gets this for free through [`crate::security::check_host`] —
+//! same reasoning as that module's allow/deny lists. Non-onion hosts
+//! (regular domains, IP literals) are left alone; only addresses ending
+//! in `.onion` are looked at.
+
+use sha3::{Digest, Sha3_256};
+
+use crate::error::{OrcError, Result};
+
+const V3_ADDRESS_LEN: usize = 56;
+const V2_ADDRESS_LEN: usize = 16;
+const V3_VERSION: u8 = 3;
+const CHECKSUM_LEN: usize = 2;
+/// Length of a v3 onion public key — also [`crate::net::onion_identity`]'s
+/// `ed25519_dalek::VerifyingKey::to_bytes`, since it's the same ed25519
+/// public key either way.
+pub(crate) const PUBKEY_LEN: usize = 32;
+
+/// Checks `host` if it looks like an onion address; anything not ending
+/// in `.onion` is returned as-is, since format validation here has
+/// nothing to do with regular hostnames.
+///
+/// `allow_v2` opts back into accepting the old 16-character v2 format,
+/// which is otherwise rejected outright — Tor stopped serving v2
+/// addresses in 2021, so one that still resolves today is stale or
+/// actively malicious, not a real destination.
+pub fn validate_onion_host(host: &str, allow_v2: bool) -> Result<()> {
+    let Some(label) = host.strip_suffix(".onion") else {
+        return Ok(());
+    };
+
+    if label.len() == V2_ADDRESS_LEN {
+        if allow_v2 {
+            return Ok(());
+        }
+        return Err(OrcError::InvalidArgument(format!(
+            "`{host}` looks like a v2 onion address (16 characters); v2 was retired in 2021 and is rejected by default — set \"security.allow_v2_onion\" to true to accept one anyway"
+        )));
+    }
+
+    if label.len() != V3_ADDRESS_LEN {
+        return Err(OrcError::InvalidArgument(format!(
+            "`{host}` is not a valid onion address: expected {V3_ADDRESS_LEN} characters before \".onion\", found {}",
+            label.len()
+        )));
+    }
+
+    let decoded = decode_base32(label).ok_or_else(|| OrcError::InvalidArgument(format!("`{host}` is not valid base32")))?;
+    if decoded.len() != PUBKEY_LEN + CHECKSUM_LEN + 1 {
+        return Err(OrcError::InvalidArgument(format!("`{host}` does not decode to a v3 onion address")));
+    }
+
+    let pubkey = &decoded[..PUBKEY_LEN];
+    let checksum = &decoded[PUBKEY_LEN..PUBKEY_LEN + CHECKSUM_LEN];
+    let version = decoded[PUBKEY_LEN + CHECKSUM_LEN];
+
+    if version != V3_VERSION {
+        return Err(OrcError::InvalidArgument(format!("`{host}` has version byte {version}, not a v3 onion address")));
+    }
+
+    if checksum != checksum_for(pubkey, version) {
+        return Err(OrcError::InvalidArgument(format!("`{host}` has a checksum that doesn't match its public key")));
+    }
+
+    Ok(())
+}
+
+/// `SHA3-256(".onion checksum" || pubkey || version)`, truncated to the
+/// first two bytes — the checksum Tor's v3 onion address spec embeds in
+/// every address so a corrupted or hand-typed one can be caught before
+/// it's ever dialed.
+///
+/// `pub(crate)` rather than private so [`crate::net::onion_identity`] can
+/// compute the same checksum when deriving an address from a freshly
+/// generated key, instead of duplicating this.
+pub(crate) fn checksum_for(pubkey: &[u8], version: u8) -> [u8; CHECKSUM_LEN] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b".onion checksum");
+    hasher.update(pubkey);
+    hasher.update([version]);
+    let digest = hasher.finalize();
+    [digest[0], digest[1]]
+}
+
+/// Encodes a v3 onion public key as the `<56 characters>.onion` address
+/// Tor derives from it — the encode-side counterpart of
+/// [`validate_onion_host`]'s decode, used by
+/// [`crate::net::onion_identity`] so a freshly generated identity key can
+/// report its own address without this crate growing a second copy of
+/// the base32/checksum logic. Only that (`serve`-gated) module calls this
+/// outside of tests, hence the matching `#[cfg]`.
+#[cfg(feature = "serve")]
+pub(crate) fn encode_v3_address(pubkey: &[u8; PUBKEY_LEN]) -> String {
+    let checksum = checksum_for(pubkey, V3_VERSION);
+    let mut body = pubkey.to_vec();
+    body.extend_from_slice(&checksum);
+    body.push(V3_VERSION);
+    format!("{}.onion", encode_base32(&body))
+}
+
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Encodes RFC 4648 base32 (no padding) — the encode-side counterpart of
+/// [`decode_base32`]. `pub(crate)` rather than test-only, now that
+/// [`encode_v3_address`] needs it outside of tests too; only used outside
+/// of tests when the `serve` feature is on, hence the `cfg_attr` below.
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+pub(crate) fn encode_base32(data: &[u8]) -> String {
+    let mut bits: u32 = 0;
+    let mut bit_count: u32 = 0;
+    let mut out = String::new();
+    for &byte in data {
+        bits = (bits << 8) | byte as u32;
+        bit_count += 8;
+        while bit_count >= 5 {
+            bit_count -= 5;
+            out.push(BASE32_ALPHABET[(bits >> bit_count) as usize & 0x1f] as char);
+        }
+    }
+    if bit_count > 0 {
+        out.push(BASE32_ALPHABET[(bits << (5 - bit_count)) as usize & 0x1f] as char);
+    }
+    out
+}
+
+/// Decodes RFC 4648 base32 (no padding), case-insensitively — the form
+/// Tor always prints onion addresses in.
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut bits: u32 = 0;
+    let mut bit_count: u32 = 0;
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+
+    for ch in input.chars() {
+        let value = BASE32_ALPHABET.iter().position(|&b| b == ch.to_ascii_lowercase() as u8)? as u32;
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+        }
+    }
+
+    // Any leftover bits must be zero padding, not stray data.
+    if bit_count > 0 && bits & ((1 << bit_count) - 1) != 0 {
+        return None;
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_v3_address() -> String {
+        let pubkey = [7u8; PUBKEY_LEN];
+        let version = V3_VERSION;
+        let checksum = checksum_for(&pubkey, version);
+        let mut body = pubkey.to_vec();
+        body.extend_from_slice(&checksum);
+        body.push(version);
+        format!("{}.onion", encode_base32(&body))
+    }
+
+    #[test]
+    fn ignores_hosts_that_are_not_onion_addresses() {
+        assert!(validate_onion_host("example.com", false).is_ok());
+    }
+
+    #[test]
+    fn accepts_a_well_formed_v3_address() {
+        let host = valid_v3_address();
+        assert!(validate_onion_host(&host, false).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_tampered_checksum() {
+        let mut host = valid_v3_address();
+        let flipped = if host.as_bytes()[0] == b'a' { 'b' } else { 'a' };
+        host.replace_range(0..1, &flipped.to_string());
+        assert!(validate_onion_host(&host, false).is_err());
+    }
+
+    #[test]
+    fn rejects_the_wrong_length() {
+        assert!(validate_onion_host("short.onion", false).is_err());
+    }
+
+    #[test]
+    fn rejects_a_v2_address_by_default() {
+        assert!(validate_onion_host("aaaaaaaaaaaaaaaa.onion", false).is_err());
+    }
+
+    #[test]
+    fn accepts_a_v2_address_when_opted_in() {
+        assert!(validate_onion_host("aaaaaaaaaaaaaaaa.onion", true).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_version_byte_other_than_three() {
+        let pubkey = [9u8; PUBKEY_LEN];
+        let version = 4u8;
+        let checksum = checksum_for(&pubkey, V3_VERSION); // checksum for the wrong version, like a corrupted byte
+        let mut body = pubkey.to_vec();
+        body.extend_from_slice(&checksum);
+        body.push(version);
+        let host = format!("{}.onion", encode_base32(&body));
+        assert!(validate_onion_host(&host, false).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Storage for Tor v3 onion client-authorization private keys — the
+//! x25519 secret a client needs on disk (or wherever it's kept) before
+//! it can reach an onion service that has `ClientAuth` turned on.
+//!
+//! Defaults to one hex-encoded file per onion address under
+//! [`default_auth_dir`], the same "a file per entry" shape
+//! [`crate::net::tls`]'s pin store uses for a single file. Built with
+//! the `keyring-backend` feature, [`AuthKeyStore::Keyring`] keeps the
+//! same keys in the platform keyring (Secret Service, Keychain, Windows
+//! Credential Manager) instead, for a caller who doesn't want a client's
+//! entire set of onion keys sitting in `$HOME` in the clear. There's no
+//! `Memory` variant the way [`crate::net::tls::PinStore`] has one — an
+//! onion client-auth key has to persist somewhere to be useful at all,
+//! so there's no ephemeral use case to serve here.
+//!
+//! [`ClientAuthKeypair`] (built with the `serve` feature) is the other
+//! side of this: generating a *fresh* x25519 keypair to hand to a
+//! client, when `orc serve --client` turns on client authorization for
+//! a hosted service, rather than storing a secret this crate already
+//! has on hand to reach somebody else's.
+
+use std::path::PathBuf;
+
+use crate::error::{OrcError, Result};
+use crate::output;
+use crate::secret::SensitiveBytes;
+
+/// Length of a v3 onion client-auth secret key: a raw x25519 scalar.
+pub const SECRET_LEN: usize = 32;
+
+/// A freshly generated x25519 client-authorization keypair, produced
+/// when `orc serve --client <name>` turns on client auth for a hosted
+/// service. The secret half goes to that client, as the line they drop
+/// into their own `ClientOnionAuthDir` ([`Self::client_auth_line`]); the
+/// public half goes to Tor's control port as the service side's
+/// `ClientAuthV3=` flag ([`Self::public_base32`]) — see
+/// [`crate::net::torctl::TorControlClient::add_onion`]. Neither half is
+/// stored by this crate once printed: unlike [`AuthKeyStore`], there's
+/// no "load this key again later" use case on the hosting side, only on
+/// the client's, and the client's own storage is outside this crate's
+/// control.
+#[cfg(feature = "serve")]
+pub struct ClientAuthKeypair {
+    secret: SensitiveBytes,
+    public: [u8; SECRET_LEN],
+}
+
+#[cfg(feature = "serve")]
+impl ClientAuthKeypair {
+    /// Generates a fresh keypair: [`SECRET_LEN`] random bytes as the
+    /// secret, and its x25519 public key — the same raw-random-bytes
+    /// approach [`crate::net::onion_identity::IdentityKey::generate`]
+    /// takes for ed25519 identity seeds, via real OS randomness.
+    pub fn generate() -> Result<ClientAuthKeypair> {
+        let mut secret = [0u8; SECRET_LEN];
+        getrandom::getrandom(&mut secret).map_err(|err| OrcError::InvalidArgument(format!("could not read random bytes: {err}")))?;
+        let public = curve25519_dalek::MontgomeryPoint::mul_base_clamped(secret).to_bytes();
+        Ok(ClientAuthKeypair { secret: SensitiveBytes::new(secret.to_vec()), public })
+    }
+
+    /// Base32 encoding of the public half — the value Tor's `ADD_ONION`
+    /// expects after `ClientAuthV3=`.
+    pub fn public_base32(&self) -> String {
+        crate::net::onion::encode_base32(&self.public)
+    }
+
+    /// The line a client saves as `<name>.auth_private` under their
+    /// `ClientOnionAuthDir` to actually use this key —
+    /// `<onion-address>:descriptor:x25519:<base32 secret>`, the format
+    /// Tor's control-port manual documents for client-side auth key
+    /// files. `onion_address` may include or omit the `.onion` suffix;
+    /// either way only the address label itself goes into the line.
+    pub fn client_auth_line(&self, onion_address: &str) -> String {
+        let label = onion_address.trim_end_matches(".onion");
+        format!("{label}:descriptor:x25519:{}", crate::net::onion::encode_base32(self.secret.as_bytes()))
+    }
+}
+
+#[cfg(feature = "keyring-backend")]
+const KEYRING_SERVICE: &str = "orc-client-auth";
+#[cfg(feature = "keyring-backend")]
+const KEYRING_INDEX_SERVICE: &str = "orc-client-auth-index";
+#[cfg(feature = "keyring-backend")]
+const KEYRING_INDEX_ACCOUNT: &str = "index";
+
+/// Where client-auth keys are kept.
+#[derive(Clone)]
+pub enum AuthKeyStore {
+    File(PathBuf),
+    #[cfg(feature = "keyring-backend")]
+    Keyring,
+}
+
+impl AuthKeyStore {
+    /// Stores `secret` for `onion`, overwriting any key already stored
+    /// under that address. Rejects anything that isn't exactly
+    /// [`SECRET_LEN`] bytes up front, rather than writing a key that
+    /// could never work and failing mysteriously the first time it's used.
+    pub fn store(&self, onion: &str, secret: &SensitiveBytes) -> Result<()> {
+        if secret.as_bytes().len() != SECRET_LEN {
+            return Err(OrcError::InvalidArgument(format!(
+                "a client-auth secret key must be {SECRET_LEN} bytes, got {}",
+                secret.as_bytes().len()
+            )));
+        }
+        match self {
+            AuthKeyStore::File(dir) => {
+                std::fs::create_dir_all(dir)?;
+                std::fs::write(key_path(dir, onion), output::hex_string(secret.as_bytes()))?;
+                Ok(())
+            }
+            #[cfg(feature = "keyring-backend")]
+            AuthKeyStore::Keyring => {
+                keyring_entry(KEYRING_SERVICE, onion)?
+                    .set_secret(secret.as_bytes())
+                    .map_err(keyring_error)?;
+                let mut onions = keyring_index()?;
+                if !onions.iter().any(|existing| existing == onion) {
+                    onions.push(onion.to_string());
+                    save_keyring_index(&onions)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Loads the key stored for `onion`, failing with a clear message
+    /// rather than a bare "not found" if nothing's been stored yet.
+    pub fn load(&self, onion: &str) -> Result<SensitiveBytes> {
+        match self {
+            AuthKeyStore::File(dir) => match std::fs::read_to_string(key_path(dir, onion)) {
+                Ok(text) => Ok(SensitiveBytes::new(output::decode_hex(text.trim())?)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(no_such_key(onion)),
+                Err(err) => Err(err.into()),
+            },
+            #[cfg(feature = "keyring-backend")]
+            AuthKeyStore::Keyring => match keyring_entry(KEYRING_SERVICE, onion)?.get_secret() {
+                Ok(secret) => Ok(SensitiveBytes::new(secret)),
+                Err(keyring::Error::NoEntry) => Err(no_such_key(onion)),
+                Err(err) => Err(keyring_error(err)),
+            },
+        }
+    }
+
+    /// Removes the key stored for `onion`, if any.
+    pub fn remove(&self, onion: &str) -> Result<()> {
+        match self {
+            AuthKeyStore::File(dir) => match std::fs::remove_file(key_path(dir, onion)) {
+                Ok(()) => Ok(()),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(no_such_key(onion)),
+                Err(err) => Err(err.into()),
+            },
+            #[cfg(feature = "keyring-backend")]
+            AuthKeyStore::Keyring => {
+                match keyring_entry(KEYRING_SERVICE, onion)?.delete_credential() {
+                    Ok(()) => {}
+                    Err(keyring::Error::NoEntry) => return Err(no_such_key(onion)),
+                    Err(err) => return Err(keyring_error(err)),
+                }
+                let onions: Vec<String> = keyring_index()?.into_iter().filter(|existing| existing != onion).collect();
+                save_keyring_index(&onions)
+            }
+        }
+    }
+
+    /// Lists onion addresses with a stored key, sorted for stable output.
+    pub fn list(&self) -> Result<Vec<String>> {
+        match self {
+            AuthKeyStore::File(dir) => {
+                let entries = match std::fs::read_dir(dir) {
+                    Ok(entries) => entries,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                    Err(err) => return Err(err.into()),
+                };
+                let mut onions: Vec<String> = entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.strip_suffix(".key")).map(str::to_string))
+                    .collect();
+                onions.sort();
+                Ok(onions)
+            }
+            #[cfg(feature = "keyring-backend")]
+            AuthKeyStore::Keyring => {
+                let mut onions = keyring_index()?;
+                onions.sort();
+                Ok(onions)
+            }
+        }
+    }
+}
+
+fn key_path(dir: &std::path::Path, onion: &str) -> PathBuf {
+    dir.join(format!("{onion}.key"))
+}
+
+fn no_such_key(onion: &str) -> OrcError {
+    OrcError::InvalidArgument(format!("no client-auth key stored for `{onion}`"))
+}
+
+/// Default location for file-backed client-auth keys:
+/// `$HOME/.config/orc/client_auth/`.
+pub fn default_auth_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/orc/client_auth")
+}
+
+#[cfg(feature = "keyring-backend")]
+fn keyring_entry(service: &str, account: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(service, account).map_err(keyring_error)
+}
+
+#[cfg(feature = "keyring-backend")]
+fn keyring_error(err: keyring::Error) -> OrcError {
+    OrcError::Keyring(err.to_string())
+}
+
+/// The keyring has no portable "list accounts for this service" call, so
+/// the set of onion addresses with a stored key is tracked by hand in a
+/// second entry alongside the keys themselves.
+#[cfg(feature = "keyring-backend")]
+fn keyring_index() -> Result<Vec<String>> {
+    match keyring_entry(KEYRING_INDEX_SERVICE, KEYRING_INDEX_ACCOUNT)?.get_password() {
+        Ok(text) if text.is_empty() => Ok(Vec::new()),
+        Ok(text) => Ok(text.lines().map(str::to_string).collect()),
+        Err(keyring::Error::NoEntry) => Ok(Vec::new()),
+        Err(err) => Err(keyring_error(err)),
+    }
+}
+
+#[cfg(feature = "keyring-backend")]
+fn save_keyring_index(onions: &[String]) -> Result<()> {
+    keyring_entry(KEYRING_INDEX_SERVICE, KEYRING_INDEX_ACCOUNT)?
+        .set_password(&onions.join("\n"))
+        .map_err(keyring_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("orc-client-auth-test-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn stores_and_loads_a_key_by_onion_address() {
+        let dir = temp_dir("roundtrip");
+        let store = AuthKeyStore::File(dir.clone());
+        let secret = SensitiveBytes::new(vec![7u8; SECRET_LEN]);
+
+        store.store("example.onion", &secret).unwrap();
+        let loaded = store.load("example.onion").unwrap();
+        assert_eq!(loaded.as_bytes(), secret.as_bytes());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_a_key_of_the_wrong_length() {
+        let dir = temp_dir("bad-length");
+        let store = AuthKeyStore::File(dir.clone());
+        let secret = SensitiveBytes::new(vec![1u8; 4]);
+        assert!(store.store("example.onion", &secret).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn loading_a_missing_key_is_a_clear_error() {
+        let dir = temp_dir("missing");
+        let store = AuthKeyStore::File(dir.clone());
+        let err = store.load("example.onion").unwrap_err();
+        assert!(err.to_string().contains("no client-auth key"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn lists_and_removes_stored_keys() {
+        let dir = temp_dir("list-remove");
+        let store = AuthKeyStore::File(dir.clone());
+        store.store("a.onion", &SensitiveBytes::new(vec![1u8; SECRET_LEN])).unwrap();
+        store.store("b.onion", &SensitiveBytes::new(vec![2u8; SECRET_LEN])).unwrap();
+
+        assert_eq!(store.list().unwrap(), vec!["a.onion".to_string(), "b.onion".to_string()]);
+
+        store.remove("a.onion").unwrap();
+        assert_eq!(store.list().unwrap(), vec!["b.onion".to_string()]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn generated_client_auth_keypairs_have_a_full_length_secret_and_public_key() {
+        let keypair = ClientAuthKeypair::generate().unwrap();
+        assert_eq!(keypair.secret.as_bytes().len(), SECRET_LEN);
+        assert_eq!(keypair.public.len(), SECRET_LEN);
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn the_client_auth_line_carries_the_onion_address_and_encoded_secret() {
+        let keypair = ClientAuthKeypair::generate().unwrap();
+        let line = keypair.client_auth_line("abc123.onion");
+        assert!(line.starts_with("abc123:descriptor:x25519:"));
+        assert!(!line.ends_with(&keypair.public_base32()));
+    }
+
+    #[cfg(feature = "serve")]
+    #[test]
+    fn two_generated_keypairs_have_different_public_keys() {
+        let a = ClientAuthKeypair::generate().unwrap();
+        let b = ClientAuthKeypair::generate().unwrap();
+        assert_ne!(a.public_base32(), b.public_base32());
+    }
+}
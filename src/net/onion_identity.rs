@@ -0,0 +1,392 @@
+//! Persistent ed25519 identity keys for hosting a Tor v3 onion service —
+//! the server-side counterpart to [`crate::net::onion_auth`]'s client-auth
+//! keys. This module only covers generating, storing, and exporting the
+//! key itself in the form Tor's control port expects it in; registering
+//! a service with `ADD_ONION` is done by `orc serve` through
+//! [`crate::net::torctl`].
+//!
+//! An identity key is a 32-byte seed. Everything else — the ed25519
+//! public key half of the v3 onion address, and the 64-byte "expanded"
+//! private key (`ED25519-V3:<base64>`, the form `ADD_ONION` and the
+//! `hs_ed25519_secret_key` file both use) — is derived from it. The
+//! curve point arithmetic needed to turn a seed into a public key comes
+//! from `ed25519-dalek`, a dependency this crate only just started
+//! pulling in for exactly this — everywhere else this crate hand-rolls
+//! its own hashing and parsing, but elliptic-curve math is not something
+//! to hand-roll. The RFC 8032 §5.1.5 expansion itself (seed ->
+//! `SHA-512(seed)` -> clamp -> scalar || prefix) is ordinary hashing, so
+//! it's done by hand below with [`sha2::Sha512`] rather than reaching for
+//! `ed25519-dalek`'s own (feature-gated, `hazmat`-only) equivalent.
+//!
+//! Generating a seed was also the first place in this crate that needed
+//! real randomness rather than the cheap derive-from-time-and-pid trick
+//! [`crate::session_store`] and the other passphrase-encrypted stores
+//! used for a salt — a key seed that an attacker could predict from the
+//! moment the key was generated is not a key at all, more than a salt
+//! merely avoiding repeats. `getrandom` is this crate's one dependency
+//! on actual OS entropy, but no longer the only place that uses it: now
+//! that it's pulled in by the default feature set, every salt in this
+//! crate draws from it too (falling back to the old trick only in a
+//! `--no-default-features` build without `getrandom`).
+//!
+//! Keys are stored encrypted at rest under a passphrase, one file per
+//! name, using the same SHA-256-keystream-plus-HMAC construction
+//! [`crate::session_store`] already uses for `orc browse`'s saved tabs —
+//! unlike [`crate::net::onion_auth::AuthKeyStore`]'s plain hex files,
+//! since a service's identity key losing it lets someone else impersonate
+//! the service, not just read one client's traffic.
+
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::SigningKey;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::net::onion;
+use crate::output;
+use crate::secret::{SensitiveBytes, SensitiveString};
+
+/// Length of an identity key seed — the same as an ed25519 secret key.
+pub const SEED_LEN: usize = 32;
+
+const SALT_LEN: usize = 16;
+const HMAC_BLOCK_SIZE: usize = 64;
+const TAG_LEN: usize = 32;
+
+/// An onion service identity key: just the seed everything else is
+/// derived from.
+#[derive(Debug)]
+pub struct IdentityKey {
+    seed: SensitiveBytes,
+}
+
+impl IdentityKey {
+    /// Generates a fresh key from [`getrandom`], the OS's own CSPRNG —
+    /// see this module's doc comment for why that matters here and
+    /// nowhere else in this crate.
+    pub fn generate() -> Result<IdentityKey> {
+        let mut seed = [0u8; SEED_LEN];
+        getrandom::getrandom(&mut seed).map_err(|err| OrcError::InvalidArgument(format!("could not read random bytes: {err}")))?;
+        Ok(IdentityKey { seed: SensitiveBytes::new(seed.to_vec()) })
+    }
+
+    /// Wraps an already-known seed, e.g. one read back from
+    /// [`IdentityKeyStore::load`] or imported from another tool. Rejects
+    /// anything that isn't exactly [`SEED_LEN`] bytes, the same way
+    /// [`crate::net::onion_auth::AuthKeyStore::store`] rejects a
+    /// wrong-length client-auth secret.
+    pub fn from_seed(seed: SensitiveBytes) -> Result<IdentityKey> {
+        if seed.as_bytes().len() != SEED_LEN {
+            return Err(OrcError::InvalidArgument(format!("an identity key seed must be {SEED_LEN} bytes, got {}", seed.as_bytes().len())));
+        }
+        Ok(IdentityKey { seed })
+    }
+
+    pub fn seed(&self) -> &SensitiveBytes {
+        &self.seed
+    }
+
+    fn signing_key(&self) -> SigningKey {
+        let mut bytes = [0u8; SEED_LEN];
+        bytes.copy_from_slice(self.seed.as_bytes());
+        SigningKey::from_bytes(&bytes)
+    }
+
+    /// The v3 `.onion` address this key serves, reusing
+    /// [`onion::encode_v3_address`] rather than duplicating the
+    /// base32/checksum logic [`onion::validate_onion_host`] already has.
+    pub fn onion_address(&self) -> String {
+        onion::encode_v3_address(&self.signing_key().verifying_key().to_bytes())
+    }
+
+    /// The 64-byte "expanded" private key — scalar half || prefix half,
+    /// per RFC 8032 §5.1.5 — base64-encoded the way `ADD_ONION
+    /// ED25519-V3:<base64>` and the `hs_ed25519_secret_key` file (past
+    /// its 96-byte header) both expect it.
+    pub fn add_onion_key_blob(&self) -> String {
+        let expanded = expanded_private_key(self.seed.as_bytes());
+        format!("ED25519-V3:{}", base64::engine::general_purpose::STANDARD.encode(expanded))
+    }
+}
+
+/// `SHA-512(seed)`, clamped per RFC 8032 §5.1.5: the low 32 bytes become
+/// the curve scalar (low 3 bits of byte 0 cleared, high bit of byte 31
+/// cleared, second-highest bit of byte 31 set), the high 32 bytes are
+/// used as-is as the "prefix" mixed into every signature this key makes.
+/// Concatenating the two gives the 64-byte expanded private key Tor's
+/// control port and on-disk key file both use in place of the raw seed.
+fn expanded_private_key(seed: &[u8]) -> [u8; 64] {
+    let digest = Sha512::digest(seed);
+    let mut expanded = [0u8; 64];
+    expanded.copy_from_slice(&digest);
+    expanded[0] &= 0b1111_1000;
+    expanded[31] &= 0b0111_1111;
+    expanded[31] |= 0b0100_0000;
+    expanded
+}
+
+use base64::Engine;
+
+/// Where identity keys are kept: one encrypted file per name under
+/// [`default_identity_dir`], the same "a file per entry" shape
+/// [`crate::net::onion_auth::AuthKeyStore::File`] uses — except each file
+/// here is encrypted under a passphrase rather than plain hex, per this
+/// module's doc comment.
+pub struct IdentityKeyStore {
+    dir: PathBuf,
+}
+
+impl IdentityKeyStore {
+    pub fn new(dir: PathBuf) -> IdentityKeyStore {
+        IdentityKeyStore { dir }
+    }
+
+    /// Encrypts `key`'s seed under `passphrase` and writes it to
+    /// `<name>.identity` in this store's directory, overwriting any key
+    /// already stored under that name.
+    pub fn store(&self, name: &str, key: &IdentityKey, passphrase: &SensitiveString) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let salt = fresh_salt();
+        let derived = derive_key(passphrase.as_str(), &salt);
+        let keystream = keystream(&derived, SEED_LEN);
+        let mut ciphertext: Vec<u8> = key.seed.as_bytes().iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+        let tag = hmac_sha256(&derived, &ciphertext);
+        ciphertext.extend_from_slice(&tag);
+
+        let fields = vec![
+            ("salt".to_string(), Value::String(output::hex_string(&salt))),
+            ("ciphertext".to_string(), Value::String(output::hex_string(&ciphertext))),
+        ];
+        std::fs::write(key_path(&self.dir, name), Value::Object(fields).to_string())?;
+        Ok(())
+    }
+
+    /// Reads and decrypts the key stored under `name`. Fails (rather than
+    /// returning garbage) on a wrong passphrase or a corrupted file, since
+    /// the authentication tag is checked before anything is decoded.
+    pub fn load(&self, name: &str, passphrase: &SensitiveString) -> Result<IdentityKey> {
+        let text = match std::fs::read_to_string(key_path(&self.dir, name)) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(no_such_key(name)),
+            Err(err) => return Err(err.into()),
+        };
+        let file = json::parse(&text)?;
+        let salt_hex = file.get("salt").and_then(Value::as_str).ok_or_else(|| OrcError::InvalidArgument("identity key file has no salt".into()))?;
+        let ciphertext_hex = file
+            .get("ciphertext")
+            .and_then(Value::as_str)
+            .ok_or_else(|| OrcError::InvalidArgument("identity key file has no ciphertext".into()))?;
+        let salt = output::decode_hex(salt_hex)?;
+        let mut ciphertext = output::decode_hex(ciphertext_hex)?;
+        if ciphertext.len() < TAG_LEN {
+            return Err(OrcError::InvalidArgument("identity key file is truncated".into()));
+        }
+        let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+
+        let derived = derive_key(passphrase.as_str(), &salt);
+        if !crate::constant_time::eq(&hmac_sha256(&derived, &ciphertext), &tag) {
+            return Err(OrcError::InvalidArgument("wrong passphrase or corrupted identity key file".into()));
+        }
+
+        let keystream = keystream(&derived, ciphertext.len());
+        let seed: Vec<u8> = ciphertext.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+        IdentityKey::from_seed(SensitiveBytes::new(seed))
+    }
+
+    /// Removes the key stored under `name`, if any.
+    pub fn remove(&self, name: &str) -> Result<()> {
+        match std::fs::remove_file(key_path(&self.dir, name)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(no_such_key(name)),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Lists stored key names, sorted for stable output.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.strip_suffix(".identity")).map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+}
+
+fn key_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.identity"))
+}
+
+fn no_such_key(name: &str) -> OrcError {
+    OrcError::InvalidArgument(format!("no identity key stored under `{name}`"))
+}
+
+/// Default location for identity keys: `$HOME/.config/orc/onion_identity/`.
+pub fn default_identity_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/orc/onion_identity")
+}
+
+/// A per-save salt from the OS's CSPRNG via [`getrandom`] — the same as
+/// [`crate::session_store`]'s `fresh_salt` in a `serve` build, which is
+/// the only build this module is compiled into.
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).expect("the OS's CSPRNG should not fail");
+    salt
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-onion-identity-v1");
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    hasher.finalize().into()
+}
+
+/// See [`crate::session_store`]'s identically-shaped `keystream`.
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// See [`crate::session_store`]'s identically-shaped `hmac_sha256`.
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; HMAC_BLOCK_SIZE];
+    key_block[..key.len()].copy_from_slice(key);
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(message);
+    let inner_digest = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_digest);
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("orc-onion-identity-test-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn generated_keys_have_a_full_length_seed() {
+        let key = IdentityKey::generate().unwrap();
+        assert_eq!(key.seed().as_bytes().len(), SEED_LEN);
+    }
+
+    #[test]
+    fn from_seed_rejects_the_wrong_length() {
+        assert!(IdentityKey::from_seed(SensitiveBytes::new(vec![1u8; 4])).is_err());
+    }
+
+    #[test]
+    fn the_same_seed_always_derives_the_same_onion_address() {
+        let key_a = IdentityKey::from_seed(SensitiveBytes::new(vec![7u8; SEED_LEN])).unwrap();
+        let key_b = IdentityKey::from_seed(SensitiveBytes::new(vec![7u8; SEED_LEN])).unwrap();
+        assert_eq!(key_a.onion_address(), key_b.onion_address());
+        assert!(key_a.onion_address().ends_with(".onion"));
+    }
+
+    #[test]
+    fn a_different_seed_derives_a_different_onion_address() {
+        let key_a = IdentityKey::from_seed(SensitiveBytes::new(vec![7u8; SEED_LEN])).unwrap();
+        let key_b = IdentityKey::from_seed(SensitiveBytes::new(vec![8u8; SEED_LEN])).unwrap();
+        assert_ne!(key_a.onion_address(), key_b.onion_address());
+    }
+
+    #[test]
+    fn the_expanded_private_key_is_clamped_per_rfc_8032() {
+        let expanded = expanded_private_key(&[7u8; SEED_LEN]);
+        assert_eq!(expanded[0] & 0b0000_0111, 0);
+        assert_eq!(expanded[31] & 0b1000_0000, 0);
+        assert_eq!(expanded[31] & 0b0100_0000, 0b0100_0000);
+    }
+
+    #[test]
+    fn add_onion_key_blob_is_tagged_and_base64() {
+        let key = IdentityKey::from_seed(SensitiveBytes::new(vec![7u8; SEED_LEN])).unwrap();
+        let blob = key.add_onion_key_blob();
+        assert!(blob.starts_with("ED25519-V3:"));
+    }
+
+    #[test]
+    fn round_trips_a_key_through_store_and_load() {
+        let dir = temp_dir("roundtrip");
+        let store = IdentityKeyStore::new(dir.clone());
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let key = IdentityKey::from_seed(SensitiveBytes::new(vec![3u8; SEED_LEN])).unwrap();
+
+        store.store("blog", &key, &passphrase).unwrap();
+        let loaded = store.load("blog", &passphrase).unwrap();
+
+        assert_eq!(loaded.onion_address(), key.onion_address());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn load_rejects_the_wrong_passphrase() {
+        let dir = temp_dir("wrong-passphrase");
+        let store = IdentityKeyStore::new(dir.clone());
+        let key = IdentityKey::from_seed(SensitiveBytes::new(vec![3u8; SEED_LEN])).unwrap();
+        store.store("blog", &key, &SensitiveString::new("right".to_string())).unwrap();
+
+        let result = store.load("blog", &SensitiveString::new("wrong".to_string()));
+        assert!(result.is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn lists_and_removes_stored_keys() {
+        let dir = temp_dir("list-remove");
+        let store = IdentityKeyStore::new(dir.clone());
+        let passphrase = SensitiveString::new("pw".to_string());
+        store.store("a", &IdentityKey::from_seed(SensitiveBytes::new(vec![1u8; SEED_LEN])).unwrap(), &passphrase).unwrap();
+        store.store("b", &IdentityKey::from_seed(SensitiveBytes::new(vec![2u8; SEED_LEN])).unwrap(), &passphrase).unwrap();
+
+        assert_eq!(store.list().unwrap(), vec!["a".to_string(), "b".to_string()]);
+
+        store.remove("a").unwrap();
+        assert_eq!(store.list().unwrap(), vec!["b".to_string()]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn loading_a_missing_key_is_a_clear_error() {
+        let dir = temp_dir("missing");
+        let store = IdentityKeyStore::new(dir.clone());
+        let err = store.load("nope", &SensitiveString::new("pw".to_string())).unwrap_err();
+        assert!(err.to_string().contains("no identity key"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,77 @@
+//! A client for OnionShare's HTTP protocol: HTTP Basic auth with username
+//! `onionshare` and the share's password, a GET of `/download` to fetch a
+//! sender's archive, and a multipart POST to `/upload` to give a receiver
+//! a file. No directory listings, no upload progress, no auto-stop.
+
+use std::net::SocketAddr;
+
+use base64::Engine;
+
+use crate::error::{OrcError, Result};
+use crate::net::http::{self, HttpRequest};
+use crate::net::tcp::ConnectOptions;
+use crate::secret::SensitiveString;
+
+const UPLOAD_BOUNDARY: &str = "orc-onionshare-boundary";
+
+fn auth_header(password: &SensitiveString) -> (String, String) {
+    let credentials = base64::engine::general_purpose::STANDARD.encode(format!("onionshare:{}", password.as_str()));
+    ("Authorization".to_string(), format!("Basic {credentials}"))
+}
+
+/// Downloads the shared archive from an OnionShare send-mode server.
+pub fn download(proxy: SocketAddr, url: &str, password: &SensitiveString, options: &ConnectOptions) -> Result<Vec<u8>> {
+    let download_url = format!("{}/download", url.trim_end_matches('/'));
+    let headers = [auth_header(password)];
+    let response = http::send(HttpRequest { proxy, method: "GET", url: &download_url, headers: &headers, body: &[], options })?;
+    if response.status != 200 {
+        return Err(OrcError::Socks(format!("download failed with HTTP status {}", response.status)));
+    }
+    Ok(response.body)
+}
+
+/// Uploads `file_bytes` (named `file_name`) to an OnionShare receive-mode
+/// server.
+pub fn upload(
+    proxy: SocketAddr,
+    url: &str,
+    file_name: &str,
+    file_bytes: &[u8],
+    password: &SensitiveString,
+    options: &ConnectOptions,
+) -> Result<()> {
+    let upload_url = format!("{}/upload", url.trim_end_matches('/'));
+    let body = build_multipart_body(file_name, file_bytes);
+
+    let headers = [auth_header(password), ("Content-Type".to_string(), format!("multipart/form-data; boundary={UPLOAD_BOUNDARY}"))];
+
+    let response = http::send(HttpRequest { proxy, method: "POST", url: &upload_url, headers: &headers, body: &body, options })?;
+    if response.status != 200 {
+        return Err(OrcError::Socks(format!("upload failed with HTTP status {}", response.status)));
+    }
+    Ok(())
+}
+
+fn build_multipart_body(file_name: &str, file_bytes: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(file_bytes.len() + 256);
+    body.extend_from_slice(format!("--{UPLOAD_BOUNDARY}\r\n").as_bytes());
+    body.extend_from_slice(format!("Content-Disposition: form-data; name=\"file[]\"; filename=\"{file_name}\"\r\n").as_bytes());
+    body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+    body.extend_from_slice(file_bytes);
+    body.extend_from_slice(format!("\r\n--{UPLOAD_BOUNDARY}--\r\n").as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_body_contains_the_filename_and_content() {
+        let body = build_multipart_body("notes.txt", b"hello");
+        let text = String::from_utf8_lossy(&body);
+        assert!(text.contains("filename=\"notes.txt\""));
+        assert!(text.contains("hello"));
+        assert!(text.ends_with(&format!("--{UPLOAD_BOUNDARY}--\r\n")));
+    }
+}
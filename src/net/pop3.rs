@@ -0,0 +1,110 @@
+//! A minimal POP3 client: implicit TLS, USER/PASS, STAT, and RETR.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls;
+use crate::secret::SensitiveString;
+
+pub struct FetchRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub username: &'a SensitiveString,
+    pub password: &'a SensitiveString,
+    pub pin_file: &'a Path,
+    pub options: &'a ConnectOptions,
+}
+
+/// Logs into a POP3 server and retrieves every message in the mailbox,
+/// in order, as raw RFC 822 bytes.
+pub fn fetch_all(request: FetchRequest<'_>) -> Result<Vec<Vec<u8>>> {
+    let socks_stream = create_socks_stream(request.proxy, request.host, request.port, request.options)?;
+    let tls_stream = tls::connect_tofu(socks_stream, request.host, request.pin_file)?;
+    let mut reader = BufReader::new(tls_stream);
+
+    read_status(&mut reader)?;
+    send_line(reader.get_mut(), &format!("USER {}", request.username.as_str()))?;
+    read_status(&mut reader)?;
+    send_line(reader.get_mut(), &format!("PASS {}", request.password.as_str()))?;
+    read_status(&mut reader)?;
+
+    send_line(reader.get_mut(), "STAT")?;
+    let stat = read_status(&mut reader)?;
+    let count: usize = stat
+        .trim_start_matches("+OK ")
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+
+    let mut messages = Vec::with_capacity(count);
+    for i in 1..=count {
+        send_line(reader.get_mut(), &format!("RETR {i}"))?;
+        read_status(&mut reader)?;
+        messages.push(read_dot_terminated(&mut reader)?);
+    }
+
+    send_line(reader.get_mut(), "QUIT")?;
+    Ok(messages)
+}
+
+fn send_line<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\r\n")?;
+    Ok(())
+}
+
+fn read_status<R: std::io::Read>(reader: &mut BufReader<R>) -> Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.starts_with("+OK") {
+        Ok(line)
+    } else {
+        Err(OrcError::Socks(format!(
+            "POP3 command failed: {}",
+            line.trim_end()
+        )))
+    }
+}
+
+fn read_dot_terminated<R: std::io::Read>(reader: &mut BufReader<R>) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        let n = reader.read_until(b'\n', &mut line)?;
+        if n == 0 || line == b".\r\n" || line == b".\n" {
+            break;
+        }
+        body.extend_from_slice(&line);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_status_line() {
+        let mut reader = BufReader::new(Cursor::new(b"+OK hi\r\n".to_vec()));
+        assert!(read_status(&mut reader).is_ok());
+    }
+
+    #[test]
+    fn rejects_error_status() {
+        let mut reader = BufReader::new(Cursor::new(b"-ERR no\r\n".to_vec()));
+        assert!(read_status(&mut reader).is_err());
+    }
+
+    #[test]
+    fn reads_dot_terminated_body() {
+        let mut reader = BufReader::new(Cursor::new(b"line one\r\nline two\r\n.\r\n".to_vec()));
+        let body = read_dot_terminated(&mut reader).unwrap();
+        assert_eq!(body, b"line one\r\nline two\r\n");
+    }
+}
@@ -0,0 +1,167 @@
+//! Tor's SOCKS5 RESOLVE/RESOLVE_PTR extension (commands 0xF0 and 0xF1),
+//! which asks the exit relay to do a DNS lookup instead of the client's
+//! own OS resolver. This is deliberately separate from [`crate::net::tcp`]:
+//! resolution never opens a connection to anything but the Tor SOCKS port,
+//! and the two command bytes aren't part of RFC 1928 proper.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::ConnectOptions;
+
+const SOCKS_VERSION: u8 = 0x05;
+const CMD_RESOLVE: u8 = 0xF0;
+const CMD_RESOLVE_PTR: u8 = 0xF1;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// Asks the proxy to resolve `hostname` to an IP address.
+pub fn resolve(proxy_addr: SocketAddr, hostname: &str, options: &ConnectOptions) -> Result<IpAddr> {
+    if hostname.len() > 255 {
+        return Err(OrcError::InvalidArgument("hostname is too long for SOCKS5".into()));
+    }
+
+    let mut stream = handshake(proxy_addr, options)?;
+
+    let mut request = Vec::with_capacity(7 + hostname.len());
+    request.push(SOCKS_VERSION);
+    request.push(CMD_RESOLVE);
+    request.push(0x00); // reserved
+    request.push(ATYP_DOMAIN);
+    request.push(hostname.len() as u8);
+    request.extend_from_slice(hostname.as_bytes());
+    request.extend_from_slice(&0u16.to_be_bytes());
+    stream.write_all(&request)?;
+
+    match read_reply(&mut stream)? {
+        Answer::Address(addr) => Ok(addr),
+        Answer::Name(name) => Err(OrcError::Socks(format!(
+            "expected an address in the RESOLVE reply, got name {name}"
+        ))),
+    }
+}
+
+/// Asks the proxy to resolve `addr` back to a hostname.
+pub fn resolve_ptr(proxy_addr: SocketAddr, addr: IpAddr, options: &ConnectOptions) -> Result<String> {
+    let mut stream = handshake(proxy_addr, options)?;
+
+    let mut request = Vec::with_capacity(22);
+    request.push(SOCKS_VERSION);
+    request.push(CMD_RESOLVE_PTR);
+    request.push(0x00); // reserved
+    match addr {
+        IpAddr::V4(v4) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&v4.octets());
+        }
+        IpAddr::V6(v6) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&v6.octets());
+        }
+    }
+    request.extend_from_slice(&0u16.to_be_bytes());
+    stream.write_all(&request)?;
+
+    match read_reply(&mut stream)? {
+        Answer::Name(name) => Ok(name),
+        Answer::Address(addr) => Err(OrcError::Socks(format!(
+            "expected a name in the RESOLVE_PTR reply, got address {addr}"
+        ))),
+    }
+}
+
+/// Connects to the proxy and performs the version/method greeting shared
+/// by every SOCKS5 request, resolve included.
+///
+/// Checked against [`crate::security::check_proxy_addr`] first, the same
+/// as [`crate::net::tcp::create_socks_stream`] — otherwise
+/// `--allow-remote-socks` enforcement would be bypassed for every
+/// command that resolves through here instead of dialing a stream.
+fn handshake(proxy_addr: SocketAddr, options: &ConnectOptions) -> Result<TcpStream> {
+    crate::security::check_proxy_addr(&proxy_addr)?;
+    let mut stream = TcpStream::connect_timeout(&proxy_addr, options.connect_timeout)?;
+    stream.write_all(&[SOCKS_VERSION, 0x01, 0x00])?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(OrcError::Socks(format!(
+            "unexpected SOCKS version {} in method selection",
+            reply[0]
+        )));
+    }
+    if reply[1] != 0x00 {
+        return Err(OrcError::Socks(
+            "proxy did not accept the \"no authentication\" method".into(),
+        ));
+    }
+    Ok(stream)
+}
+
+enum Answer {
+    Address(IpAddr),
+    Name(String),
+}
+
+fn read_reply(stream: &mut TcpStream) -> Result<Answer> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    if header[0] != SOCKS_VERSION {
+        return Err(OrcError::Socks(format!(
+            "unexpected SOCKS version {} in resolve reply",
+            header[0]
+        )));
+    }
+    if header[1] != 0x00 {
+        return Err(OrcError::Socks(format!("proxy could not resolve the name (code {})", header[1])));
+    }
+
+    match header[3] {
+        ATYP_IPV4 => {
+            let mut addr = [0u8; 4];
+            stream.read_exact(&mut addr)?;
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port)?;
+            Ok(Answer::Address(IpAddr::V4(Ipv4Addr::from(addr))))
+        }
+        ATYP_IPV6 => {
+            let mut addr = [0u8; 16];
+            stream.read_exact(&mut addr)?;
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port)?;
+            Ok(Answer::Address(IpAddr::V6(Ipv6Addr::from(addr))))
+        }
+        ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            let mut name = vec![0u8; len[0] as usize];
+            stream.read_exact(&mut name)?;
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port)?;
+            Ok(Answer::Name(String::from_utf8_lossy(&name).into_owned()))
+        }
+        other => Err(OrcError::Socks(format!("unsupported address type {other} in resolve reply"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_oversized_hostname() {
+        let options = ConnectOptions::default();
+        let hostname = "a".repeat(300);
+        let err = resolve("127.0.0.1:1".parse().unwrap(), &hostname, &options).unwrap_err();
+        assert!(err.to_string().contains("too long"));
+    }
+
+    #[test]
+    fn refuses_a_remote_proxy_address_before_connecting() {
+        let options = ConnectOptions::default();
+        let err = resolve("203.0.113.5:9050".parse().unwrap(), "example.onion", &options).unwrap_err();
+        assert!(err.to_string().contains("refusing to send unencrypted SOCKS traffic"));
+    }
+}
@@ -0,0 +1,101 @@
+//! A minimal JSON-RPC-over-HTTP client for onion-hosted nodes (Bitcoin,
+//! Monero, and Electrum-style daemons commonly exposed this way).
+
+use std::net::SocketAddr;
+
+use base64::Engine;
+
+use crate::error::{OrcError, Result};
+use crate::net::http::{self, HttpRequest};
+use crate::net::json::{self, Value};
+use crate::net::tcp::ConnectOptions;
+use crate::secret::SensitiveString;
+
+/// One call to include in the request; more than one is sent as a batch.
+pub struct Call<'a> {
+    pub method: &'a str,
+    pub params: Value,
+}
+
+pub struct RpcRequest<'a> {
+    pub proxy: SocketAddr,
+    pub url: &'a str,
+    pub calls: &'a [Call<'a>],
+    pub username: Option<&'a str>,
+    pub password: Option<&'a SensitiveString>,
+    pub options: &'a ConnectOptions,
+}
+
+/// Sends `request.calls` as a single HTTP POST (a JSON object for one
+/// call, a JSON array for more than one) and returns the parsed response
+/// body, unexamined — callers are responsible for checking for a
+/// top-level `"error"` field.
+pub fn call(request: RpcRequest<'_>) -> Result<Value> {
+    let body = build_body(request.calls);
+    let body_bytes = body.to_string().into_bytes();
+
+    let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+    if let (Some(username), Some(password)) = (request.username, request.password) {
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{username}:{}", password.as_str()));
+        headers.push(("Authorization".to_string(), format!("Basic {credentials}")));
+    }
+
+    let response = http::send(HttpRequest {
+        proxy: request.proxy,
+        method: "POST",
+        url: request.url,
+        headers: &headers,
+        body: &body_bytes,
+        options: request.options,
+    })?;
+
+    if response.status >= 400 {
+        return Err(OrcError::Socks(format!("RPC call failed with HTTP status {}", response.status)));
+    }
+
+    let body_text =
+        std::str::from_utf8(&response.body).map_err(|_| OrcError::Socks("RPC response body is not valid UTF-8".into()))?;
+    json::parse(body_text)
+}
+
+fn build_body(calls: &[Call<'_>]) -> Value {
+    let requests: Vec<Value> = calls
+        .iter()
+        .enumerate()
+        .map(|(i, call)| {
+            Value::Object(vec![
+                ("jsonrpc".to_string(), Value::String("2.0".to_string())),
+                ("id".to_string(), Value::Number((i + 1) as f64)),
+                ("method".to_string(), Value::String(call.method.to_string())),
+                ("params".to_string(), call.params.clone()),
+            ])
+        })
+        .collect();
+
+    match requests.len() {
+        1 => requests.into_iter().next().unwrap(),
+        _ => Value::Array(requests),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_single_call_as_an_object() {
+        let calls = [Call { method: "getblockcount", params: Value::Array(vec![]) }];
+        let body = build_body(&calls);
+        assert_eq!(body.get("method"), Some(&Value::String("getblockcount".to_string())));
+    }
+
+    #[test]
+    fn builds_multiple_calls_as_an_array() {
+        let calls = [
+            Call { method: "a", params: Value::Array(vec![]) },
+            Call { method: "b", params: Value::Array(vec![]) },
+        ];
+        let body = build_body(&calls);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+    }
+}
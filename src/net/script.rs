@@ -0,0 +1,206 @@
+//! A tiny line-oriented script format for exercising multi-step binary
+//! protocols through `stream --script`.
+//!
+//! Each non-blank, non-comment line is one step:
+//!
+//! ```text
+//! send text Hello
+//! send hex 48656c6c6f0d0a
+//! expect text World
+//! expect hex deadbeef
+//! timeout 5
+//! ```
+//!
+//! `send`/`expect` take an encoding (`text` or `hex`) and the bytes to use.
+//! `timeout` sets the read timeout, in seconds, applied to subsequent
+//! `expect` steps.
+
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::error::{OrcError, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Send(Vec<u8>),
+    Expect(Vec<u8>),
+    Timeout(Duration),
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Step>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_line)
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<Step> {
+    let mut parts = line.splitn(3, char::is_whitespace);
+    let verb = parts.next().unwrap_or_default();
+
+    match verb {
+        "send" | "expect" => {
+            let encoding = parts
+                .next()
+                .ok_or_else(|| OrcError::InvalidArgument(format!("missing encoding: `{line}`")))?;
+            let rest = parts
+                .next()
+                .ok_or_else(|| OrcError::InvalidArgument(format!("missing payload: `{line}`")))?;
+            let bytes = decode(encoding, rest)?;
+            if verb == "send" {
+                Ok(Step::Send(bytes))
+            } else {
+                Ok(Step::Expect(bytes))
+            }
+        }
+        "timeout" => {
+            let seconds: u64 = parts
+                .next()
+                .ok_or_else(|| OrcError::InvalidArgument(format!("missing seconds: `{line}`")))?
+                .parse()
+                .map_err(|_| OrcError::InvalidArgument(format!("bad timeout value: `{line}`")))?;
+            Ok(Step::Timeout(Duration::from_secs(seconds)))
+        }
+        other => Err(OrcError::InvalidArgument(format!(
+            "unknown script step `{other}` in line: `{line}`"
+        ))),
+    }
+}
+
+fn decode(encoding: &str, payload: &str) -> Result<Vec<u8>> {
+    match encoding {
+        "text" => Ok(payload.as_bytes().to_vec()),
+        "hex" => {
+            if !payload.len().is_multiple_of(2) {
+                return Err(OrcError::InvalidArgument(format!(
+                    "`{payload}` is not valid hex: odd length"
+                )));
+            }
+            (0..payload.len())
+                .step_by(2)
+                .map(|i| {
+                    u8::from_str_radix(&payload[i..i + 2], 16).map_err(|_| {
+                        OrcError::InvalidArgument(format!("`{payload}` is not valid hex"))
+                    })
+                })
+                .collect()
+        }
+        other => Err(OrcError::InvalidArgument(format!(
+            "unknown encoding `{other}`, expected `text` or `hex`"
+        ))),
+    }
+}
+
+/// Runs `steps` against `stream`, returning a human-readable transcript of
+/// what was sent and received. `on_timeout` is invoked for each `timeout`
+/// step so the caller can apply it to the underlying socket.
+pub fn run<S, F>(stream: &mut S, steps: &[Step], mut on_timeout: F) -> Result<String>
+where
+    S: Read + Write,
+    F: FnMut(Duration) -> Result<()>,
+{
+    let mut transcript = String::new();
+    for step in steps {
+        match step {
+            Step::Send(bytes) => {
+                stream.write_all(bytes)?;
+                transcript.push_str(&format!(">> sent {} bytes: {}\n", bytes.len(), crate::output::hex_string(bytes)));
+            }
+            Step::Expect(expected) => {
+                let mut actual = vec![0u8; expected.len()];
+                stream.read_exact(&mut actual)?;
+                if &actual == expected {
+                    transcript.push_str(&format!("<< matched {} bytes: {}\n", actual.len(), crate::output::hex_string(&actual)));
+                } else {
+                    transcript.push_str(&format!(
+                        "<< MISMATCH: expected {} got {}\n",
+                        crate::output::hex_string(expected),
+                        crate::output::hex_string(&actual)
+                    ));
+                    return Err(OrcError::InvalidArgument(format!(
+                        "expect step failed: expected {} got {}",
+                        crate::output::hex_string(expected),
+                        crate::output::hex_string(&actual)
+                    )));
+                }
+            }
+            Step::Timeout(duration) => {
+                on_timeout(*duration)?;
+                transcript.push_str(&format!("-- timeout set to {}s\n", duration.as_secs()));
+            }
+        }
+    }
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn parses_send_expect_and_timeout() {
+        let script = "send text Hello\nexpect hex deadbeef\ntimeout 5\n# a comment\n";
+        let steps = parse(script).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                Step::Send(b"Hello".to_vec()),
+                Step::Expect(vec![0xde, 0xad, 0xbe, 0xef]),
+                Step::Timeout(Duration::from_secs(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_verb() {
+        assert!(parse("frobnicate foo").is_err());
+    }
+
+    #[derive(Default)]
+    struct LoopStream {
+        read_from: Cursor<Vec<u8>>,
+        written: Vec<u8>,
+    }
+
+    impl Read for LoopStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.read_from.read(buf)
+        }
+    }
+
+    impl Write for LoopStream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn runs_matching_exchange() {
+        let mut stream = LoopStream {
+            read_from: Cursor::new(b"World".to_vec()),
+            written: Vec::new(),
+        };
+        let steps = parse("send text Hello\nexpect text World\n").unwrap();
+        let transcript = run(&mut stream, &steps, |_| Ok(())).unwrap();
+        assert_eq!(stream.written, b"Hello");
+        assert!(transcript.contains("sent 5 bytes"));
+        assert!(transcript.contains("matched 5 bytes"));
+    }
+
+    #[test]
+    fn fails_on_mismatch() {
+        let mut stream = LoopStream {
+            read_from: Cursor::new(b"Nope!".to_vec()),
+            written: Vec::new(),
+        };
+        let steps = parse("expect text World\n").unwrap();
+        assert!(run(&mut stream, &steps, |_| Ok(())).is_err());
+    }
+}
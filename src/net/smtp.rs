@@ -0,0 +1,141 @@
+//! A minimal SMTP submission client: EHLO, STARTTLS, AUTH LOGIN, and a
+//! single message send. Just enough to submit mail through an onion
+//! provider; no support for pipelining or alternative AUTH mechanisms.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use base64::Engine;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls;
+use crate::secret::SensitiveString;
+
+pub struct SendRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub helo_domain: &'a str,
+    pub username: Option<SensitiveString>,
+    pub password: Option<SensitiveString>,
+    pub from: &'a str,
+    pub to: &'a [String],
+    pub message: &'a [u8],
+    pub pin_file: &'a Path,
+    pub options: &'a ConnectOptions,
+}
+
+pub fn send(request: SendRequest<'_>) -> Result<()> {
+    let socks_stream = create_socks_stream(request.proxy, request.host, request.port, request.options)?;
+    let mut reader = BufReader::new(socks_stream);
+
+    read_reply(&mut reader, "220")?;
+    send_line(reader.get_mut(), &format!("EHLO {}", request.helo_domain))?;
+    read_multiline_reply(&mut reader, "250")?;
+
+    send_line(reader.get_mut(), "STARTTLS")?;
+    read_reply(&mut reader, "220")?;
+
+    let socks_stream = reader.into_inner();
+    let tls_stream = tls::connect_tofu(socks_stream, request.host, request.pin_file)?;
+    let mut reader = BufReader::new(tls_stream);
+
+    send_line(reader.get_mut(), &format!("EHLO {}", request.helo_domain))?;
+    read_multiline_reply(&mut reader, "250")?;
+
+    if let (Some(username), Some(password)) = (&request.username, &request.password) {
+        authenticate(&mut reader, username, password)?;
+    }
+
+    send_line(reader.get_mut(), &format!("MAIL FROM:<{}>", request.from))?;
+    read_reply(&mut reader, "250")?;
+
+    for recipient in request.to {
+        send_line(reader.get_mut(), &format!("RCPT TO:<{recipient}>"))?;
+        read_reply(&mut reader, "250")?;
+    }
+
+    send_line(reader.get_mut(), "DATA")?;
+    read_reply(&mut reader, "354")?;
+
+    reader.get_mut().write_all(request.message)?;
+    if !request.message.ends_with(b"\r\n") {
+        reader.get_mut().write_all(b"\r\n")?;
+    }
+    reader.get_mut().write_all(b".\r\n")?;
+    read_reply(&mut reader, "250")?;
+
+    send_line(reader.get_mut(), "QUIT")?;
+    Ok(())
+}
+
+fn authenticate<S: Read + Write>(
+    reader: &mut BufReader<S>,
+    username: &SensitiveString,
+    password: &SensitiveString,
+) -> Result<()> {
+    send_line(reader.get_mut(), "AUTH LOGIN")?;
+    read_reply(reader, "334")?;
+    let user_b64 = base64::engine::general_purpose::STANDARD.encode(username.as_str());
+    send_line(reader.get_mut(), &user_b64)?;
+    read_reply(reader, "334")?;
+    let pass_b64 = base64::engine::general_purpose::STANDARD.encode(password.as_str());
+    send_line(reader.get_mut(), &pass_b64)?;
+    read_reply(reader, "235")?;
+    Ok(())
+}
+
+fn send_line<W: Write>(stream: &mut W, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// Reads a single SMTP reply line and checks it starts with `expected_code`.
+fn read_reply<S: Read>(reader: &mut BufReader<S>, expected_code: &str) -> Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    check_code(&line, expected_code)?;
+    Ok(line)
+}
+
+/// Reads a possibly-multiline SMTP reply (continuation lines use `-` after
+/// the code) and checks the final line starts with `expected_code`.
+fn read_multiline_reply<S: Read>(reader: &mut BufReader<S>, expected_code: &str) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let is_last = line.as_bytes().get(3) != Some(&b'-');
+        if is_last {
+            return check_code(&line, expected_code);
+        }
+    }
+}
+
+fn check_code(line: &str, expected_code: &str) -> Result<()> {
+    if line.starts_with(expected_code) {
+        Ok(())
+    } else {
+        Err(OrcError::Socks(format!(
+            "expected SMTP {expected_code} reply, got: {}",
+            line.trim_end()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_matching_code() {
+        assert!(check_code("250 OK\r\n", "250").is_ok());
+    }
+
+    #[test]
+    fn rejects_mismatched_code() {
+        assert!(check_code("550 No such user\r\n", "250").is_err());
+    }
+}
@@ -0,0 +1,587 @@
+//! A minimal SOCKS5 client (RFC 1928) used to reach onion services through
+//! a local Tor daemon.
+//!
+//! Only the pieces `orc` actually needs are implemented: CONNECT to a
+//! domain name or IP address, either unauthenticated or with a
+//! [`SocksAuth`] username/password pair Tor uses to pick which circuit a
+//! connection lands on rather than to authenticate anyone. There is no
+//! BIND/UDP ASSOCIATE support because Tor's SOCKS port doesn't offer
+//! them.
+//!
+//! The proxy itself is normally reached over TCP at a `host:port`, but
+//! Tor can also be told to listen on a Unix domain socket
+//! (`SocksPort unix:/run/tor/socks` in `torrc`) instead — one less port
+//! for anything else on the host to probe. Setting `ORC_SOCKS_SOCKET` to
+//! that socket's path makes every `orc` command reach it that way
+//! instead of the `--proxy host:port` it was given, which is left
+//! untouched so existing scripts and configs keep working unmodified.
+//! `ORC_SOCKS_ADDR` is the TCP equivalent, for callers that want to
+//! override the proxy address without a Unix socket — set by `orc
+//! browse` when [`detect_proxy`] picks a TCP entry from a
+//! config-provided candidate list. There's no Windows named-pipe equivalent compiled in — std's
+//! `UnixStream` has no Windows counterpart in this crate's dependency
+//! set — so `ORC_SOCKS_SOCKET` is a hard error there rather than a
+//! silent fallback to TCP.
+//!
+//! [`ConnectOptions::default`]'s connect timeout, and how many times the
+//! proxy connect itself is retried, both come from
+//! [`crate::defaults`] — see that module for their `ORC_*` overrides.
+//!
+//! A TCP proxy address is checked against
+//! [`crate::security::check_proxy_addr`] before it's ever dialed, so a
+//! typo'd `--proxy`/`ORC_SOCKS_ADDR` pointing off-box doesn't silently
+//! send unencrypted SOCKS traffic across the network.
+//!
+//! [`ConnectOptions::events`] reports [`crate::events::OrcEvent`]s —
+//! connect started, the handshake completing, a retry being scheduled —
+//! to whoever wants to watch a connection as it happens, e.g. a CLI
+//! progress bar.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use socket2::{Socket, TcpKeepalive};
+
+use crate::cancellation::CancellationToken;
+use crate::error::{OrcError, Result};
+use crate::events::{EventSink, OrcEvent};
+
+const SOCKS_VERSION: u8 = 0x05;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_IPV6: u8 = 0x04;
+
+const AUTH_METHOD_NONE: u8 = 0x00;
+const AUTH_METHOD_PASSWORD: u8 = 0x02;
+const AUTH_VERSION: u8 = 0x01;
+
+/// Username/password handed to the proxy during the SOCKS5 method
+/// subnegotiation (RFC 1929). Tor doesn't check these against any real
+/// account — it uses them purely to decide which circuit a connection
+/// gets: two connections presenting different credentials are kept on
+/// separate circuits ("IsolateSOCKSAuth" in `torrc`, on by default).
+/// `orc` never needs the password scheme for actual authentication, only
+/// for this isolation side effect.
+#[derive(Debug, Clone)]
+pub struct SocksAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// Timeout and keepalive settings for a SOCKS5 connection, threaded in
+/// from config and CLI flags rather than hard-coded.
+#[derive(Clone)]
+pub struct ConnectOptions {
+    /// How long to wait for the TCP handshake with the proxy itself.
+    pub connect_timeout: Duration,
+    /// Timeout applied to the resulting stream's reads, if any.
+    pub read_timeout: Option<Duration>,
+    /// Timeout applied to the resulting stream's writes, if any.
+    pub write_timeout: Option<Duration>,
+    /// TCP keepalive idle time, if keepalive should be enabled at all.
+    pub keepalive: Option<Duration>,
+    /// SOCKS5 username/password to present during the handshake, for
+    /// callers that want Tor to isolate this connection onto its own
+    /// circuit. `None` falls back to the "no authentication" method,
+    /// just like before isolation support existed.
+    pub auth: Option<SocksAuth>,
+    /// Notified of [`OrcEvent`]s as [`create_socks_stream`] works, if
+    /// set — see [`crate::events`].
+    pub events: Option<Arc<dyn EventSink>>,
+    /// Checked between retry attempts in [`create_socks_stream`] (and,
+    /// by whatever reads a long response, between chunks of a transfer
+    /// — see [`crate::net::http::send`]) so a caller can cancel a
+    /// proxied connection cleanly instead of only by killing the
+    /// process. `None` behaves exactly like before cancellation
+    /// support existed.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            connect_timeout: crate::defaults::connect_timeout(),
+            read_timeout: None,
+            write_timeout: None,
+            keepalive: None,
+            auth: None,
+            events: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl fmt::Debug for ConnectOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectOptions")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("write_timeout", &self.write_timeout)
+            .field("keepalive", &self.keepalive)
+            .field("auth", &self.auth)
+            .field("events", &self.events.is_some())
+            .field("cancellation", &self.cancellation)
+            .finish()
+    }
+}
+
+/// Either half of the connection `orc` can reach a SOCKS5 proxy over:
+/// the usual TCP `host:port`, or (see the module doc comment) a Unix
+/// domain socket when `ORC_SOCKS_SOCKET` is set. Everything past the
+/// connect step — the handshake, and whatever the caller does with the
+/// resulting [`Socks5Stream`] — is identical either way.
+enum Transport {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Transport {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            Transport::Tcp(s) => s.set_read_timeout(timeout),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.set_read_timeout(timeout),
+        }
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            Transport::Tcp(s) => s.set_write_timeout(timeout),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.set_write_timeout(timeout),
+        }
+    }
+
+    fn try_clone(&self) -> std::io::Result<Transport> {
+        match self {
+            Transport::Tcp(s) => s.try_clone().map(Transport::Tcp),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.try_clone().map(Transport::Unix),
+        }
+    }
+
+    fn shutdown(&self, how: Shutdown) -> std::io::Result<()> {
+        match self {
+            Transport::Tcp(s) => s.shutdown(how),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.shutdown(how),
+        }
+    }
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Tcp(s) => s.read(buf),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Tcp(s) => s.write(buf),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Transport::Tcp(s) => s.flush(),
+            #[cfg(unix)]
+            Transport::Unix(s) => s.flush(),
+        }
+    }
+}
+
+/// A stream that has completed a SOCKS5 CONNECT handshake to a remote
+/// host, over whichever [`Transport`] reached the proxy. Reading and
+/// writing behave exactly like the underlying stream; the wrapper exists
+/// so callers can see at the type level that a connection has already
+/// been proxied through Tor.
+pub struct Socks5Stream {
+    inner: Transport,
+}
+
+impl Socks5Stream {
+    /// Shuts down the write half of the connection, signalling EOF to the
+    /// peer while leaving the read half open. Needed by protocols where
+    /// the client must half-close before the server will respond.
+    pub fn shutdown_write(&self) -> Result<()> {
+        self.inner.shutdown(Shutdown::Write)?;
+        Ok(())
+    }
+
+    /// Clones the underlying stream, e.g. to read and write it from
+    /// separate threads.
+    pub fn try_clone(&self) -> Result<Socks5Stream> {
+        Ok(Socks5Stream { inner: self.inner.try_clone()? })
+    }
+
+    /// Changes the read timeout on the underlying stream.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        self.inner.set_read_timeout(timeout)?;
+        Ok(())
+    }
+}
+
+impl Read for Socks5Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Write for Socks5Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A [`Socks5Stream`] that re-checks its own target against
+/// [`crate::security::check_host`] at construction, independent of
+/// whether whoever built the underlying stream already did — the same
+/// defense-in-depth [`crate::security`]'s own doc comment describes for
+/// its allow/deny policy, applied to the one object [`OrcClient::connect`]
+/// hands to an embedder.
+///
+/// This crate has no async runtime anywhere (see [`crate::client`]'s doc
+/// comment), so `TorStream` reads and writes synchronously like
+/// everything else here rather than implementing `AsyncRead`/`AsyncWrite`
+/// — an embedder on an async runtime wraps it in their own executor's
+/// blocking-task adapter, the same way they'd wrap any other blocking
+/// socket type. There's also nothing of this wrapper's own to zeroize on
+/// drop: unlike [`crate::secret::SensitiveBytes`], `TorStream` never
+/// copies bytes into a heap buffer it owns — reads and writes pass
+/// straight through to the OS socket underneath [`Socks5Stream`], so
+/// there'd be nothing left in memory for a `Drop` impl to find. A caller
+/// that buffers bytes read off this stream into its own `Vec<u8>` should
+/// reach for [`crate::secret::SensitiveBytes`] for that buffer, the same
+/// as any other sensitive value this crate handles.
+///
+/// [`OrcClient::connect`]: crate::OrcClient::connect
+pub struct TorStream {
+    inner: Socks5Stream,
+}
+
+impl TorStream {
+    /// Wraps `stream`, which must already be connected to `host` —
+    /// rejected with [`OrcError::Denied`] if `host` itself wouldn't pass
+    /// [`crate::security::check_host`], even though
+    /// [`create_socks_stream`] would already have refused to open the
+    /// stream in that case; this only protects against some future
+    /// caller building a `Socks5Stream` some other way and handing it to
+    /// `TorStream::new` without going through that check first.
+    pub fn new(stream: Socks5Stream, host: &str) -> Result<TorStream> {
+        crate::security::check_host(host)?;
+        Ok(TorStream { inner: stream })
+    }
+
+    /// Shuts down the write half of the connection; see
+    /// [`Socks5Stream::shutdown_write`].
+    pub fn shutdown_write(&self) -> Result<()> {
+        self.inner.shutdown_write()
+    }
+}
+
+impl Read for TorStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Write for TorStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Checks `ORC_SOCKS_SOCKET` for a Unix domain socket path to reach the
+/// proxy over instead of TCP. Unset or empty means "no override".
+fn socks_socket_override() -> Option<PathBuf> {
+    std::env::var_os("ORC_SOCKS_SOCKET").map(PathBuf::from).filter(|path| !path.as_os_str().is_empty())
+}
+
+/// Checks `ORC_SOCKS_ADDR` for a `host:port` to use instead of whatever
+/// `proxy_addr` a command was given. Unset, empty, or unparseable means
+/// "no override" — the caller's own `--proxy` wins.
+fn socks_addr_override() -> Option<SocketAddr> {
+    std::env::var_os("ORC_SOCKS_ADDR")
+        .and_then(|value| value.to_str().map(str::to_string))
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Connects to the proxy itself: `proxy_addr` over TCP, unless
+/// [`socks_socket_override`] names a Unix domain socket to use instead,
+/// or (checked second, since a Unix socket is the more specific ask)
+/// [`socks_addr_override`] names a different TCP address.
+fn connect_transport(proxy_addr: SocketAddr, connect_timeout: Duration) -> Result<Transport> {
+    if let Some(socket_path) = socks_socket_override() {
+        #[cfg(unix)]
+        {
+            return Ok(Transport::Unix(UnixStream::connect(&socket_path)?));
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = connect_timeout;
+            return Err(OrcError::InvalidArgument(format!(
+                "ORC_SOCKS_SOCKET is set to {} but this build has no Unix domain socket support",
+                socket_path.display()
+            )));
+        }
+    }
+    let proxy_addr = socks_addr_override().unwrap_or(proxy_addr);
+    crate::security::check_proxy_addr(&proxy_addr)?;
+    Ok(Transport::Tcp(TcpStream::connect_timeout(&proxy_addr, connect_timeout)?))
+}
+
+/// Wraps [`connect_transport`] with [`crate::defaults::retries`] attempts
+/// at reaching the proxy itself, in case it's momentarily refusing
+/// connections (e.g. Tor still bootstrapping). Only the connect is
+/// retried — a failure anywhere in the SOCKS handshake or afterwards is
+/// still surfaced immediately, same as before retries existed.
+///
+/// Checked against `cancellation` before each attempt, so a caller that
+/// cancels while this is waiting out a retry delay doesn't have to wait
+/// for the whole retry budget to run out first.
+fn connect_transport_with_retries(
+    proxy_addr: SocketAddr,
+    connect_timeout: Duration,
+    events: Option<&Arc<dyn EventSink>>,
+    cancellation: Option<&CancellationToken>,
+) -> Result<Transport> {
+    let attempts = crate::defaults::retries();
+    let mut last_err = None;
+    for attempt in 0..attempts {
+        if let Some(token) = cancellation {
+            token.check()?;
+        }
+        match connect_transport(proxy_addr, connect_timeout) {
+            Ok(transport) => return Ok(transport),
+            Err(err) => last_err = Some(err),
+        }
+        if attempt + 1 < attempts {
+            tracing::debug!(target: "orc::net::tcp", proxy = %proxy_addr, attempt = attempt + 1, "retrying SOCKS connect");
+            if let Some(sink) = events {
+                sink.handle(OrcEvent::RetryScheduled { proxy: proxy_addr, attempt: attempt + 1 });
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    Err(last_err.expect("attempts is always at least 1"))
+}
+
+/// Opens a connection to the local Tor SOCKS proxy — at `proxy_addr`
+/// over TCP, or over a Unix domain socket if `ORC_SOCKS_SOCKET` is set
+/// (see the module doc comment) — and asks it to CONNECT to
+/// `target_host:target_port`.
+///
+/// `target_host` is sent as a domain name so Tor itself resolves onion and
+/// regular hostnames, rather than `orc` doing DNS resolution locally.
+///
+/// Checked against [`crate::security::check_host`] first, so every
+/// protocol module that dials out through here is covered by the same
+/// allow/deny policy without each one remembering to call it itself.
+pub fn create_socks_stream(
+    proxy_addr: SocketAddr,
+    target_host: &str,
+    target_port: u16,
+    options: &ConnectOptions,
+) -> Result<Socks5Stream> {
+    if target_host.len() > 255 {
+        return Err(OrcError::InvalidArgument(
+            "target host name is too long for SOCKS5".into(),
+        ));
+    }
+    crate::security::check_host(target_host)?;
+
+    let target = format!("{target_host}:{target_port}");
+    tracing::debug!(target: "orc::net::tcp", proxy = %proxy_addr, target = %target, "connecting");
+    if let Some(sink) = &options.events {
+        sink.handle(OrcEvent::ConnectStarted { proxy: proxy_addr, target: target.clone() });
+    }
+
+    let mut stream =
+        connect_transport_with_retries(proxy_addr, options.connect_timeout, options.events.as_ref(), options.cancellation.as_ref())?;
+    stream.set_read_timeout(options.read_timeout)?;
+    stream.set_write_timeout(options.write_timeout)?;
+    stream = match (stream, options.keepalive) {
+        (Transport::Tcp(tcp), Some(idle)) => {
+            let socket = Socket::from(tcp);
+            socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+            Transport::Tcp(socket.into())
+        }
+        (other, _) => other,
+    };
+
+    // Greeting: version 5, one auth method — "no authentication required"
+    // unless the caller handed us isolation credentials, in which case we
+    // only offer username/password so the proxy can't silently fall back
+    // to an unisolated connection.
+    let method = if options.auth.is_some() { AUTH_METHOD_PASSWORD } else { AUTH_METHOD_NONE };
+    stream.write_all(&[SOCKS_VERSION, 0x01, method])?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(OrcError::Socks(format!(
+            "unexpected SOCKS version {} in method selection",
+            reply[0]
+        )));
+    }
+    if reply[1] != method {
+        return Err(OrcError::Socks(format!(
+            "proxy did not accept the {} method",
+            if method == AUTH_METHOD_PASSWORD { "username/password" } else { "\"no authentication\"" }
+        )));
+    }
+
+    if let Some(auth) = &options.auth {
+        negotiate_password_auth(&mut stream, auth)?;
+    }
+
+    // CONNECT request, always addressed by domain name.
+    let mut request = Vec::with_capacity(7 + target_host.len());
+    request.push(SOCKS_VERSION);
+    request.push(CMD_CONNECT);
+    request.push(0x00); // reserved
+    request.push(ATYP_DOMAIN);
+    request.push(target_host.len() as u8);
+    request.extend_from_slice(target_host.as_bytes());
+    request.extend_from_slice(&target_port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    if header[0] != SOCKS_VERSION {
+        return Err(OrcError::Socks(format!(
+            "unexpected SOCKS version {} in connect reply",
+            header[0]
+        )));
+    }
+    if header[1] != 0x00 {
+        return Err(OrcError::Socks(socks_reply_message(header[1])));
+    }
+
+    // Drain the bound address the proxy echoes back; we don't use it.
+    match header[3] {
+        ATYP_IPV4 => {
+            let mut rest = [0u8; 4 + 2];
+            stream.read_exact(&mut rest)?;
+        }
+        ATYP_IPV6 => {
+            let mut rest = [0u8; 16 + 2];
+            stream.read_exact(&mut rest)?;
+        }
+        ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            let mut rest = vec![0u8; len[0] as usize + 2];
+            stream.read_exact(&mut rest)?;
+        }
+        other => {
+            return Err(OrcError::Socks(format!(
+                "unsupported address type {other} in connect reply"
+            )));
+        }
+    }
+
+    if let Some(sink) = &options.events {
+        sink.handle(OrcEvent::SocksEstablished { proxy: proxy_addr, target });
+    }
+    Ok(Socks5Stream { inner: stream })
+}
+
+/// RFC 1929's username/password subnegotiation, run once the proxy has
+/// selected that method during the greeting.
+fn negotiate_password_auth(stream: &mut Transport, auth: &SocksAuth) -> Result<()> {
+    if auth.username.len() > 255 || auth.password.len() > 255 {
+        return Err(OrcError::InvalidArgument(
+            "SOCKS isolation username/password must each be 255 bytes or shorter".into(),
+        ));
+    }
+
+    let mut request = Vec::with_capacity(3 + auth.username.len() + auth.password.len());
+    request.push(AUTH_VERSION);
+    request.push(auth.username.len() as u8);
+    request.extend_from_slice(auth.username.as_bytes());
+    request.push(auth.password.len() as u8);
+    request.extend_from_slice(auth.password.as_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[1] != 0x00 {
+        return Err(OrcError::Socks("proxy rejected the SOCKS5 username/password".into()));
+    }
+    Ok(())
+}
+
+/// Where a [`ProxyCandidate`] says the proxy might be listening.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyTarget {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+/// One entry in a config-provided, ordered list of proxies to try, used
+/// by [`detect_proxy`] when a caller would rather probe for a working
+/// proxy than hard-code one. `label` is never interpreted, only shown
+/// back to the user so they can tell candidates apart in output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyCandidate {
+    pub label: Option<String>,
+    pub target: ProxyTarget,
+}
+
+/// Tries each candidate in order and returns the first one that accepts
+/// a raw connection. This is deliberately shallow — it only proves
+/// something is listening, not that it speaks SOCKS5 — the same as
+/// every other proxy address in this module, which only finds out
+/// whether it's usable once [`create_socks_stream`] starts the
+/// handshake.
+pub fn detect_proxy(candidates: &[ProxyCandidate]) -> Option<&ProxyCandidate> {
+    const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+    candidates.iter().find(|candidate| match &candidate.target {
+        ProxyTarget::Tcp(addr) => TcpStream::connect_timeout(addr, PROBE_TIMEOUT).is_ok(),
+        #[cfg(unix)]
+        ProxyTarget::Unix(path) => UnixStream::connect(path).is_ok(),
+        #[cfg(not(unix))]
+        ProxyTarget::Unix(_) => false,
+    })
+}
+
+fn socks_reply_message(code: u8) -> String {
+    let reason = match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    };
+    format!("proxy refused the connection: {reason} (code {code})")
+}
@@ -0,0 +1,311 @@
+//! Trust-on-first-use TLS for protocols layered on top of a SOCKS stream
+//! (Gemini, plus the POP3, IMAP, SMTP, XMPP and Electrum clients). We don't have a CA bundle to validate against over
+//! Tor, and the onion address itself is already the trust anchor for the
+//! underlying connection, so instead of rejecting unknown certificates we
+//! pin the first one we see and refuse to proceed if it later changes.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, SignatureScheme, StreamOwned};
+use sha2::{Digest, Sha256};
+
+use crate::error::{OrcError, Result};
+
+/// Accepts any certificate (we have no CA to check against) but records
+/// its fingerprint so the caller can apply trust-on-first-use pinning
+/// after the handshake completes.
+#[derive(Debug)]
+struct RecordingVerifier {
+    seen_fingerprint: Mutex<Option<String>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        *self.seen_fingerprint.lock().unwrap() = Some(fingerprint(end_entity));
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PKCS1_SHA256,
+            SignatureScheme::RSA_PKCS1_SHA384,
+            SignatureScheme::RSA_PKCS1_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+            SignatureScheme::ED25519,
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+        ]
+    }
+}
+
+fn fingerprint(cert: &CertificateDer<'_>) -> String {
+    let digest = Sha256::digest(cert.as_ref());
+    crate::output::hex_string(&digest)
+}
+
+pub struct TofuTlsStream<S: Read + Write> {
+    inner: StreamOwned<ClientConnection, S>,
+}
+
+impl<S: Read + Write> Read for TofuTlsStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<S: Read + Write> Write for TofuTlsStream<S> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Where trust-on-first-use pins are kept. The normal case is a file on
+/// disk ([`default_pin_file`]); [`PinStore::Memory`] backs an in-memory
+/// store instead, for callers like `orc browse --ephemeral` that must
+/// never touch disk. `Memory` holds an `Arc` rather than a reference so
+/// it can be handed to background threads (e.g. the download dispatcher)
+/// alongside the rest of a command's config. Built with the
+/// `keyring-backend` feature, [`PinStore::Keyring`] keeps the whole pin
+/// list in the platform keyring instead of a file, the same choice
+/// [`crate::net::onion_auth::AuthKeyStore`] offers for client-auth keys.
+#[derive(Clone)]
+pub enum PinStore {
+    File(PathBuf),
+    Memory(Arc<Mutex<Vec<(String, String)>>>),
+    #[cfg(feature = "keyring-backend")]
+    Keyring,
+}
+
+impl PinStore {
+    /// A fresh, empty in-memory pin store.
+    pub fn memory() -> Self {
+        PinStore::Memory(Arc::new(Mutex::new(Vec::new())))
+    }
+}
+
+/// Performs a TLS handshake over `raw_stream` (a SOCKS stream, or a plain
+/// socket already mid-exchange for STARTTLS-style upgrades), enforcing
+/// trust-on-first-use pinning against entries stored at `pin_file`: the
+/// first certificate seen for `hostname` is recorded, and later sessions
+/// fail closed if the peer presents a different one.
+pub fn connect_tofu<S: Read + Write>(
+    raw_stream: S,
+    hostname: &str,
+    pin_file: &Path,
+) -> Result<TofuTlsStream<S>> {
+    connect_tofu_with(raw_stream, hostname, &PinStore::File(pin_file.to_path_buf()))
+}
+
+/// Same as [`connect_tofu`], but against any [`PinStore`] rather than
+/// always a file — used by callers like `orc gemini`/`orc browse` that
+/// may need an in-memory pin store instead.
+pub fn connect_tofu_with<S: Read + Write>(
+    raw_stream: S,
+    hostname: &str,
+    store: &PinStore,
+) -> Result<TofuTlsStream<S>> {
+    let verifier = Arc::new(RecordingVerifier {
+        seen_fingerprint: Mutex::new(None),
+    });
+
+    let config = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let server_name = ServerName::try_from(hostname.to_string())
+        .map_err(|_| OrcError::InvalidArgument(format!("`{hostname}` is not a valid TLS server name")))?;
+    let conn = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|err| OrcError::Socks(format!("TLS setup failed: {err}")))?;
+
+    let mut stream = StreamOwned::new(conn, raw_stream);
+    // Force the handshake so we can inspect the certificate before any
+    // application data is exchanged.
+    stream.flush()?;
+
+    let fingerprint = verifier
+        .seen_fingerprint
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| OrcError::Socks("TLS handshake completed without a certificate".into()))?;
+
+    check_and_update_pin(store, hostname, &fingerprint)?;
+
+    Ok(TofuTlsStream { inner: stream })
+}
+
+/// Pre-populates `store` for `hostname` with `fingerprint`, as if a
+/// connection to it had already happened — used for a config file's
+/// `hosts.<host>.pin` override, so a known-good fingerprint is checked
+/// against from the very first connection instead of merely being
+/// trusted on first use. Fails the same way [`connect_tofu`] would if
+/// `hostname` is already pinned to a different fingerprint.
+pub fn seed_pin(store: &PinStore, hostname: &str, fingerprint: &str) -> Result<()> {
+    check_and_update_pin(store, hostname, fingerprint)
+}
+
+fn check_and_update_pin(store: &PinStore, hostname: &str, fingerprint: &str) -> Result<()> {
+    match store {
+        PinStore::File(pin_file) => {
+            let mut pins = load_pins(pin_file)?;
+            if apply_pin(&mut pins, hostname, fingerprint)? {
+                save_pins(pin_file, &pins)?;
+            }
+            Ok(())
+        }
+        PinStore::Memory(pins) => {
+            let mut pins = pins.lock().unwrap();
+            apply_pin(&mut pins, hostname, fingerprint).map(|_| ())
+        }
+        #[cfg(feature = "keyring-backend")]
+        PinStore::Keyring => {
+            let mut pins = load_pins_from_keyring()?;
+            if apply_pin(&mut pins, hostname, fingerprint)? {
+                save_pins_to_keyring(&pins)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Checks `fingerprint` against whatever's already pinned for `hostname`
+/// in `pins`, inserting it if this is the first time seen. Returns
+/// whether a new entry was added, so a file-backed store knows whether
+/// it needs to persist the change.
+fn apply_pin(pins: &mut Vec<(String, String)>, hostname: &str, fingerprint: &str) -> Result<bool> {
+    match pins.iter().find(|(host, _)| host == hostname) {
+        Some((_, pinned)) if pinned == fingerprint => Ok(false),
+        Some((_, pinned)) => Err(OrcError::Socks(format!(
+            "certificate for {hostname} changed: pinned {pinned}, got {fingerprint}"
+        ))),
+        None => {
+            pins.push((hostname.to_string(), fingerprint.to_string()));
+            Ok(true)
+        }
+    }
+}
+
+fn load_pins(pin_file: &Path) -> Result<Vec<(String, String)>> {
+    match std::fs::read_to_string(pin_file) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(host, fp)| (host.to_string(), fp.to_string()))
+            .collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn save_pins(pin_file: &Path, pins: &[(String, String)]) -> Result<()> {
+    if let Some(parent) = pin_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents: String = pins
+        .iter()
+        .map(|(host, fp)| format!("{host} {fp}\n"))
+        .collect();
+    std::fs::write(pin_file, contents)?;
+    Ok(())
+}
+
+/// Default location for TOFU pins: `$HOME/.config/orc/tofu_pins`.
+pub fn default_pin_file() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/orc/tofu_pins")
+}
+
+#[cfg(feature = "keyring-backend")]
+const PIN_KEYRING_SERVICE: &str = "orc-tls-pins";
+#[cfg(feature = "keyring-backend")]
+const PIN_KEYRING_ACCOUNT: &str = "pins";
+
+/// Same `"host fingerprint\n"` lines [`load_pins`]/[`save_pins`] read and
+/// write to a file, just kept in one keyring entry instead.
+#[cfg(feature = "keyring-backend")]
+fn load_pins_from_keyring() -> Result<Vec<(String, String)>> {
+    let entry = keyring::Entry::new(PIN_KEYRING_SERVICE, PIN_KEYRING_ACCOUNT).map_err(pin_keyring_error)?;
+    match entry.get_password() {
+        Ok(text) => Ok(text.lines().filter_map(|line| line.split_once(' ')).map(|(host, fp)| (host.to_string(), fp.to_string())).collect()),
+        Err(keyring::Error::NoEntry) => Ok(Vec::new()),
+        Err(err) => Err(pin_keyring_error(err)),
+    }
+}
+
+#[cfg(feature = "keyring-backend")]
+fn save_pins_to_keyring(pins: &[(String, String)]) -> Result<()> {
+    let entry = keyring::Entry::new(PIN_KEYRING_SERVICE, PIN_KEYRING_ACCOUNT).map_err(pin_keyring_error)?;
+    let contents: String = pins.iter().map(|(host, fp)| format!("{host} {fp}\n")).collect();
+    entry.set_password(&contents).map_err(pin_keyring_error)
+}
+
+#[cfg(feature = "keyring-backend")]
+fn pin_keyring_error(err: keyring::Error) -> OrcError {
+    OrcError::Keyring(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_a_new_host_and_accepts_it_again() {
+        let dir = std::env::temp_dir().join(format!("orc-tofu-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pin_file = dir.join("pins");
+        let store = PinStore::File(pin_file);
+
+        check_and_update_pin(&store, "example.onion", "aaaa").unwrap();
+        check_and_update_pin(&store, "example.onion", "aaaa").unwrap();
+        let err = check_and_update_pin(&store, "example.onion", "bbbb").unwrap_err();
+        assert!(err.to_string().contains("changed"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn memory_store_pins_without_touching_disk() {
+        let store = PinStore::memory();
+
+        check_and_update_pin(&store, "example.onion", "aaaa").unwrap();
+        check_and_update_pin(&store, "example.onion", "aaaa").unwrap();
+        let err = check_and_update_pin(&store, "example.onion", "bbbb").unwrap_err();
+        assert!(err.to_string().contains("changed"));
+    }
+}
@@ -0,0 +1,219 @@
+//! A minimal Tor control-port client: `AUTHENTICATE`, a handful of
+//! `GETINFO` queries for a live status line, `SIGNAL NEWNYM` to ask for a
+//! fresh circuit identity, and `ADD_ONION`/`DEL_ONION` to publish and
+//! retire a hidden service (see [`crate::commands::serve`]). The control
+//! port is reached directly (it's local to the Tor daemon, not something
+//! that would be proxied through itself), and only NULL, password, and
+//! cookie authentication are supported — no SAFECOOKIE challenge-response,
+//! and no subscribing to asynchronous `650` events.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+
+use crate::error::{OrcError, Result};
+use crate::output;
+use crate::secret::SensitiveString;
+
+/// How to authenticate to the control port, mirroring the three schemes
+/// `torrc`'s `CookieAuthentication`/`HashedControlPassword` can require.
+pub enum Auth<'a> {
+    Null,
+    Password(&'a SensitiveString),
+    CookieFile(&'a Path),
+}
+
+pub struct TorControlClient {
+    reader: BufReader<TcpStream>,
+}
+
+/// A snapshot of what the control port reports right now. There's no
+/// `isolation` field: which circuit a connection lands on is decided by
+/// the SOCKS5 credentials it presents ([`crate::net::tcp::SocksAuth`]),
+/// a per-connection detail `GETINFO` doesn't expose, so there's nothing
+/// truthful to report at this level.
+pub struct TorStatus {
+    pub version: String,
+    pub socks_listeners: String,
+    pub circuit_established: bool,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+}
+
+impl TorControlClient {
+    /// Connects to the control port at `addr` and authenticates.
+    pub fn connect(addr: SocketAddr, auth: Auth<'_>) -> Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let mut client = TorControlClient { reader: BufReader::new(stream) };
+        client.authenticate(auth)?;
+        Ok(client)
+    }
+
+    fn authenticate(&mut self, auth: Auth<'_>) -> Result<()> {
+        let command = match auth {
+            Auth::Null => "AUTHENTICATE".to_string(),
+            Auth::Password(password) => format!("AUTHENTICATE \"{}\"", escape_quoted(password.as_str())),
+            Auth::CookieFile(path) => {
+                let cookie = std::fs::read(path)?;
+                format!("AUTHENTICATE {}", output::hex_string(&cookie))
+            }
+        };
+        self.send_line(&command)?;
+        self.read_simple_reply("250")
+    }
+
+    /// Reads the fields that make up a status line: version, the
+    /// configured SOCKS listeners, whether a circuit has ever completed,
+    /// and cumulative traffic counters.
+    pub fn status(&mut self) -> Result<TorStatus> {
+        Ok(TorStatus {
+            version: self.getinfo("version")?,
+            socks_listeners: self.getinfo("net/listeners/socks")?,
+            circuit_established: self.getinfo("status/circuit-established")? == "1",
+            bytes_read: self.getinfo("traffic/read")?.parse().unwrap_or(0),
+            bytes_written: self.getinfo("traffic/written")?.parse().unwrap_or(0),
+        })
+    }
+
+    /// Sends `SIGNAL NEWNYM`, asking Tor to stop using existing circuits
+    /// for new connections — a fresh identity at the exit, though nothing
+    /// already established is torn down.
+    pub fn signal_newnym(&mut self) -> Result<()> {
+        self.send_line("SIGNAL NEWNYM")?;
+        self.read_simple_reply("250")
+    }
+
+    /// Publishes a hidden service forwarding `public_port` to `target` (a
+    /// plain `127.0.0.1` address — inbound traffic Tor hands to a local
+    /// listener, not something this client proxies through SOCKS) and
+    /// returns its onion address's service ID (without the `.onion`
+    /// suffix).
+    ///
+    /// `key_blob` is `None` for a brand-new, ephemeral service — Tor
+    /// generates the private key itself and, since `Flags=DiscardPK` is
+    /// set, never hands it back, so there's nothing to persist afterward
+    /// — or `Some("ED25519-V3:<base64>")` ([`crate::net::onion_identity::IdentityKey::add_onion_key_blob`])
+    /// to publish under an already-known identity instead, so the same
+    /// address survives restarting `orc serve`.
+    ///
+    /// No `Flags=Detach`: deliberately so that closing this control
+    /// connection (e.g. `orc serve` exiting) tears the service down along
+    /// with it, rather than leaving an onion address reachable after the
+    /// local HTTP server behind it has stopped.
+    ///
+    /// `client_auth_keys` is the base32 public half of each x25519
+    /// keypair [`crate::net::onion_auth::ClientAuthKeypair::generate`]
+    /// produced — one `ClientAuthV3=` flag per entry — turning on Tor's
+    /// descriptor-level client authorization so the service isn't
+    /// reachable by anyone who merely learns the address. Empty for a
+    /// world-reachable service, same as omitting `--client` entirely on
+    /// `orc serve`.
+    pub fn add_onion(&mut self, key_blob: Option<&str>, public_port: u16, target: SocketAddr, client_auth_keys: &[String]) -> Result<String> {
+        let key_arg = key_blob.unwrap_or("NEW:BEST");
+        let mut command = format!("ADD_ONION {key_arg}");
+        if key_blob.is_none() {
+            command.push_str(" Flags=DiscardPK");
+        }
+        command.push_str(&format!(" Port={public_port},{target}"));
+        for key in client_auth_keys {
+            command.push_str(&format!(" ClientAuthV3={key}"));
+        }
+        self.send_line(&command)?;
+        self.read_add_onion_reply()
+    }
+
+    /// Retires a service [`Self::add_onion`] published, by the service ID
+    /// it returned.
+    pub fn del_onion(&mut self, service_id: &str) -> Result<()> {
+        self.send_line(&format!("DEL_ONION {service_id}"))?;
+        self.read_simple_reply("250")
+    }
+
+    fn read_add_onion_reply(&mut self) -> Result<String> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        let service_id = line
+            .trim_end()
+            .strip_prefix("250-ServiceID=")
+            .ok_or_else(|| OrcError::Socks(format!("unexpected ADD_ONION reply: {}", line.trim_end())))?
+            .to_string();
+        loop {
+            let mut next = String::new();
+            self.reader.read_line(&mut next)?;
+            if next.starts_with("250 ") {
+                break;
+            }
+            if !next.starts_with("250-") {
+                return Err(OrcError::Socks(format!("unexpected ADD_ONION reply: {}", next.trim_end())));
+            }
+        }
+        Ok(service_id)
+    }
+
+    fn getinfo(&mut self, key: &str) -> Result<String> {
+        self.send_line(&format!("GETINFO {key}"))?;
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        let continues = line.starts_with("250-");
+        let value = parse_getinfo_line(key, &line)?;
+        if continues {
+            // A single-key GETINFO still ends with a trailing "250 OK".
+            let mut ok_line = String::new();
+            self.reader.read_line(&mut ok_line)?;
+        }
+        Ok(value)
+    }
+
+    fn send_line(&mut self, line: &str) -> Result<()> {
+        self.reader.get_mut().write_all(line.as_bytes())?;
+        self.reader.get_mut().write_all(b"\r\n")?;
+        Ok(())
+    }
+
+    fn read_simple_reply(&mut self, expected_code: &str) -> Result<()> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        if line.starts_with(expected_code) {
+            Ok(())
+        } else {
+            Err(OrcError::Socks(format!("expected control-port {expected_code} reply, got: {}", line.trim_end())))
+        }
+    }
+}
+
+fn escape_quoted(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Pulls `key`'s value out of one line of a `GETINFO` reply, whether it's
+/// the only key (`250 key=value`) or one of several (`250-key=value`),
+/// stripping the surrounding quotes Tor puts around some values.
+fn parse_getinfo_line(key: &str, line: &str) -> Result<String> {
+    let trimmed = line.trim_end();
+    let body = trimmed
+        .strip_prefix("250-")
+        .or_else(|| trimmed.strip_prefix("250 "))
+        .ok_or_else(|| OrcError::Socks(format!("unexpected GETINFO reply: {trimmed}")))?;
+    let value = body.strip_prefix(&format!("{key}=")).unwrap_or(body);
+    Ok(value.trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_plain_value() {
+        assert_eq!(parse_getinfo_line("version", "250-version=0.4.8.9\r\n").unwrap(), "0.4.8.9");
+    }
+
+    #[test]
+    fn parses_a_quoted_value() {
+        assert_eq!(parse_getinfo_line("net/listeners/socks", "250 net/listeners/socks=\"127.0.0.1:9050\"\r\n").unwrap(), "127.0.0.1:9050");
+    }
+
+    #[test]
+    fn rejects_a_non_250_reply() {
+        assert!(parse_getinfo_line("version", "552 Unrecognized key \"version\"\r\n").is_err());
+    }
+}
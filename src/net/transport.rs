@@ -0,0 +1,42 @@
+//! [`TorTransport`]: the three operations [`crate::net::tcp`],
+//! [`crate::net::resolve`], and [`crate::net::http`] each expose as free
+//! functions today, gathered behind one trait so a caller can swap in
+//! something other than the real SOCKS5 proxy — a test double that never
+//! touches the network (see `orc`'s test suite for where that matters
+//! most), or eventually a different transport entirely — without
+//! `net::http`/`net::tcp` themselves needing to know which one they're
+//! talking to.
+//!
+//! [`crate::OrcClient`] implements this trait over the real SOCKS5
+//! proxy, and [`crate::testing::MockTransport`] is the in-process test
+//! double; those are the two implementations in this crate.
+
+use std::io::{Read, Write};
+use std::net::IpAddr;
+
+use crate::error::Result;
+use crate::net::http::HttpResponse;
+
+/// A stream returned by [`TorTransport::connect_stream`]: readable and
+/// writable like any TCP stream, boxed so different implementations
+/// ([`crate::OrcClient`]'s [`crate::net::tcp::TorStream`], or
+/// [`crate::testing::MockTransport`]'s canned-bytes stream)
+/// don't have to share a concrete type.
+pub trait DuplexStream: Read + Write + Send {}
+impl<T: Read + Write + Send> DuplexStream for T {}
+
+/// Opens connections and resolves names through Tor — or, for a test
+/// double, through whatever a caller wants to stand in for it instead.
+pub trait TorTransport {
+    /// Opens a stream to `host:port` through this transport.
+    fn connect_stream(&self, host: &str, port: u16) -> Result<Box<dyn DuplexStream>>;
+
+    /// Resolves `hostname` through this transport rather than the local
+    /// resolver.
+    fn resolve(&self, hostname: &str) -> Result<IpAddr>;
+
+    /// Sends a single `http://` request through this transport and reads
+    /// the response to EOF, the same one-shot request [`crate::net::http::send`]
+    /// makes over a real SOCKS connection.
+    fn http_client(&self, method: &str, url: &str, headers: &[(String, String)], body: &[u8]) -> Result<HttpResponse>;
+}
@@ -0,0 +1,283 @@
+//! A minimal XMPP (RFC 6120) client: STARTTLS, SASL PLAIN, resource
+//! binding, fetching the roster, and one-to-one `<message/>` stanzas.
+//! No MUC, no PEP/pubsub, no SASL mechanism beyond PLAIN — just enough
+//! to talk to a single onion XMPP server from a terminal.
+//!
+//! There's no general XML parser here either: stanzas are read off the
+//! wire by tracking `<tag>`/`</tag>`/`<tag/>` nesting depth back to zero,
+//! the same tag-scraping approach `net::feed` uses for RSS/Atom, and
+//! values are pulled out with simple attribute/text lookups. This holds
+//! for well-formed stanzas from a real server but isn't a conformant XML
+//! parser (no CDATA, no comments, no literal `>` inside attributes).
+
+use std::io::{BufReader, Read, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use base64::Engine;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+use crate::net::tls::{self, TofuTlsStream};
+use crate::secret::SensitiveString;
+
+pub struct ConnectRequest<'a> {
+    pub proxy: SocketAddr,
+    pub host: &'a str,
+    pub port: u16,
+    pub jid: &'a str,
+    pub password: &'a SensitiveString,
+    pub resource: Option<&'a str>,
+    pub pin_file: &'a Path,
+    pub options: &'a ConnectOptions,
+}
+
+pub struct RosterItem {
+    pub jid: String,
+    pub name: Option<String>,
+}
+
+/// A connected, authenticated, resource-bound XMPP session.
+pub struct XmppSession {
+    reader: BufReader<TofuTlsStream<crate::net::tcp::Socks5Stream>>,
+    next_id: u64,
+}
+
+/// Connects, negotiates STARTTLS, authenticates with SASL PLAIN, and
+/// binds a resource, returning a session ready to exchange stanzas.
+pub fn connect(request: ConnectRequest<'_>) -> Result<XmppSession> {
+    let (local, domain) = split_jid(request.jid)?;
+    let socks_stream = create_socks_stream(request.proxy, request.host, request.port, request.options)?;
+    let mut reader = BufReader::new(socks_stream);
+
+    open_stream(reader.get_mut(), domain)?;
+    read_tag_open(&mut reader)?; // <stream:stream ...>
+    read_element(&mut reader)?; // <stream:features> offering STARTTLS
+
+    send_raw(reader.get_mut(), "<starttls xmlns='urn:ietf:params:xml:ns:xmpp-tls'/>")?;
+    let proceed = read_element(&mut reader)?;
+    if !proceed.starts_with("<proceed") {
+        return Err(OrcError::Socks(format!("server refused STARTTLS: {proceed}")));
+    }
+
+    let socks_stream = reader.into_inner();
+    let tls_stream = tls::connect_tofu(socks_stream, request.host, request.pin_file)?;
+    let mut reader = BufReader::new(tls_stream);
+
+    open_stream(reader.get_mut(), domain)?;
+    read_tag_open(&mut reader)?;
+    read_element(&mut reader)?; // <stream:features> offering SASL mechanisms
+
+    let sasl_payload = format!("\0{local}\0{}", request.password.as_str());
+    let sasl_b64 = base64::engine::general_purpose::STANDARD.encode(sasl_payload);
+    send_raw(reader.get_mut(), &format!("<auth xmlns='urn:ietf:params:xml:ns:xmpp-sasl' mechanism='PLAIN'>{sasl_b64}</auth>"))?;
+    let auth_result = read_element(&mut reader)?;
+    if !auth_result.starts_with("<success") {
+        return Err(OrcError::Socks(format!("SASL authentication failed: {auth_result}")));
+    }
+
+    open_stream(reader.get_mut(), domain)?;
+    read_tag_open(&mut reader)?;
+    read_element(&mut reader)?; // <stream:features> again, post-auth
+
+    let mut session = XmppSession { reader, next_id: 1 };
+    session.bind_resource(request.resource)?;
+    Ok(session)
+}
+
+impl XmppSession {
+    fn bind_resource(&mut self, resource: Option<&str>) -> Result<()> {
+        let id = self.take_id();
+        let resource_xml = resource.map(|r| format!("<resource>{r}</resource>")).unwrap_or_default();
+        self.send(&format!(
+            "<iq type='set' id='{id}'><bind xmlns='urn:ietf:params:xml:ns:xmpp-bind'>{resource_xml}</bind></iq>"
+        ))?;
+        let response = self.recv_stanza()?;
+        if !response.contains("type='result'") && !response.contains("type=\"result\"") {
+            return Err(OrcError::Socks(format!("resource binding failed: {response}")));
+        }
+        Ok(())
+    }
+
+    /// Requests the roster and parses each `<item/>` entry out of the
+    /// response.
+    pub fn roster(&mut self) -> Result<Vec<RosterItem>> {
+        let id = self.take_id();
+        self.send(&format!("<iq type='get' id='{id}'><query xmlns='jabber:iq:roster'/></iq>"))?;
+        let response = self.recv_stanza()?;
+        Ok(extract_tags(&response, "item")
+            .iter()
+            .filter_map(|item| extract_attr(item, "jid").map(|jid| RosterItem { jid, name: extract_attr(item, "name") }))
+            .collect())
+    }
+
+    /// Sends a one-to-one chat message to `to`.
+    pub fn send_message(&mut self, to: &str, body: &str) -> Result<()> {
+        let id = self.take_id();
+        self.send(&format!(
+            "<message type='chat' to='{to}' id='{id}'><body>{}</body></message>",
+            escape(body)
+        ))
+    }
+
+    /// Blocks for the next top-level stanza from the server.
+    pub fn recv_stanza(&mut self) -> Result<String> {
+        read_element(&mut self.reader)
+    }
+
+    fn send(&mut self, stanza: &str) -> Result<()> {
+        send_raw(self.reader.get_mut(), stanza)
+    }
+
+    fn take_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+fn split_jid(jid: &str) -> Result<(&str, &str)> {
+    jid.split_once('@').ok_or_else(|| OrcError::InvalidArgument(format!("`{jid}` is not a bare JID (user@domain)")))
+}
+
+fn open_stream<W: Write>(stream: &mut W, domain: &str) -> Result<()> {
+    send_raw(
+        stream,
+        &format!("<?xml version='1.0'?><stream:stream to='{domain}' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0'>"),
+    )
+}
+
+fn send_raw<W: Write>(stream: &mut W, data: &str) -> Result<()> {
+    stream.write_all(data.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Reads up to and including the first `>`, for the open-ended
+/// `<stream:stream ...>` tag that never closes for the life of the
+/// connection.
+fn read_tag_open<S: Read>(reader: &mut BufReader<S>) -> Result<String> {
+    let mut tag = String::new();
+    let mut byte = [0u8; 1];
+    loop {
+        reader.read_exact(&mut byte)?;
+        tag.push(byte[0] as char);
+        if byte[0] == b'>' {
+            return Ok(tag);
+        }
+    }
+}
+
+/// Reads one complete top-level XML element (self-closing, or with a
+/// matching end tag), tracking `<`/`>` nesting depth.
+fn read_element<S: Read>(reader: &mut BufReader<S>) -> Result<String> {
+    let mut text = String::new();
+    let mut depth = 0i32;
+    let mut byte = [0u8; 1];
+
+    loop {
+        reader.read_exact(&mut byte)?;
+        let ch = byte[0] as char;
+        text.push(ch);
+
+        if ch == '<' {
+            let mut tag_tail = String::new();
+            loop {
+                reader.read_exact(&mut byte)?;
+                let tail_ch = byte[0] as char;
+                text.push(tail_ch);
+                if tail_ch == '>' {
+                    break;
+                }
+                tag_tail.push(tail_ch);
+            }
+
+            if tag_tail.starts_with('/') {
+                depth -= 1;
+            } else if !tag_tail.ends_with('/') && !tag_tail.starts_with('?') {
+                depth += 1;
+            }
+
+            if depth == 0 {
+                return Ok(text);
+            }
+        }
+    }
+}
+
+/// Returns every non-overlapping `<tag ...>...</tag>` element in `xml`
+/// verbatim, open and close tags included (or a self-closing
+/// `<tag .../>` as-is).
+fn extract_tags(xml: &str, tag: &str) -> Vec<String> {
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&format!("<{tag}")) {
+        let after_open = &rest[start..];
+        let Some(tag_end) = after_open.find('>') else { break };
+        if after_open.as_bytes()[tag_end - 1] == b'/' {
+            elements.push(after_open[..=tag_end].to_string());
+            rest = &after_open[tag_end + 1..];
+            continue;
+        }
+        let close = format!("</{tag}>");
+        let Some(end) = after_open.find(&close) else { break };
+        elements.push(after_open[..end + close.len()].to_string());
+        rest = &after_open[end + close.len()..];
+    }
+    elements
+}
+
+fn extract_attr(xml: &str, attr: &str) -> Option<String> {
+    let tag_end = xml.find('>')?;
+    let tag_text = &xml[..tag_end];
+    let marker = format!("{attr}=\"");
+    if let Some(start) = tag_text.find(&marker) {
+        let start = start + marker.len();
+        let end = tag_text[start..].find('"')? + start;
+        return Some(tag_text[start..end].to_string());
+    }
+    let marker = format!("{attr}='");
+    let start = tag_text.find(&marker)? + marker.len();
+    let end = tag_text[start..].find('\'')? + start;
+    Some(tag_text[start..end].to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_a_self_closing_element() {
+        let mut reader = BufReader::new(Cursor::new(b"<presence/>".to_vec()));
+        assert_eq!(read_element(&mut reader).unwrap(), "<presence/>");
+    }
+
+    #[test]
+    fn reads_a_nested_element() {
+        let mut reader = BufReader::new(Cursor::new(b"<iq type='result'><query><item jid='a@b'/></query></iq>".to_vec()));
+        let stanza = read_element(&mut reader).unwrap();
+        assert!(stanza.ends_with("</iq>"));
+        assert!(stanza.contains("<item jid='a@b'/>"));
+    }
+
+    #[test]
+    fn extracts_roster_items() {
+        let xml = "<query><item jid='a@b' name='A'/><item jid='c@d'/></query>";
+        let items = extract_tags(xml, "item");
+        assert_eq!(items.len(), 2);
+        assert_eq!(extract_attr(&items[0], "jid").as_deref(), Some("a@b"));
+        assert_eq!(extract_attr(&items[0], "name").as_deref(), Some("A"));
+        assert_eq!(extract_attr(&items[1], "name"), None);
+    }
+
+    #[test]
+    fn splits_a_bare_jid() {
+        assert_eq!(split_jid("user@onion.example").unwrap(), ("user", "onion.example"));
+        assert!(split_jid("not-a-jid").is_err());
+    }
+}
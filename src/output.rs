@@ -0,0 +1,128 @@
+//! Shared byte-formatting helpers for commands that print raw data
+//! received from the network.
+
+use base64::Engine;
+
+use crate::error::{OrcError, Result};
+
+/// How a command should render a buffer of received bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Write the bytes to stdout exactly as received.
+    Raw,
+    /// A single unbroken lowercase hex string.
+    Hex,
+    /// The canonical offset + hex + ASCII hexdump view.
+    Hexdump,
+    /// Standard base64.
+    Base64,
+}
+
+/// Renders `data` according to `format`. `Raw` is returned as-is since it
+/// may not be valid UTF-8; callers should write it to stdout as bytes.
+pub fn render(data: &[u8], format: OutputFormat) -> Vec<u8> {
+    match format {
+        OutputFormat::Raw => data.to_vec(),
+        OutputFormat::Hex => hex_string(data).into_bytes(),
+        OutputFormat::Hexdump => hexdump(data).into_bytes(),
+        OutputFormat::Base64 => base64::engine::general_purpose::STANDARD
+            .encode(data)
+            .into_bytes(),
+    }
+}
+
+/// A single unbroken lowercase hex string, e.g. `deadbeef`.
+pub fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parses a hex string produced by [`hex_string`] (or typed by a user)
+/// back into bytes.
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(OrcError::InvalidArgument(
+            "hex payload must have an even number of digits".into(),
+        ));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| OrcError::InvalidArgument(format!("`{hex}` is not valid hex")))
+        })
+        .collect()
+}
+
+/// Renders `data` as a canonical `offset  hex bytes  |ascii|` hexdump,
+/// 16 bytes per line, matching the layout of tools like `hexdump -C`.
+pub fn hexdump(data: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, chunk) in data.chunks(16).enumerate() {
+        let offset = i * 16;
+        out.push_str(&format!("{offset:08x}  "));
+
+        for (j, byte) in chunk.iter().enumerate() {
+            out.push_str(&format!("{byte:02x} "));
+            if j == 7 {
+                out.push(' ');
+            }
+        }
+        // Pad the hex column so the ASCII column lines up for short lines.
+        let missing = 16 - chunk.len();
+        for j in chunk.len()..chunk.len() + missing {
+            out.push_str("   ");
+            if j == 7 {
+                out.push(' ');
+            }
+        }
+
+        out.push_str(" |");
+        for byte in chunk {
+            let c = *byte as char;
+            if c.is_ascii_graphic() || c == ' ' {
+                out.push(c);
+            } else {
+                out.push('.');
+            }
+        }
+        out.push_str("|\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_string_roundtrip() {
+        assert_eq!(hex_string(&[0xde, 0xad, 0xbe, 0xef]), "deadbeef");
+        assert_eq!(hex_string(&[]), "");
+    }
+
+    #[test]
+    fn hexdump_single_short_line() {
+        let data = b"Hi!";
+        let dump = hexdump(data);
+        assert_eq!(
+            dump,
+            "00000000  48 69 21                                          |Hi!|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_wraps_at_sixteen_bytes() {
+        let data: Vec<u8> = (0u8..20).collect();
+        let dump = hexdump(&data);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("00000000  "));
+        assert!(lines[1].starts_with("00000010  "));
+    }
+
+    #[test]
+    fn base64_matches_known_vector() {
+        let rendered = render(b"hello", OutputFormat::Base64);
+        assert_eq!(rendered, b"aGVsbG8=".to_vec());
+    }
+}
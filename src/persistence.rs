@@ -0,0 +1,38 @@
+//! A single gate every disk-touching command in this crate can be routed
+//! through when a caller asks for RAM-only operation (`orc browse
+//! --ephemeral`). There's no process-wide state here — nothing else in
+//! `orc` needs "ever touched disk" to be a single yes/no fact — so this
+//! stays a plain function taking the flag, the same shape as
+//! [`crate::config::validate`] taking the data it checks rather than
+//! reaching for a global: it just gives every call site in
+//! `commands::browse` one place to funnel through, instead of each
+//! writing its own ad hoc refusal message.
+
+use crate::error::{OrcError, Result};
+
+/// Refuses with a clear error if `ephemeral` is set. Call this before any
+/// operation that would read or write a file; `operation` names what was
+/// attempted, so the error is specific about what got refused.
+pub fn guard(ephemeral: bool, operation: &str) -> Result<()> {
+    if ephemeral {
+        return Err(OrcError::InvalidArgument(format!("{operation} needs disk access, which --ephemeral disables")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_the_operation_when_not_ephemeral() {
+        assert!(guard(false, "session save").is_ok());
+    }
+
+    #[test]
+    fn refuses_the_operation_when_ephemeral() {
+        let err = guard(true, "session save").unwrap_err();
+        assert!(err.to_string().contains("session save"));
+        assert!(err.to_string().contains("--ephemeral"));
+    }
+}
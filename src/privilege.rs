@@ -0,0 +1,46 @@
+//! Refuses to run as root/Administrator unless `--allow-root` opts in.
+//!
+//! `orc` leans on process isolation it doesn't itself provide (the
+//! sandboxed syscall allowlist in [`crate::sandbox`], the OS's normal
+//! per-user file permissions protecting `~/.config/orc`) to keep a
+//! compromised run from doing much beyond what `orc` itself needed to
+//! do. Running as root defeats that for free: a bug or a malicious
+//! onion peer that manages to run arbitrary code runs it with every
+//! privilege on the box instead of just this user's.
+//!
+//! Checked once from [`crate::run_cli`], the same pre-clap-flag-plus-startup-guard
+//! shape as [`crate::coredump::disable`] and `--sandbox`. Like `--sandbox`,
+//! and unlike core dump disabling, there's no "best effort, silently do
+//! less" fallback here — a
+//! user actually running as root gets a loud refusal, not a quieter
+//! version of the same check.
+//!
+//! Windows has no dependency in this crate that exposes
+//! `IsUserAnAdmin`/token-elevation checks, the same gap
+//! [`crate::coredump`] and [`crate::signals`] already document for that
+//! platform — [`is_elevated`] is always `false` there, so this guard is
+//! a no-op on Windows rather than a false sense of safety.
+
+use crate::error::{OrcError, Result};
+
+/// Whether the current process is running as `root` (UID 0) on Unix.
+/// Always `false` on other platforms — see the module doc comment.
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+pub fn is_elevated() -> bool {
+    false
+}
+
+/// Refuses to continue if [`is_elevated`] and `allow_root` wasn't given.
+pub fn guard(allow_root: bool) -> Result<()> {
+    if is_elevated() && !allow_root {
+        return Err(OrcError::Denied(
+            "refusing to run as root — pass --allow-root if you really mean to".into(),
+        ));
+    }
+    Ok(())
+}
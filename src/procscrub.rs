@@ -0,0 +1,70 @@
+//! Zeroes this process's own argv memory once it's done being read, so a
+//! secret typed directly on the command line doesn't linger in
+//! `/proc/<pid>/cmdline` — and therefore in `ps`, `top`, or anything else
+//! that reads the same thing — for the rest of the process's lifetime.
+//! Called once from [`crate::run_cli`], right after [`clap::Parser::parse_from`] has
+//! copied every argument into owned `String`s of its own; the raw bytes
+//! this overwrites are the kernel's original copy from `execve`, not
+//! anything still in use.
+//!
+//! This crate's own convention, used throughout [`crate::commands`]
+//! (`--password-stdin` on `orc matrix`/`orc mail`/`orc xmpp`/`orc
+//! oshare`, [`crate::secret::HexSource`] for `--secret-fd`), is to keep
+//! a secret out of argv in the first place rather than clean up after
+//! it arrives there — reading it from stdin, a file, or an inherited
+//! descriptor never touches argv at all. `scrub_argv` is the backstop
+//! for what that convention can't cover: a hostname, URL, or other
+//! argument that happens to carry a credential embedded in it (a
+//! `user:pass@host` URL, say) that nothing flagged as sensitive enough
+//! to route through `HexSource`.
+//!
+//! Deliberately does *not* touch `ORC_*` environment variables.
+//! [`crate::config::apply_env_overrides`] re-reads them fresh on every
+//! `orc browse` invocation by design (see [`crate::security::check_host`]'s
+//! doc comment for the same reasoning applied to the config file) —
+//! clearing one after its first read would silently stop working from
+//! the second `orc browse` inside an `orc repl` session onward. None of
+//! them carry a secret today, so there's nothing that actually needs
+//! scrubbing; if one ever does, it belongs behind `--password-stdin`
+//! like everything else here, not behind this module.
+
+/// Overwrites this process's own argv bytes with zero. Best-effort: a
+/// missing or unreadable `/proc/self/stat` (or a platform without one)
+/// leaves argv exactly as it would have been before this existed, the
+/// same "don't fail startup over a hardening step" tradeoff as
+/// [`crate::coredump::disable`].
+#[cfg(target_os = "linux")]
+pub fn scrub_argv() {
+    if let Some((start, end)) = arg_region() {
+        if end > start {
+            // Safety: `start`/`end` come from the kernel's own record of
+            // this exact process's argv block (`/proc/self/stat`'s
+            // `arg_start`/`arg_end`), so the range is guaranteed mapped,
+            // writable, and owned by this process — it's the same
+            // memory `execve` populated argv from.
+            unsafe {
+                std::ptr::write_bytes(start as *mut u8, 0, end - start);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn scrub_argv() {}
+
+/// Reads `arg_start`/`arg_end` out of `/proc/self/stat` — fields 48 and
+/// 49 (1-indexed) per `proc(5)`, the virtual address range of this
+/// process's own argv block. `comm` (field 2) is parenthesized and can
+/// contain spaces, so fields are counted from the last `)` rather than
+/// by splitting the whole line on whitespace.
+#[cfg(target_os = "linux")]
+fn arg_region() -> Option<(usize, usize)> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    let after_comm = stat.rsplit_once(')')?.1;
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    // `fields[0]` is field 3 (state); arg_start is field 48, arg_end is
+    // field 49, so they land at indices 45 and 46.
+    let arg_start = fields.get(45)?.parse().ok()?;
+    let arg_end = fields.get(46)?.parse().ok()?;
+    Some((arg_start, arg_end))
+}
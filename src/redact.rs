@@ -0,0 +1,102 @@
+//! Truncates `.onion` addresses in error messages and other terminal
+//! output so a full address doesn't end up sitting in scrollback or get
+//! pasted whole into a shared bug report. On by default; [`crate::run_cli`] only
+//! turns it off when both `--verbose` and `--no-redact` are given, since
+//! `--verbose` alone asking for more detail shouldn't also hand out
+//! addresses nobody asked to expose.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDACT: AtomicBool = AtomicBool::new(true);
+
+const V2_LABEL_LEN: usize = 16;
+const V3_LABEL_LEN: usize = 56;
+const SUFFIX: &str = ".onion";
+
+/// Set once from [`crate::run_cli`] after its pre-clap `--verbose`/`--no-redact`
+/// handling; read from every later error-printing path.
+pub fn set_enabled(enabled: bool) {
+    REDACT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    REDACT.load(Ordering::Relaxed)
+}
+
+/// Replaces every `.onion` address found in `text` with a truncated
+/// `abcd…wxyz.onion` form when redaction is enabled, leaving everything
+/// else (including non-onion hosts) untouched. Returns `text` verbatim
+/// when redaction is off.
+pub fn redact(text: &str) -> String {
+    redact_if(text, is_enabled())
+}
+
+fn redact_if(text: &str, enabled: bool) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let run_start = i;
+        while i < chars.len() && is_base32_char(chars[i]) {
+            i += 1;
+        }
+        let label: String = chars[run_start..i].iter().collect();
+        let followed_by_suffix = chars[i..].iter().collect::<String>().starts_with(SUFFIX);
+
+        if followed_by_suffix && (label.len() == V2_LABEL_LEN || label.len() == V3_LABEL_LEN) {
+            out.push_str(&truncate_label(&label));
+        } else {
+            out.push_str(&label);
+        }
+
+        if run_start == i {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+fn is_base32_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | 'A'..='Z' | '2'..='7')
+}
+
+/// `abcd…wxyz`, the first and last 4 characters of `label` joined by an
+/// ellipsis — short enough to be useless for re-dialing the address, long
+/// enough that two different addresses in the same report don't collide.
+fn truncate_label(label: &str) -> String {
+    format!("{}…{}", &label[..4], &label[label.len() - 4..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_a_v3_address_embedded_in_a_sentence() {
+        let v3 = "a".repeat(56);
+        let text = format!("connecting to {v3}.onion failed");
+        assert_eq!(redact_if(&text, true), "connecting to aaaa…aaaa.onion failed");
+    }
+
+    #[test]
+    fn leaves_a_regular_hostname_alone() {
+        assert_eq!(redact_if("connecting to example.com failed", true), "connecting to example.com failed");
+    }
+
+    #[test]
+    fn does_nothing_when_disabled() {
+        let v3 = "a".repeat(56);
+        let text = format!("{v3}.onion");
+        assert_eq!(redact_if(&text, false), text);
+    }
+
+    #[test]
+    fn leaves_a_wrong_length_label_alone() {
+        assert_eq!(redact_if("short.onion", true), "short.onion");
+    }
+}
@@ -0,0 +1,278 @@
+//! Applies OS-level sandboxing behind `--sandbox`, read directly off
+//! `argv` in [`crate::run_cli`] the same way as `--allow-core-dumps` (see
+//! [`crate::coredump`]), since it has to take effect before alias
+//! expansion or clap parsing run. Unlike `--allow-core-dumps`, this is
+//! opt-*in*: it's new enough that a command nobody has exercised under
+//! it yet could turn out to need a syscall the allowlist below doesn't
+//! have, so it isn't forced on every invocation while that shakes out.
+//!
+//! On Linux/x86_64, installs a seccomp-bpf filter that returns `EPERM`
+//! for every syscall outside a fixed allowlist — in particular
+//! `execve`/`execveat` (this crate never runs another program, so
+//! `orc` has no legitimate reason to replace its own process image)
+//! and `ptrace` (so nothing can attach to a running `orc` and read a
+//! decrypted secret out of its memory, the same motivation as
+//! [`crate::coredump::disable`]). `clone`/`clone3`/`fork`/`vfork` stay
+//! allowed despite being how a process is duplicated too, because
+//! that's also how [`std::thread::spawn`] starts a thread — used by
+//! [`crate::signals::install`] and [`crate::commands::audit`] already
+//! — and without `execve` afterward a forked copy of `orc` can't turn
+//! into a different, attacker-chosen program anyway. On OpenBSD,
+//! `pledge` and `unveil` get the same properties more directly, since
+//! that's what they're for.
+//!
+//! What this module does *not* do is restrict `connect()` to loopback
+//! or a particular host. Classic seccomp-bpf only ever sees a syscall
+//! number and its raw integer/pointer arguments, never the memory a
+//! pointer argument refers to — it has no way to read the `sockaddr` a
+//! `connect()` call is about to dial, so it structurally can't tell a
+//! loopback connect from one to the open internet. That's exactly what
+//! [`crate::security::check_host`] already does, at the application
+//! layer, before every command calls
+//! [`crate::net::tcp::create_socks_stream`] in the first place — this
+//! module hardens what happens *around* that check (no forking, no
+//! tracing, no syscalls this binary has no business making), not a
+//! replacement for it.
+//!
+//! Every other platform has neither seccomp nor pledge exposed by
+//! anything this crate depends on, and Linux outside x86_64 isn't
+//! covered either, since the syscall numbers an allowlist needs are
+//! per-architecture. `enable` returns an error there rather than
+//! silently doing nothing — `--sandbox` is an explicit ask, and the
+//! caller should find out it couldn't be honored rather than believe
+//! it was.
+
+use std::io;
+
+use crate::error::{OrcError, Result};
+
+/// Applies whatever sandboxing this platform supports. Meant to run
+/// once, as early as possible in [`crate::run_cli`].
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+pub fn enable() -> Result<()> {
+    install_seccomp_filter()
+}
+
+#[cfg(target_os = "openbsd")]
+pub fn enable() -> Result<()> {
+    apply_pledge_and_unveil()
+}
+
+#[cfg(not(any(all(target_os = "linux", target_arch = "x86_64"), target_os = "openbsd")))]
+pub fn enable() -> Result<()> {
+    Err(OrcError::InvalidArgument(
+        "--sandbox is only implemented for x86_64 Linux (seccomp-bpf) and OpenBSD (pledge/unveil); this platform has neither".into(),
+    ))
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const NR_OFFSET: u32 = 0; // offsetof(struct seccomp_data, nr)
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const ARCH_OFFSET: u32 = 4; // offsetof(struct seccomp_data, arch)
+
+// From `<linux/audit.h>`: `AUDIT_ARCH_X86_64` is
+// `EM_X86_64 (62) | __AUDIT_ARCH_64BIT (0x80000000) | __AUDIT_ARCH_LE (0x40000000)`.
+// Not exposed by the `libc` crate, so written out as the value it
+// compiles down to.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const AUDIT_ARCH_X86_64: u32 = 0xC000_003E;
+
+/// Everything an `orc` process might legitimately call: file I/O,
+/// sockets, memory management, threading, signals, timing, and exit.
+/// Includes `unlink`/`unlinkat`/`rmdir`/`mkdir`/`mkdirat`/`getdents64` —
+/// [`crate::killswitch::secure_wipe_path_with`] needs all six to remove
+/// what it overwrites, and without them `orc panic` and a duress wipe
+/// would both report success while leaving every file fully intact.
+/// Deliberately missing `execve`/`execveat` (replaces this process
+/// with a different program) and `ptrace` (inspects another process).
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const ALLOWED_SYSCALLS: &[i64] = &[
+    libc::SYS_read,
+    libc::SYS_write,
+    libc::SYS_close,
+    libc::SYS_openat,
+    libc::SYS_unlink,
+    libc::SYS_unlinkat,
+    libc::SYS_rmdir,
+    libc::SYS_mkdir,
+    libc::SYS_mkdirat,
+    libc::SYS_getdents64,
+    libc::SYS_clone,
+    libc::SYS_clone3,
+    libc::SYS_fork,
+    libc::SYS_vfork,
+    libc::SYS_stat,
+    libc::SYS_fstat,
+    libc::SYS_lstat,
+    libc::SYS_newfstatat,
+    libc::SYS_lseek,
+    libc::SYS_pread64,
+    libc::SYS_pwrite64,
+    libc::SYS_readv,
+    libc::SYS_writev,
+    libc::SYS_mmap,
+    libc::SYS_mprotect,
+    libc::SYS_munmap,
+    libc::SYS_madvise,
+    libc::SYS_brk,
+    libc::SYS_rt_sigaction,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_sigaltstack,
+    libc::SYS_ioctl,
+    libc::SYS_fcntl,
+    libc::SYS_dup,
+    libc::SYS_dup2,
+    libc::SYS_dup3,
+    libc::SYS_pipe,
+    libc::SYS_pipe2,
+    libc::SYS_poll,
+    libc::SYS_ppoll,
+    libc::SYS_epoll_create1,
+    libc::SYS_epoll_ctl,
+    libc::SYS_epoll_wait,
+    libc::SYS_socket,
+    libc::SYS_connect,
+    libc::SYS_accept,
+    libc::SYS_accept4,
+    libc::SYS_bind,
+    libc::SYS_listen,
+    libc::SYS_sendto,
+    libc::SYS_recvfrom,
+    libc::SYS_shutdown,
+    libc::SYS_getsockname,
+    libc::SYS_getpeername,
+    libc::SYS_setsockopt,
+    libc::SYS_getsockopt,
+    libc::SYS_sched_yield,
+    libc::SYS_nanosleep,
+    libc::SYS_clock_gettime,
+    libc::SYS_gettimeofday,
+    libc::SYS_futex,
+    libc::SYS_getrandom,
+    libc::SYS_getpid,
+    libc::SYS_gettid,
+    libc::SYS_getrlimit,
+    libc::SYS_setrlimit,
+    libc::SYS_prlimit64,
+    libc::SYS_set_robust_list,
+    libc::SYS_rseq,
+    libc::SYS_restart_syscall,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+];
+
+/// Builds the BPF program and installs it with `seccomp(2)`. The
+/// program first kills the process outright if it's somehow not
+/// running under the x86_64 ABI this was written against — trusting a
+/// syscall number that means something else there would be worse than
+/// refusing to run — then returns `EPERM` for anything not on
+/// [`ALLOWED_SYSCALLS`].
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn install_seccomp_filter() -> Result<()> {
+    let ld_w_abs = (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16;
+    let jmp_jeq_k = (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16;
+    let ret_k = (libc::BPF_RET | libc::BPF_K) as u16;
+
+    let mut program = Vec::with_capacity(ALLOWED_SYSCALLS.len() + 6);
+    unsafe {
+        program.push(libc::BPF_STMT(ld_w_abs, ARCH_OFFSET));
+        program.push(libc::BPF_JUMP(jmp_jeq_k, AUDIT_ARCH_X86_64, 1, 0));
+        program.push(libc::BPF_STMT(ret_k, libc::SECCOMP_RET_KILL_PROCESS));
+        program.push(libc::BPF_STMT(ld_w_abs, NR_OFFSET));
+
+        let last = ALLOWED_SYSCALLS.len() - 1;
+        for (i, &syscall) in ALLOWED_SYSCALLS.iter().enumerate() {
+            let jt = (last - i) as u8;
+            let jf = if i == last { 1 } else { 0 };
+            program.push(libc::BPF_JUMP(jmp_jeq_k, syscall as u32, jt, jf));
+        }
+
+        program.push(libc::BPF_STMT(ret_k, libc::SECCOMP_RET_ALLOW));
+        program.push(libc::BPF_STMT(ret_k, libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32)));
+    }
+
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(OrcError::InvalidArgument(format!("prctl(PR_SET_NO_NEW_PRIVS) failed: {}", io::Error::last_os_error())));
+        }
+
+        let fprog = libc::sock_fprog { len: program.len() as u16, filter: program.as_mut_ptr() };
+        if libc::syscall(libc::SYS_seccomp, libc::SECCOMP_SET_MODE_FILTER, 0u32, &fprog as *const libc::sock_fprog) != 0 {
+            return Err(OrcError::InvalidArgument(format!("seccomp() failed: {}", io::Error::last_os_error())));
+        }
+    }
+
+    Ok(())
+}
+
+/// `unveil`s `orc`'s config directory (the only file this process
+/// writes to on its own — see [`crate::config::default_config_file`])
+/// read/write/create, locks further `unveil` calls, then `pledge`s
+/// down to what a Tor-only network client needs. A `security.wipe_paths`
+/// entry or a `--proxy /path/to.sock` Unix socket outside the config
+/// directory won't be reachable under this — a known gap while this
+/// flag is still new, not a silent one: the `unveil` denial shows up
+/// as a loud I/O error from whichever command hits it, same as any
+/// other `unveil` violation.
+#[cfg(target_os = "openbsd")]
+fn apply_pledge_and_unveil() -> Result<()> {
+    use std::ffi::CString;
+    use std::path::Path;
+
+    let config_dir = crate::config::default_config_file().parent().map(Path::to_path_buf).ok_or_else(|| OrcError::InvalidArgument("could not determine a config directory to unveil".into()))?;
+    std::fs::create_dir_all(&config_dir)?;
+
+    let dir_c = CString::new(config_dir.to_string_lossy().into_owned()).map_err(|_| OrcError::InvalidArgument("config directory path contains a NUL byte".into()))?;
+    let rwc = CString::new("rwc").expect("no NUL bytes in a string literal");
+
+    unsafe {
+        if libc::unveil(dir_c.as_ptr(), rwc.as_ptr()) != 0 {
+            return Err(OrcError::InvalidArgument(format!("unveil() failed: {}", io::Error::last_os_error())));
+        }
+        if libc::unveil(std::ptr::null(), std::ptr::null()) != 0 {
+            return Err(OrcError::InvalidArgument(format!("unveil() lock failed: {}", io::Error::last_os_error())));
+        }
+    }
+
+    let promises = CString::new("stdio rpath wpath cpath inet unix").expect("no NUL bytes in a string literal");
+    unsafe {
+        if libc::pledge(promises.as_ptr(), std::ptr::null()) != 0 {
+            return Err(OrcError::InvalidArgument(format!("pledge() failed: {}", io::Error::last_os_error())));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux", target_arch = "x86_64"))]
+mod tests {
+    use super::*;
+
+    /// [`ALLOWED_SYSCALLS`] has to cover everything
+    /// [`crate::killswitch::secure_wipe_path_with`] calls — `unlink(at)`
+    /// and `rmdir` to remove what it overwrites, `mkdir(at)` for the
+    /// directories [`crate::killswitch::load_wipe_paths`] might be asked
+    /// to recreate, `getdents64` for the `read_dir` it recurses a
+    /// directory with — or `orc panic` and a duress wipe both report
+    /// success under `--sandbox` while every file they were meant to
+    /// remove is still sitting on disk. Doesn't install the real filter
+    /// here: `seccomp()` applies to the whole process for the rest of
+    /// its life, and every other test in this binary still needs
+    /// syscalls a real install would then refuse; `orc --sandbox panic`
+    /// actually removing a file is checked by hand, run against the
+    /// built binary, instead.
+    #[test]
+    fn allowed_syscalls_cover_what_a_wipe_needs_to_delete() {
+        for syscall in [
+            libc::SYS_unlink,
+            libc::SYS_unlinkat,
+            libc::SYS_rmdir,
+            libc::SYS_mkdir,
+            libc::SYS_mkdirat,
+            libc::SYS_getdents64,
+        ] {
+            assert!(ALLOWED_SYSCALLS.contains(&syscall), "syscall {syscall} is missing from ALLOWED_SYSCALLS");
+        }
+    }
+}
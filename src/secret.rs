@@ -0,0 +1,220 @@
+//! A string wrapper for credentials and other values that must never be
+//! printed, logged, or appear in a `Debug` dump.
+//!
+//! Both [`SensitiveString`] and [`SensitiveBytes`] also register their
+//! buffer with [`crate::zeroize`] for as long as they're alive, so
+//! [`crate::zeroize::emergency_exit`] can find and overwrite them even
+//! from a panic far away from wherever the secret was created.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::error::Result;
+use crate::zeroize::{self, Registration};
+
+pub struct SensitiveString {
+    // Order matters: fields drop top-to-bottom, and unregistering before
+    // `value`'s buffer is freed is what `zeroize::register`'s contract
+    // requires.
+    _registration: Registration,
+    value: String,
+}
+
+impl SensitiveString {
+    pub fn new(mut value: String) -> Self {
+        // Safety: `value`'s buffer is never reallocated after this —
+        // `wipe` only shrinks its logical length, it never grows past
+        // its original capacity — so the pointer stays valid for as
+        // long as `_registration` does.
+        let registration = unsafe { zeroize::register(value.as_mut_ptr(), value.len()) };
+        SensitiveString { _registration: registration, value }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Overwrites the secret's bytes with zero in place and clears it.
+    /// Best-effort: this crate has no zeroizing-allocator dependency, so
+    /// there's no guarantee an older copy of the string wasn't already
+    /// moved or reallocated before this runs. Use it for "wipe what we
+    /// can, now" situations like [`crate::killswitch`], not as a
+    /// cryptographic guarantee.
+    pub fn wipe(&mut self) {
+        unsafe {
+            for byte in self.value.as_bytes_mut() {
+                std::ptr::write_volatile(byte, 0);
+            }
+        }
+        self.value.clear();
+    }
+}
+
+impl Clone for SensitiveString {
+    fn clone(&self) -> Self {
+        SensitiveString::new(self.value.clone())
+    }
+}
+
+impl From<String> for SensitiveString {
+    fn from(value: String) -> Self {
+        SensitiveString::new(value)
+    }
+}
+
+impl fmt::Debug for SensitiveString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SensitiveString(<redacted>)")
+    }
+}
+
+impl fmt::Display for SensitiveString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// A byte-string counterpart to [`SensitiveString`], for values a command
+/// handles as raw bytes rather than text — a decoded `--send-hex`
+/// payload, once it comes from one of [`HexSource`]'s places instead of
+/// a plain CLI argument.
+pub struct SensitiveBytes {
+    // Order matters: fields drop top-to-bottom, and unregistering before
+    // `value`'s buffer is freed is what `zeroize::register`'s contract
+    // requires.
+    _registration: Registration,
+    value: Vec<u8>,
+}
+
+impl SensitiveBytes {
+    pub fn new(mut value: Vec<u8>) -> Self {
+        // Safety: same reasoning as `SensitiveString::new` — `value`
+        // never grows past its original capacity after this point.
+        let registration = unsafe { zeroize::register(value.as_mut_ptr(), value.len()) };
+        SensitiveBytes { _registration: registration, value }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.value
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl Clone for SensitiveBytes {
+    fn clone(&self) -> Self {
+        SensitiveBytes::new(self.value.clone())
+    }
+}
+
+impl fmt::Debug for SensitiveBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SensitiveBytes(<redacted>)")
+    }
+}
+
+impl fmt::Display for SensitiveBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Where a command reads a hex-encoded secret from, instead of taking it
+/// as a plain `--send-hex`-style CLI argument that ends up in `ps`
+/// output and shell history the moment it's typed. Mirrors the
+/// `--password-stdin` flags commands like `orc rpc` already have, but
+/// generalized to a file or an already-open file descriptor too, for a
+/// caller that's piping a secret in from somewhere other than its own
+/// terminal.
+pub enum HexSource {
+    /// A single line of hex text on stdin.
+    Stdin,
+    /// A file containing hex text, trailing whitespace ignored.
+    File(PathBuf),
+    /// An already-open file descriptor (Unix only) — e.g. `--secret-fd 3`
+    /// alongside a caller's own `exec 3<secret.hex`.
+    Fd(u32),
+}
+
+impl HexSource {
+    /// Reads the hex text from this source and decodes it.
+    pub fn read(&self) -> Result<SensitiveBytes> {
+        let text = match self {
+            HexSource::Stdin => {
+                let mut line = String::new();
+                std::io::stdin().read_line(&mut line)?;
+                line
+            }
+            HexSource::File(path) => std::fs::read_to_string(path)?,
+            HexSource::Fd(fd) => read_fd_to_string(*fd)?,
+        };
+        crate::output::decode_hex(text.trim()).map(SensitiveBytes::new)
+    }
+}
+
+#[cfg(unix)]
+fn read_fd_to_string(fd: u32) -> Result<String> {
+    use std::io::Read;
+    use std::mem::ManuallyDrop;
+    use std::os::unix::io::FromRawFd;
+
+    // Safety: the caller asked for this by name (`--secret-fd N`), the
+    // same contract ssh's `-oPasswordAuthentication`/gpg's
+    // `--passphrase-fd` flags rely on — fd N is expected to already be
+    // open for reading. Wrapped in `ManuallyDrop` so we never close it:
+    // we didn't open it, closing it is whatever set it up in the first
+    // place's job (e.g. the shell's own `exec N<&-`), and closing a
+    // number that turns out not to be a live descriptor is exactly what
+    // trips Rust's I/O safety abort on drop rather than a plain error.
+    let mut file = ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(fd as std::os::unix::io::RawFd) });
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+#[cfg(not(unix))]
+fn read_fd_to_string(_fd: u32) -> Result<String> {
+    Err(crate::error::OrcError::InvalidArgument("--secret-fd is only supported on Unix".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_and_display_never_expose_the_value() {
+        let secret = SensitiveString::new("hunter2".to_string());
+        assert_eq!(format!("{secret:?}"), "SensitiveString(<redacted>)");
+        assert_eq!(format!("{secret}"), "<redacted>");
+        assert_eq!(secret.as_str(), "hunter2");
+    }
+
+    #[test]
+    fn wipe_clears_the_value() {
+        let mut secret = SensitiveString::new("hunter2".to_string());
+        secret.wipe();
+        assert_eq!(secret.as_str(), "");
+    }
+
+    #[test]
+    fn sensitive_bytes_debug_and_display_never_expose_the_value() {
+        let secret = SensitiveBytes::new(vec![0xde, 0xad]);
+        assert_eq!(format!("{secret:?}"), "SensitiveBytes(<redacted>)");
+        assert_eq!(format!("{secret}"), "<redacted>");
+        assert_eq!(secret.into_bytes(), vec![0xde, 0xad]);
+    }
+
+    #[test]
+    fn hex_source_file_reads_and_decodes_trimmed_hex_text() {
+        let mut file = std::env::temp_dir();
+        file.push(format!("orc-hex-source-test-{}.txt", std::process::id()));
+        std::fs::write(&file, "deadbeef\n").unwrap();
+
+        let secret = HexSource::File(file.clone()).read().unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(secret.into_bytes(), vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+}
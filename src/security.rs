@@ -0,0 +1,282 @@
+//! Config-driven allow/deny lists of hosts, checked by [`check_host`]
+//! before [`crate::net::tcp::create_socks_stream`] dials out — the one
+//! place every protocol module's connection passes through, so this is
+//! "central" by construction rather than by each of the twenty commands
+//! remembering to call it.
+//!
+//! The same chokepoint also runs [`crate::net::onion::validate_onion_host`]
+//! on every host before the allow/deny lists ever see it: a malformed or
+//! checksum-mismatched onion address isn't a policy decision, so it's
+//! rejected before `allow`/`deny` get a say, not folded into either list.
+//!
+//! [`check_proxy_addr`] guards the other end of that same connect call —
+//! the proxy address itself rather than the target host — refusing a
+//! non-loopback one unless `--allow-remote-socks` opted in.
+//!
+//! Loaded the same ambient way [`crate::config::load_aliases`] loads
+//! `"aliases"`: most of `orc`'s protocol commands never load a config
+//! file at all (only `orc browse` does), so threading a `--config-file`
+//! flag onto every one of them just for this would be a bigger change
+//! than a host policy deserves. A missing or encrypted config file means
+//! "no policy, allow everything", same as `load_aliases`; a present,
+//! unencrypted, malformed one is still a real error.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config;
+use crate::error::{OrcError, Result};
+use crate::net::json::Value;
+
+/// Whether [`check_proxy_addr`] should let a non-loopback SOCKS proxy
+/// address through. Off by default; set once from [`crate::run_cli`] by the
+/// `--allow-remote-socks` flag, the same pre-clap-flag-plus-ambient-setter
+/// pattern [`crate::redact::set_enabled`] uses, since this has to govern
+/// every command's own `--proxy` flag rather than being a flag on each
+/// of their twenty `Args` structs itself.
+static ALLOW_REMOTE_SOCKS: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether [`check_proxy_addr`] allows a non-loopback proxy address.
+pub fn set_allow_remote_socks(allow: bool) {
+    ALLOW_REMOTE_SOCKS.store(allow, Ordering::Relaxed);
+}
+
+/// Refuses a SOCKS proxy address that isn't loopback unless
+/// `--allow-remote-socks` was given: traffic between `orc` and the proxy
+/// itself is unencrypted SOCKS5, so a non-loopback address means whatever
+/// Tor is supposed to be hiding the traffic pattern of is instead sent
+/// across the network in the clear on its way to the proxy — almost
+/// always a typo'd `ORC_SOCKS_ADDR`/`--proxy` rather than an intentional
+/// remote proxy, which is exactly why this defaults to refusing it.
+pub fn check_proxy_addr(addr: &SocketAddr) -> Result<()> {
+    check_proxy_addr_with(addr, ALLOW_REMOTE_SOCKS.load(Ordering::Relaxed))
+}
+
+/// The pure check [`check_proxy_addr`] wraps around the process-wide
+/// [`ALLOW_REMOTE_SOCKS`] flag — tests call this directly with a local
+/// `allow_remote` rather than racing each other over the shared flag.
+fn check_proxy_addr_with(addr: &SocketAddr, allow_remote: bool) -> Result<()> {
+    if addr.ip().is_loopback() || allow_remote {
+        return Ok(());
+    }
+    Err(OrcError::Denied(format!(
+        "refusing to send unencrypted SOCKS traffic to non-loopback proxy {addr} — pass --allow-remote-socks if this is intentional"
+    )))
+}
+
+/// An allow list and a deny list of host patterns, plus whether dead v2
+/// onion addresses should be let through. An entry ending in `*` matches
+/// by prefix (`"evil*"` matches `evil1.onion` and `evil2.onion`);
+/// anything else matches only that exact host. An empty policy — no
+/// config file, or no `"security"` section in it — allows everything
+/// (other than malformed onion addresses), same as before this existed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HostPolicy {
+    allow: Vec<String>,
+    deny: Vec<String>,
+    allow_v2_onion: bool,
+}
+
+impl HostPolicy {
+    fn matches(pattern: &str, host: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => host.starts_with(prefix),
+            None => host == pattern,
+        }
+    }
+
+    /// Checks `host` against this policy: a match in `deny` always wins
+    /// over `allow`, then — if `allow` isn't empty — `host` must match
+    /// something in it. Deny winning outright, and a non-empty allow
+    /// list implying "everything else is refused", are both the usual
+    /// firewall-style reading of "allow and deny lists". This is purely
+    /// the allow/deny check — [`check_host`] also runs
+    /// [`crate::net::onion::validate_onion_host`] first.
+    pub fn check(&self, host: &str) -> Result<()> {
+        if self.deny.iter().any(|pattern| Self::matches(pattern, host)) {
+            return Err(OrcError::Denied(format!("{host} matches the configured deny list")));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|pattern| Self::matches(pattern, host)) {
+            return Err(OrcError::Denied(format!("{host} is not on the configured allow list")));
+        }
+        Ok(())
+    }
+}
+
+/// Loads the `"security"` section of the default config file (see
+/// [`config::default_config_file`]): `{"security": {"allow": [...],
+/// "deny": [...]}}`, both optional and defaulting to empty.
+pub fn load_policy() -> Result<HostPolicy> {
+    let text = match std::fs::read_to_string(config::default_config_file()) {
+        Ok(text) => text,
+        Err(_) => return Ok(HostPolicy::default()),
+    };
+    let parsed = crate::net::json::parse(&text)?;
+    if config::is_encrypted(&parsed) {
+        return Ok(HostPolicy::default());
+    }
+    match parsed.get("security") {
+        None => Ok(HostPolicy::default()),
+        Some(Value::Object(fields)) => parse_policy(fields),
+        Some(_) => Err(OrcError::InvalidArgument("config file's \"security\" must be an object".into())),
+    }
+}
+
+fn parse_policy(fields: &[(String, Value)]) -> Result<HostPolicy> {
+    let mut policy = HostPolicy::default();
+    for (key, value) in fields {
+        match key.as_str() {
+            "allow" => policy.allow = parse_host_list(value, "allow")?,
+            "deny" => policy.deny = parse_host_list(value, "deny")?,
+            "allow_v2_onion" => match value {
+                Value::Bool(allow) => policy.allow_v2_onion = *allow,
+                _ => return Err(OrcError::InvalidArgument("config file's \"security.allow_v2_onion\" must be a boolean".into())),
+            },
+            // Unknown fields are reported by `config::validate`, not here.
+            _ => {}
+        }
+    }
+    Ok(policy)
+}
+
+fn parse_host_list(value: &Value, field: &str) -> Result<Vec<String>> {
+    let Value::Array(entries) = value else {
+        return Err(OrcError::InvalidArgument(format!("config file's \"security.{field}\" must be an array")));
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            entry
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| OrcError::InvalidArgument(format!("config file's \"security.{field}\" entries must be strings")))
+        })
+        .collect()
+}
+
+/// Checks `host` against the default config file's `"security"` section,
+/// loading it fresh each time — cheap next to the network round trip
+/// [`crate::net::tcp::create_socks_stream`] is about to make, and keeps
+/// this in step with an edited config file without restarting `orc`,
+/// same reasoning as [`crate::commands::browse`]'s hot-reloaded host
+/// overrides.
+///
+/// Onion-address well-formedness is checked here too, ahead of the
+/// allow/deny lists: a malformed or checksum-mismatched address isn't a
+/// policy decision, so [`HostPolicy::check`] never has to know about it.
+pub fn check_host(host: &str) -> Result<()> {
+    let policy = load_policy()?;
+    crate::net::onion::validate_onion_host(host, policy.allow_v2_onion)?;
+    let result = policy.check(host);
+    if let Err(err) = &result {
+        tracing::warn!(target: "orc::security", host = %host, error = %err, "refused host");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_proxy_addr_with_allows_loopback_by_default() {
+        assert!(check_proxy_addr_with(&"127.0.0.1:9050".parse().unwrap(), false).is_ok());
+        assert!(check_proxy_addr_with(&"[::1]:9050".parse().unwrap(), false).is_ok());
+    }
+
+    #[test]
+    fn check_proxy_addr_with_refuses_a_remote_address_by_default() {
+        assert!(check_proxy_addr_with(&"203.0.113.5:9050".parse().unwrap(), false).is_err());
+    }
+
+    #[test]
+    fn check_proxy_addr_with_allows_a_remote_address_when_opted_in() {
+        assert!(check_proxy_addr_with(&"203.0.113.5:9050".parse().unwrap(), true).is_ok());
+    }
+
+    #[test]
+    fn check_allows_everything_with_an_empty_policy() {
+        assert!(HostPolicy::default().check("anything.onion").is_ok());
+    }
+
+    #[test]
+    fn check_rejects_an_exact_deny_match() {
+        let policy = HostPolicy { allow: Vec::new(), deny: vec!["evil.onion".to_string()], allow_v2_onion: false };
+        assert!(policy.check("evil.onion").is_err());
+        assert!(policy.check("fine.onion").is_ok());
+    }
+
+    #[test]
+    fn check_rejects_a_prefix_deny_match() {
+        let policy = HostPolicy { allow: Vec::new(), deny: vec!["evil*".to_string()], allow_v2_onion: false };
+        assert!(policy.check("evil2.onion").is_err());
+        assert!(policy.check("notevil.onion").is_ok());
+    }
+
+    #[test]
+    fn check_rejects_anything_not_on_a_non_empty_allow_list() {
+        let policy = HostPolicy { allow: vec!["good.onion".to_string()], deny: Vec::new(), allow_v2_onion: false };
+        assert!(policy.check("good.onion").is_ok());
+        assert!(policy.check("other.onion").is_err());
+    }
+
+    #[test]
+    fn check_lets_deny_win_over_a_matching_allow_entry() {
+        let policy = HostPolicy { allow: vec!["shared.onion".to_string()], deny: vec!["shared.onion".to_string()], allow_v2_onion: false };
+        assert!(policy.check("shared.onion").is_err());
+    }
+
+    #[test]
+    fn load_policy_is_empty_when_the_default_config_file_is_absent() {
+        let home = std::env::temp_dir().join(format!("orc-security-test-no-home-{}", std::process::id()));
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let policy = load_policy().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        assert_eq!(policy, HostPolicy::default());
+    }
+
+    #[test]
+    fn load_policy_reads_allow_and_deny_lists_from_a_given_home() {
+        let home = std::env::temp_dir().join(format!("orc-security-test-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"security": {"allow": ["good.onion"], "deny": ["bad*"]}}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let policy = load_policy().unwrap();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(policy.check("good.onion").is_ok());
+        assert!(policy.check("bad1.onion").is_err());
+        assert!(policy.check("other.onion").is_err());
+    }
+
+    #[test]
+    fn load_policy_rejects_a_non_array_allow_list() {
+        let home = std::env::temp_dir().join(format!("orc-security-test-bad-home-{}", std::process::id()));
+        std::fs::create_dir_all(home.join(".config/orc")).unwrap();
+        std::fs::write(home.join(".config/orc/config.json"), r#"{"security": {"allow": "good.onion"}}"#).unwrap();
+
+        let _guard = crate::test_support::home_lock().lock().unwrap();
+        let previous = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+        let result = load_policy();
+        match previous {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        std::fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! In-memory registry of long-lived SOCKS streams, keyed by caller-chosen
+//! ID. Used by `orc session` today and intended to back future daemon and
+//! REPL modes that need to keep connections open across several commands
+//! instead of reconnecting for every exchange.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use crate::error::{OrcError, Result};
+use crate::net::tcp::{create_socks_stream, ConnectOptions};
+
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: HashMap<String, crate::net::tcp::Socks5Stream>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(
+        &mut self,
+        id: &str,
+        proxy: SocketAddr,
+        target_host: &str,
+        target_port: u16,
+        options: &ConnectOptions,
+    ) -> Result<()> {
+        if self.sessions.contains_key(id) {
+            return Err(OrcError::InvalidArgument(format!(
+                "session `{id}` is already open"
+            )));
+        }
+        let stream = create_socks_stream(proxy, target_host, target_port, options)?;
+        self.sessions.insert(id.to_string(), stream);
+        Ok(())
+    }
+
+    pub fn send(&mut self, id: &str, data: &[u8]) -> Result<()> {
+        let stream = self.get_mut(id)?;
+        stream.write_all(data)?;
+        Ok(())
+    }
+
+    /// Reads up to `max_bytes` from the session's stream. Returns fewer
+    /// bytes than requested if the peer hasn't sent that much yet.
+    pub fn recv(&mut self, id: &str, max_bytes: usize) -> Result<Vec<u8>> {
+        let stream = self.get_mut(id)?;
+        let mut buf = vec![0u8; max_bytes];
+        let n = stream.read(&mut buf)?;
+        buf.truncate(n);
+        Ok(buf)
+    }
+
+    pub fn close(&mut self, id: &str) -> Result<()> {
+        self.sessions
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| OrcError::InvalidArgument(format!("no such session `{id}`")))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut crate::net::tcp::Socks5Stream> {
+        self.sessions
+            .get_mut(id)
+            .ok_or_else(|| OrcError::InvalidArgument(format!("no such session `{id}`")))
+    }
+}
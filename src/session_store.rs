@@ -0,0 +1,256 @@
+//! Encrypted save/restore for `orc browse`'s tab list: each open tab's
+//! URL and scroll position, written to disk only on an explicit `session
+//! save` and readable only with the passphrase it was saved under.
+//! Nothing in this crate ever calls [`save`] on its own — there's no
+//! autosave anywhere in `orc browse`.
+//!
+//! [`save`] can also be given a duress passphrase: entering it instead
+//! of the real one at `session restore` silently wipes the file and
+//! [`load`] returns an empty tab list rather than an error. See
+//! [`crate::duress`].
+//!
+//! There's no cookie jar to include: `orc browse` only ever speaks
+//! Gemini, and Gemini carries no cookies, so a session is just its tabs.
+//!
+//! The tab list is serialized with [`crate::net::json`] (no serde in
+//! this crate) and then encrypted the same way [`crate::net::chat`]
+//! encrypts a line — a SHA-256 keystream with an HMAC-SHA256 tag — except
+//! there's only one "message" here instead of a numbered stream of them,
+//! so a per-save salt takes the place of `chat`'s message counter,
+//! drawn from the OS's CSPRNG via [`getrandom`] (falling back to
+//! wall-clock time and the process id in a `--no-default-features`
+//! build without it — see [`fresh_salt`]). As with `chat`, this is
+//! confidentiality and tamper-evidence against whoever finds the file,
+//! not forward secrecy.
+
+use std::path::Path;
+#[cfg(not(feature = "serve"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::duress::Duress;
+use crate::error::{OrcError, Result};
+use crate::net::json::{self, Value};
+use crate::output;
+use crate::secret::SensitiveString;
+
+const SALT_LEN: usize = 16;
+const HMAC_BLOCK_SIZE: usize = 64;
+const TAG_LEN: usize = 32;
+
+/// One saved tab: just enough to reopen it where it was left.
+pub struct SavedTab {
+    pub url: String,
+    pub scroll: usize,
+}
+
+/// Serializes `tabs`, encrypts them under `passphrase`, and writes the
+/// result to `path` as JSON (`{"salt": "...", "ciphertext": "..."}`,
+/// both hex). `duress`, if given, is hashed into the same file's
+/// envelope (see [`crate::duress`]) so a future [`load`] can recognize
+/// it and wipe the file instead of decrypting anything.
+pub fn save(path: &Path, tabs: &[SavedTab], passphrase: &SensitiveString, duress: Option<&SensitiveString>) -> Result<()> {
+    let body = Value::Array(
+        tabs.iter()
+            .map(|tab| Value::Object(vec![("url".to_string(), Value::String(tab.url.clone())), ("scroll".to_string(), Value::Number(tab.scroll as f64))]))
+            .collect(),
+    );
+    let plaintext = body.to_string();
+
+    let salt = fresh_salt();
+    let key = derive_key(passphrase.as_str(), &salt);
+    let keystream = keystream(&key, plaintext.len());
+    let mut ciphertext: Vec<u8> = plaintext.bytes().zip(keystream).map(|(b, k)| b ^ k).collect();
+    let tag = hmac_sha256(&key, &ciphertext);
+    ciphertext.extend_from_slice(&tag);
+
+    let mut fields = vec![
+        ("salt".to_string(), Value::String(output::hex_string(&salt))),
+        ("ciphertext".to_string(), Value::String(output::hex_string(&ciphertext))),
+    ];
+    if let Some(duress) = duress {
+        fields.extend(Duress::set(duress).fields());
+    }
+    std::fs::write(path, Value::Object(fields).to_string())?;
+    Ok(())
+}
+
+/// Reads `path`, decrypts it under `passphrase`, and returns the saved
+/// tab list. Fails (rather than returning garbage) on a wrong passphrase
+/// or a corrupted file, since the authentication tag is checked before
+/// anything is decoded. If `passphrase` instead matches a duress
+/// passphrase this file was [`save`]d with, `path` is securely wiped and
+/// an empty tab list is returned instead of an error.
+pub fn load(path: &Path, passphrase: &SensitiveString) -> Result<Vec<SavedTab>> {
+    let text = std::fs::read_to_string(path)?;
+    let file = json::parse(&text)?;
+    if matches!(Duress::from_envelope(&file), Ok(Some(duress)) if duress.matches(passphrase)) {
+        let _ = crate::killswitch::wipe_configured(path);
+        return Ok(Vec::new());
+    }
+    let salt_hex = file.get("salt").and_then(Value::as_str).ok_or_else(|| OrcError::InvalidArgument("session file has no salt".into()))?;
+    let ciphertext_hex = file
+        .get("ciphertext")
+        .and_then(Value::as_str)
+        .ok_or_else(|| OrcError::InvalidArgument("session file has no ciphertext".into()))?;
+    let salt = output::decode_hex(salt_hex)?;
+    let mut ciphertext = output::decode_hex(ciphertext_hex)?;
+    if ciphertext.len() < TAG_LEN {
+        return Err(OrcError::InvalidArgument("session file is truncated".into()));
+    }
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+
+    let key = derive_key(passphrase.as_str(), &salt);
+    if !crate::constant_time::eq(&hmac_sha256(&key, &ciphertext), &tag) {
+        return Err(OrcError::InvalidArgument("wrong passphrase or corrupted session file".into()));
+    }
+
+    let keystream = keystream(&key, ciphertext.len());
+    let plaintext: Vec<u8> = ciphertext.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+    let plaintext = String::from_utf8(plaintext).map_err(|_| OrcError::InvalidArgument("decrypted session is not valid UTF-8".into()))?;
+
+    let body = json::parse(&plaintext)?;
+    let items = body.as_array().ok_or_else(|| OrcError::InvalidArgument("decrypted session is not a list of tabs".into()))?;
+    items
+        .iter()
+        .map(|item| {
+            let url = item
+                .get("url")
+                .and_then(Value::as_str)
+                .ok_or_else(|| OrcError::InvalidArgument("saved tab has no url".into()))?
+                .to_string();
+            let scroll = match item.get("scroll") {
+                Some(Value::Number(n)) => *n as usize,
+                _ => 0,
+            };
+            Ok(SavedTab { url, scroll })
+        })
+        .collect()
+}
+
+/// A per-save salt from the OS's CSPRNG via [`getrandom`] — see the
+/// module doc comment.
+#[cfg(feature = "serve")]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).expect("the OS's CSPRNG should not fail");
+    salt
+}
+
+/// Falls back to a salt built from wall-clock time and the process id
+/// when built without `getrandom` (`--no-default-features` without
+/// `serve`): enough that saving the same tabs under the same passphrase
+/// twice doesn't reuse a keystream, not a substitute for real
+/// randomness.
+#[cfg(not(feature = "serve"))]
+fn fresh_salt() -> [u8; SALT_LEN] {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_be_bytes());
+    hasher.update(std::process::id().to_be_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&digest[..SALT_LEN]);
+    salt
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"orc-session-v1");
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Produces `len` bytes of keystream by hashing the key and a block
+/// counter together, one SHA-256 block at a time. See
+/// [`crate::net::chat`]'s identically-shaped `keystream`.
+fn keystream(key: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block: u32 = 0;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        hasher.update(block.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// A textbook HMAC-SHA256: `H((key XOR opad) || H((key XOR ipad) || message))`.
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut key_block = [0u8; HMAC_BLOCK_SIZE];
+    key_block[..key.len()].copy_from_slice(key);
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(message);
+    let inner_digest = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_digest);
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_tabs_through_save_and_load() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("orc-session-test-{}.json", std::process::id()));
+        let passphrase = SensitiveString::new("correct horse battery staple".to_string());
+        let tabs = vec![SavedTab { url: "gemini://a/one".to_string(), scroll: 3 }, SavedTab { url: "gemini://a/two".to_string(), scroll: 0 }];
+
+        save(&path, &tabs, &passphrase, None).unwrap();
+        let loaded = load(&path, &passphrase).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].url, "gemini://a/one");
+        assert_eq!(loaded[0].scroll, 3);
+        assert_eq!(loaded[1].url, "gemini://a/two");
+        assert_eq!(loaded[1].scroll, 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_rejects_the_wrong_passphrase() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("orc-session-test-bad-{}.json", std::process::id()));
+        let tabs = vec![SavedTab { url: "gemini://a".to_string(), scroll: 0 }];
+        save(&path, &tabs, &SensitiveString::new("right".to_string()), None).unwrap();
+
+        let result = load(&path, &SensitiveString::new("wrong".to_string()));
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_wipes_the_file_and_returns_no_tabs_under_the_duress_passphrase() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("orc-session-test-duress-{}.json", std::process::id()));
+        let tabs = vec![SavedTab { url: "gemini://a".to_string(), scroll: 0 }];
+        let passphrase = SensitiveString::new("right".to_string());
+        let duress_passphrase = SensitiveString::new("held at gunpoint".to_string());
+        save(&path, &tabs, &passphrase, Some(&duress_passphrase)).unwrap();
+
+        let loaded = load(&path, &duress_passphrase).unwrap();
+
+        assert!(loaded.is_empty());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,73 @@
+//! Installs a single handler for `SIGINT`, `SIGTERM`, and `SIGHUP` that
+//! funnels all three into [`crate::zeroize::emergency_exit`] — the same
+//! zeroize-then-exit path [`crate::zeroize::install_panic_hook`] already
+//! uses for a panic — so a user hitting Ctrl+C, `kill`, or a lost
+//! terminal all wipe registered secrets before the process goes away,
+//! not just an unhandled panic.
+//!
+//! The signal handler itself only sets an [`AtomicBool`] — about the
+//! only thing that's actually safe to do from inside a signal handler —
+//! and a background thread started alongside it polls that flag and
+//! calls `emergency_exit` from ordinary code instead. Calling
+//! `emergency_exit` directly from the handler would mean running
+//! [`crate::zeroize::zeroize_all`]'s mutex-locking, heap-walking cleanup
+//! while the signal interrupted arbitrary code on some other thread,
+//! which is exactly what async-signal-safety rules exist to rule out.
+//!
+//! Installed once from [`crate::run_cli`], the same as
+//! [`crate::zeroize::install_panic_hook`] — works whether the command
+//! that follows is one-shot (`orc gemini` exits before a signal could
+//! ever arrive) or long-running (`orc browse`, `orc chat`, a forwarded
+//! port): the watcher thread runs for exactly as long as the process
+//! does either way, not just while some specific command is active.
+//!
+//! Windows has no `SIGTERM`/`SIGHUP`, and catching a console close event
+//! needs `SetConsoleCtrlHandler`, which isn't exposed by anything this
+//! crate depends on — the same gap [`crate::coredump`] documents for the
+//! same platform. `install` is a no-op there; closing the console window
+//! skips this cleanup the same as it always has.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static SIGNALED: AtomicBool = AtomicBool::new(false);
+
+/// Installs handlers for `SIGINT`, `SIGTERM`, and `SIGHUP`, and spawns
+/// the background thread that turns a caught signal into a clean
+/// [`crate::zeroize::emergency_exit`]. Best-effort: a platform whose
+/// libc doesn't support one of the three just behaves as it would have
+/// before this existed for that signal.
+#[cfg(unix)]
+pub fn install() {
+    let handler = handle_signal as *const () as libc::sighandler_t;
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+        libc::signal(libc::SIGHUP, handler);
+    }
+    std::thread::spawn(watch);
+}
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+/// The actual signal handler: async-signal-safe by doing nothing but an
+/// atomic store. Everything else happens in [`watch`], on its own thread.
+#[cfg(unix)]
+extern "C" fn handle_signal(_signum: libc::c_int) {
+    SIGNALED.store(true, Ordering::SeqCst);
+}
+
+/// Polls [`SIGNALED`] and hands off to [`crate::zeroize::emergency_exit`]
+/// the moment it's set. A short sleep rather than a blocking wait, since
+/// this crate has no signalfd/self-pipe plumbing and a CLI tool's
+/// shutdown latency budget is generous enough that polling is unnoticeable.
+#[cfg(unix)]
+fn watch() {
+    loop {
+        if SIGNALED.load(Ordering::SeqCst) {
+            crate::zeroize::emergency_exit();
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
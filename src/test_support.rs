@@ -0,0 +1,16 @@
+//! Test-only synchronization for tests that temporarily repoint the
+//! process-wide `HOME` environment variable at a scratch directory —
+//! [`config`](crate::config), [`killswitch`](crate::killswitch),
+//! [`security`](crate::security), and [`jitter`](crate::jitter) each
+//! have a few. `cargo test` runs a file's tests on multiple threads by
+//! default, so two such tests racing on `HOME` could see each other's
+//! directory, or (in `killswitch`'s case) wipe it. [`home_lock`] gives
+//! them all one mutex to serialize through instead.
+
+use std::sync::{Mutex, OnceLock};
+
+/// Acquire for the duration of a test that reads or writes `HOME`.
+pub(crate) fn home_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
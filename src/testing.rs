@@ -0,0 +1,179 @@
+//! An in-process [`TorTransport`] for embedders' own tests — and this
+//! crate's — that want `orc`'s request/response shape without a live Tor
+//! daemon: [`MockTransport`] records every call it receives and answers
+//! from a queue of canned responses instead of dialing out.
+//!
+//! ```
+//! use orc::testing::MockTransport;
+//! use orc::net::transport::TorTransport;
+//!
+//! let transport = MockTransport::new();
+//! transport.queue_resolve(Ok("198.51.100.7".parse().unwrap()));
+//!
+//! let addr = transport.resolve("example.onion").unwrap();
+//! assert_eq!(addr.to_string(), "198.51.100.7");
+//! assert_eq!(transport.calls().len(), 1);
+//! ```
+
+use std::collections::VecDeque;
+use std::io::{self, Cursor, Read, Write};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use crate::error::{OrcError, Result};
+use crate::net::http::HttpResponse;
+use crate::net::transport::{DuplexStream, TorTransport};
+
+/// One call [`MockTransport`] received, in the order it received them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedCall {
+    ConnectStream { host: String, port: u16 },
+    Resolve { hostname: String },
+    HttpClient { method: String, url: String, headers: Vec<(String, String)>, body: Vec<u8> },
+}
+
+/// A [`TorTransport`] that never touches the network: each method pops
+/// its next answer off a queue filled ahead of time with
+/// [`Self::queue_connect_stream`]/[`Self::queue_resolve`]/[`Self::queue_http_response`],
+/// recording the call either way. A queue left empty when a call arrives
+/// is answered with [`OrcError::Socks`] rather than panicking, so a test
+/// exercising an unexpected extra call gets an ordinary `Result` to
+/// assert on.
+#[derive(Default)]
+pub struct MockTransport {
+    calls: Mutex<Vec<RecordedCall>>,
+    connect_responses: Mutex<VecDeque<Result<Vec<u8>>>>,
+    resolve_responses: Mutex<VecDeque<Result<IpAddr>>>,
+    http_responses: Mutex<VecDeque<Result<HttpResponse>>>,
+}
+
+impl MockTransport {
+    /// An empty mock with nothing queued yet.
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Queues the next [`TorTransport::connect_stream`] result: `Ok(bytes)`
+    /// is read back verbatim as the stream's contents; writes to the
+    /// stream are accepted and discarded.
+    pub fn queue_connect_stream(&self, result: Result<Vec<u8>>) {
+        self.connect_responses.lock().unwrap().push_back(result);
+    }
+
+    /// Queues the next [`TorTransport::resolve`] result.
+    pub fn queue_resolve(&self, result: Result<IpAddr>) {
+        self.resolve_responses.lock().unwrap().push_back(result);
+    }
+
+    /// Queues the next [`TorTransport::http_client`] result.
+    pub fn queue_http_response(&self, result: Result<HttpResponse>) {
+        self.http_responses.lock().unwrap().push_back(result);
+    }
+
+    /// Every call received so far, in order.
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: RecordedCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl TorTransport for MockTransport {
+    fn connect_stream(&self, host: &str, port: u16) -> Result<Box<dyn DuplexStream>> {
+        self.record(RecordedCall::ConnectStream { host: host.to_string(), port });
+        let next = self.connect_responses.lock().unwrap().pop_front();
+        match next {
+            Some(Ok(bytes)) => Ok(Box::new(MockStream { read: Cursor::new(bytes) })),
+            Some(Err(err)) => Err(err),
+            None => Err(OrcError::Socks(format!("MockTransport has no queued connect_stream response for {host}:{port}"))),
+        }
+    }
+
+    fn resolve(&self, hostname: &str) -> Result<IpAddr> {
+        self.record(RecordedCall::Resolve { hostname: hostname.to_string() });
+        match self.resolve_responses.lock().unwrap().pop_front() {
+            Some(result) => result,
+            None => Err(OrcError::Socks(format!("MockTransport has no queued resolve response for {hostname}"))),
+        }
+    }
+
+    fn http_client(&self, method: &str, url: &str, headers: &[(String, String)], body: &[u8]) -> Result<HttpResponse> {
+        self.record(RecordedCall::HttpClient {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: headers.to_vec(),
+            body: body.to_vec(),
+        });
+        match self.http_responses.lock().unwrap().pop_front() {
+            Some(result) => result,
+            None => Err(OrcError::Socks(format!("MockTransport has no queued http_client response for {url}"))),
+        }
+    }
+}
+
+/// The stream [`MockTransport::connect_stream`] hands back: reads serve
+/// the queued bytes, writes are accepted and discarded.
+struct MockStream {
+    read: Cursor<Vec<u8>>,
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read.read(buf)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_returns_the_queued_address_and_records_the_call() {
+        let transport = MockTransport::new();
+        transport.queue_resolve(Ok("198.51.100.7".parse().unwrap()));
+
+        let addr = transport.resolve("example.onion").unwrap();
+        assert_eq!(addr.to_string(), "198.51.100.7");
+        assert_eq!(transport.calls(), vec![RecordedCall::Resolve { hostname: "example.onion".to_string() }]);
+    }
+
+    #[test]
+    fn resolve_without_a_queued_response_returns_an_error() {
+        let transport = MockTransport::new();
+        assert!(transport.resolve("example.onion").is_err());
+    }
+
+    #[test]
+    fn connect_stream_reads_back_the_queued_bytes() {
+        let transport = MockTransport::new();
+        transport.queue_connect_stream(Ok(b"hello".to_vec()));
+
+        let mut stream = transport.connect_stream("example.onion", 80).unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).unwrap();
+        assert_eq!(received, b"hello");
+        stream.write_all(b"ignored").unwrap();
+    }
+
+    #[test]
+    fn responses_are_served_in_the_order_they_were_queued() {
+        let transport = MockTransport::new();
+        transport.queue_resolve(Ok("198.51.100.1".parse().unwrap()));
+        transport.queue_resolve(Ok("198.51.100.2".parse().unwrap()));
+
+        assert_eq!(transport.resolve("first.onion").unwrap().to_string(), "198.51.100.1");
+        assert_eq!(transport.resolve("second.onion").unwrap().to_string(), "198.51.100.2");
+    }
+}
@@ -0,0 +1,178 @@
+//! A best-effort global registry of live secrets' backing bytes, swept by
+//! [`emergency_exit`] so a crash zeroizes more than whatever secret
+//! happens to be in scope at the call site that noticed something go
+//! wrong.
+//!
+//! [`crate::secret::SensitiveString`] and [`crate::secret::SensitiveBytes`]
+//! [`register`] their buffer for their whole lifetime — the
+//! [`Registration`] they hold unregisters it again on drop, so the
+//! registry only ever lists memory that's still alive. Only heap-backed
+//! buffers that never move or resize after construction can register
+//! safely this way: moving the *struct* that owns a `String` or `Vec<u8>`
+//! never moves the heap allocation underneath it, but a `[u8; N]` sitting
+//! directly inside a struct (like [`crate::net::chat`]'s per-session
+//! keys) moves its address every time the struct itself moves, so it
+//! can't register here without the caller pinning it down first — this
+//! crate doesn't do that, so those stay out of the registry and rely on
+//! simply being dropped when their containing struct is.
+//!
+//! [`emergency_exit`] is reached three ways: a Rust panic via
+//! [`install_panic_hook`], `SIGINT`/`SIGTERM`/`SIGHUP` via
+//! [`crate::signals::install`]'s watcher thread, and
+//! [`crate::killswitch::trigger`]'s typed-phrase kill switch. The first
+//! two are installed once from [`crate::run_cli`] and stay armed for the
+//! process's whole lifetime, so a short-lived command (`orc resolve`,
+//! say) and a long-running one (`orc browse`, `orc chat`) get the same
+//! guarantee; the kill switch is fired by the commands that offer it
+//! (`orc panic`, and a typed phrase in `orc browse`, `orc repl` and
+//! `orc session`).
+//! There's no async runtime anywhere in this crate, so none of this
+//! needs to worry about cleanup racing a still-running task the way it
+//! would in an async CLI — everything here is plain OS threads, and
+//! [`emergency_exit`] itself is guarded to run its actual cleanup only
+//! once even if two of those three triggers fire at nearly the same
+//! moment.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+struct Entry {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// Safety: `ptr` is only ever dereferenced while holding `registry()`'s
+// lock, from whichever thread calls `zeroize_all`; nothing about sending
+// the raw pointer itself between threads is unsound on its own.
+unsafe impl Send for Entry {}
+
+fn registry() -> &'static Mutex<HashMap<u64, Entry>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<u64, Entry>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A handle returned by [`register`]. Dropping it unregisters the entry
+/// — ordinary cleanup once the buffer it was watching is about to go
+/// away on its own, not something a caller needs to do explicitly ahead
+/// of that.
+pub struct Registration(u64);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut registry) = registry().lock() {
+            registry.remove(&self.0);
+        }
+    }
+}
+
+/// Registers `len` bytes starting at `ptr` so [`zeroize_all`] overwrites
+/// them with zero if they're still registered when that runs.
+///
+/// # Safety
+/// `ptr` must be valid and writable for `len` bytes, and must stay that
+/// way — not reallocated, not freed, not moved — for as long as the
+/// returned [`Registration`] is alive. A `String` or `Vec<u8>`'s heap
+/// buffer qualifies as long as nothing grows it past its original
+/// capacity after this call (true of [`crate::secret::SensitiveString`]
+/// and [`crate::secret::SensitiveBytes`], whose only post-construction
+/// mutation shrinks their logical length rather than reallocating); a
+/// buffer embedded directly in a struct that might later move does not.
+pub unsafe fn register(ptr: *mut u8, len: usize) -> Registration {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut registry) = registry().lock() {
+        registry.insert(id, Entry { ptr, len });
+    }
+    Registration(id)
+}
+
+/// Overwrites every still-registered buffer with zero in place,
+/// best-effort — same caveats as [`crate::secret::SensitiveString::wipe`]:
+/// this doesn't defeat a copy already made elsewhere, it just means
+/// whatever's still reachable through the registry when this runs
+/// doesn't survive it. Run by [`emergency_exit`]; exposed on its own for
+/// a caller like [`crate::killswitch::trigger`] that wants the same
+/// sweep without also exiting the process itself.
+pub fn zeroize_all() {
+    let Ok(registry) = registry().lock() else { return };
+    for entry in registry.values() {
+        for i in 0..entry.len {
+            // Safety: every registered entry is guaranteed live for as
+            // long as it stays in the map — see `register`'s contract.
+            unsafe { std::ptr::write_volatile(entry.ptr.add(i), 0) };
+        }
+    }
+}
+
+static EXIT_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Zeroizes every still-registered secret and exits immediately with
+/// status 137 — the same "treat this like `kill -9`" exit code
+/// [`crate::killswitch::trigger`] uses. Never returns.
+///
+/// This is the one coordinator every shutdown path in the crate funnels
+/// through — [`install_panic_hook`], [`crate::signals::install`]'s
+/// watcher thread, and [`crate::killswitch::trigger`] all end up here.
+/// Once-only, guarded by [`EXIT_STARTED`]: if a signal arrives on the
+/// watcher thread at the same moment a panic is unwinding on another
+/// one, the second caller parks instead of racing the first to
+/// [`zeroize_all`] and `exit` concurrently.
+pub fn emergency_exit() -> ! {
+    if EXIT_STARTED.swap(true, Ordering::SeqCst) {
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+    zeroize_all();
+    std::process::exit(137);
+}
+
+/// Installs a panic hook that runs the default one (so the panic message
+/// still prints) and then [`emergency_exit`]s, so a panic anywhere in
+/// `orc` zeroizes every live secret before the process goes away instead
+/// of leaving whatever it was holding to whatever comes next (an
+/// unwinding caller that does nothing special, a core dump, ...).
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        emergency_exit();
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeroize_all_overwrites_a_registered_buffer() {
+        let mut buf = vec![0xABu8; 4];
+        let registration = unsafe { register(buf.as_mut_ptr(), buf.len()) };
+        zeroize_all();
+        drop(registration);
+        assert_eq!(buf, vec![0u8; 4]);
+    }
+
+    #[test]
+    fn dropping_a_registration_stops_it_from_being_zeroized() {
+        let mut buf = vec![0xABu8; 4];
+        let registration = unsafe { register(buf.as_mut_ptr(), buf.len()) };
+        drop(registration);
+        zeroize_all();
+        assert_eq!(buf, vec![0xABu8; 4]);
+    }
+
+    #[test]
+    fn zeroize_all_leaves_unrelated_memory_alone() {
+        let mut watched = vec![0xABu8; 2];
+        let unwatched = vec![0xCDu8; 2];
+        let registration = unsafe { register(watched.as_mut_ptr(), watched.len()) };
+        zeroize_all();
+        drop(registration);
+        assert_eq!(watched, vec![0u8; 2]);
+        assert_eq!(unwatched, vec![0xCDu8; 2]);
+    }
+}